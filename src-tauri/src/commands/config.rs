@@ -1,16 +1,17 @@
 use crate::models::{
-    AIConfigOverview, ChannelConfig, ConfiguredModel, ConfiguredProvider,
-    MCPConfig, ModelConfig, OfficialProvider, SuggestedModel,
+    AIConfigOverview, ApiTypeInfo, AvailableModel, ChannelConfig, ChannelField, ConfigSetResult, ConfiguredModel, ConfiguredProvider,
+    ConfigPaths, FieldSource, MCPConfig, McpSource, OpenClawConfig, PluginInfo, PluginInstallResult, McpTestResult, McpTransport, McpTool, McpToolList, ModelConfig, OfficialProvider, ProviderDelta, ProviderUsage,
+    ProviderHealth, ProviderModelCount, SuggestedModelStatus, ProviderUsageReport, PruneReport, ReconcileReport, ValidationError,
 };
-use crate::utils::{file, platform, shell, log_sanitizer};
+use crate::utils::{file, platform, secrets, shell, log_sanitizer};
 use log::{debug, error, info, warn};
 use serde_json::{json, Value};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tauri::command;
 
-/// Load openclaw.json configuration
-fn load_openclaw_config() -> Result<Value, String> {
+/// Read openclaw.json as stored, without migrating or writing anything
+pub(crate) fn read_openclaw_config() -> Result<Value, String> {
     let config_path = platform::get_config_file_path();
 
     if !file::file_exists(&config_path) {
@@ -19,23 +20,464 @@ fn load_openclaw_config() -> Result<Value, String> {
 
     let content =
         file::read_file(&config_path).map_err(|e| format!("Failed to read configuration file: {}", e))?;
+    parse_config_content(&content)
+}
 
-    // Strip UTF-8 BOM if present (Windows editors sometimes add this)
-    let content = content.strip_prefix('\u{FEFF}').unwrap_or(&content);
+/// Load openclaw.json configuration, migrated in memory. Nothing is written here: the
+/// migrated config reaches disk with the next locked save (or migrate_stored_config at startup).
+pub(crate) fn load_openclaw_config() -> Result<Value, String> {
+    let mut config = read_openclaw_config()?;
+
+    if let Some((from, to)) = migrate_config(&mut config) {
+        debug!("[Config] Migrated configuration from schema v{} to v{} in memory", from, to);
+    }
+
+    // Shape problems make sections silently look empty, so at least point at them in the log
+    for err in validate_config_value(&config) {
+        warn!("[Config] Schema violation at {}: {}", err.path, err.message);
+    }
+    Ok(config)
+}
+
+/// Parse openclaw.json content, ignoring a UTF-8 BOM (Windows editors sometimes add one).
+/// Hand-edited files may contain // and /* */ comments and trailing commas; these are accepted
+/// but not preserved, so the next save drops them (see config_has_comments).
+pub(crate) fn parse_config_content(content: &str) -> Result<Value, String> {
+    let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
+    serde_json::from_str(&file::strip_json_comments(content))
+        .map_err(|e| format!("Failed to parse configuration file: {}", e))
+}
+
+/// Whether openclaw.json content has comments or trailing commas that a save would drop
+pub(crate) fn content_has_comments(content: &str) -> bool {
+    file::strip_json_comments(content) != content
+}
+
+/// Whether openclaw.json has comments (or trailing commas) that saving will strip.
+/// The first save of such a file backs it up before rewriting it.
+#[command]
+pub async fn config_has_comments() -> Result<bool, String> {
+    let content = file::read_file(&platform::get_config_file_path()).unwrap_or_default();
+    Ok(content_has_comments(&content))
+}
+
+/// Serialize the config in the layout of the file it replaces (BOM, CRLF line endings,
+/// trailing newline), so saving an unchanged config reproduces the file byte for byte.
+/// Key order is kept as loaded (serde_json preserve_order).
+pub(crate) fn render_config_content(config: &Value, previous: Option<&str>) -> Result<String, String> {
+    let mut content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize configuration: {}", e))?;
+    let Some(previous) = previous else {
+        return Ok(content);
+    };
+
+    if previous.ends_with('\n') {
+        content.push('\n');
+    }
+    if previous.contains("\r\n") {
+        content = content.replace('\n', "\r\n");
+    }
+    if previous.starts_with('\u{FEFF}') {
+        content.insert(0, '\u{FEFF}');
+    }
+    Ok(content)
+}
+
+/// A config migration step. Must be idempotent: running it on an already migrated config is a no-op.
+type ConfigMigration = fn(&mut Value);
+
+/// Ordered migrations; entry `i` upgrades meta.schemaVersion `i` to `i + 1`
+const CONFIG_MIGRATIONS: &[ConfigMigration] = &[
+    // v1: meta and meta.gui are objects
+    |config| {
+        repair_meta_structure(config);
+    },
+    // v2: web.braveApiKey moved to web.providers.brave
+    |config| {
+        migrate_legacy_web_config(config);
+    },
+];
+
+/// Config schema version written by this Manager
+pub(crate) const CONFIG_SCHEMA_VERSION: u64 = CONFIG_MIGRATIONS.len() as u64;
+
+/// Apply pending migrations and stamp meta.schemaVersion.
+/// Returns (from, to) when anything ran; configs from a newer Manager are left untouched.
+pub(crate) fn migrate_config(config: &mut Value) -> Option<(u64, u64)> {
+    if !config.is_object() {
+        return None;
+    }
+    let from = config.pointer("/meta/schemaVersion").and_then(|v| v.as_u64()).unwrap_or(0);
+    if from >= CONFIG_SCHEMA_VERSION {
+        if from > CONFIG_SCHEMA_VERSION {
+            warn!("[Config] Configuration schema v{} is newer than this Manager (v{})", from, CONFIG_SCHEMA_VERSION);
+        }
+        return None;
+    }
+
+    for migration in &CONFIG_MIGRATIONS[from as usize..] {
+        migration(config);
+    }
+    repair_meta_structure(config);
+    config["meta"]["schemaVersion"] = json!(CONFIG_SCHEMA_VERSION);
+    Some((from, CONFIG_SCHEMA_VERSION))
+}
+
+/// Migrate openclaw.json on disk under the config lock, keeping a backup of the original.
+/// Runs once at startup so getters never have to write.
+pub(crate) async fn migrate_stored_config() -> Result<(), String> {
+    let _config_lock = lock_config().await;
+    if !file::file_exists(&platform::get_config_file_path()) {
+        return Ok(());
+    }
+
+    let mut config = read_openclaw_config()?;
+    if let Some((from, to)) = migrate_config(&mut config) {
+        let backup = backup_openclaw_config()?;
+        save_openclaw_config(&config)?;
+        info!("[Config] Migrated configuration from schema v{} to v{} (original kept at {})", from, to, backup);
+    }
+    Ok(())
+}
+
+/// JSON Schema describing openclaw.json
+const OPENCLAW_SCHEMA: &str = include_str!("../../schemas/openclaw.schema.json");
+
+static CONFIG_VALIDATOR: once_cell::sync::Lazy<Option<jsonschema::Validator>> = once_cell::sync::Lazy::new(|| {
+    let schema: Value = match serde_json::from_str(OPENCLAW_SCHEMA) {
+        Ok(schema) => schema,
+        Err(e) => {
+            error!("[Config] Bundled config schema is not valid JSON: {}", e);
+            return None;
+        }
+    };
+    jsonschema::validator_for(&schema)
+        .map_err(|e| error!("[Config] Bundled config schema failed to compile: {}", e))
+        .ok()
+});
+
+/// Validate a config document against the bundled schema
+pub(crate) fn validate_config_value(config: &Value) -> Vec<ValidationError> {
+    let Some(validator) = CONFIG_VALIDATOR.as_ref() else {
+        return Vec::new();
+    };
 
-    serde_json::from_str(content).map_err(|e| format!("Failed to parse configuration file: {}", e))
+    validator.iter_errors(config)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            ValidationError {
+                path: if path.is_empty() { "/".to_string() } else { path },
+                message: e.to_string(),
+            }
+        })
+        .collect()
 }
 
 /// Save openclaw.json configuration
-fn save_openclaw_config(config: &Value) -> Result<(), String> {
+pub(crate) fn save_openclaw_config(config: &Value) -> Result<(), String> {
     let config_path = platform::get_config_file_path();
 
-    let content =
-        serde_json::to_string_pretty(config).map_err(|e| format!("Failed to serialize configuration: {}", e))?;
+    // In keyring mode, plaintext secrets are moved to the OS keyring before anything hits disk
+    let mut config = config.clone();
+    if keyring_mode_enabled(&config) {
+        let moved = secrets::externalize_secrets(&mut config, &mut secrets::store_secret)?;
+        if moved > 0 {
+            info!("[Secrets] Moved {} secrets to the OS keyring", moved);
+        }
+    }
+
+    let previous = file::read_file(&config_path).ok();
+    let content = render_config_content(&config, previous.as_deref())?;
+    if previous.as_deref() == Some(content.as_str()) {
+        debug!("[Config] No changes, skipping write");
+        return Ok(());
+    }
+
+    // Comments can't be written back, so keep the annotated original next to the new file
+    if previous.as_deref().is_some_and(content_has_comments) {
+        let backup = backup_openclaw_config()?;
+        warn!("[Config] openclaw.json comments are not preserved on save; original kept at {}", backup);
+    }
 
     file::write_file(&config_path, &content).map_err(|e| format!("Failed to write configuration file: {}", e))
 }
 
+/// Resolve a project directory to the directory holding its openclaw.json: the directory
+/// itself, or its .openclaw subdirectory when only that has one
+pub(crate) fn resolve_config_scope(path: &str) -> Result<String, String> {
+    let dir = std::path::Path::new(path.trim());
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    let nested = dir.join(".openclaw");
+    let scope = if !dir.join("openclaw.json").is_file() && nested.join("openclaw.json").is_file() {
+        nested
+    } else {
+        dir.to_path_buf()
+    };
+    Ok(scope.display().to_string().trim_end_matches(['/', '\\']).to_string())
+}
+
+/// Operate on a project-local openclaw.json (`path`/openclaw.json, or `path`/.openclaw/openclaw.json)
+/// instead of the global one; None switches back. Only openclaw.json moves: the env file, MCP
+/// config and Manager settings stay global. The scope lasts until the Manager restarts.
+/// openclaw CLI commands and gateways started by the Manager get OPENCLAW_CONFIG_PATH pointing at
+/// the scoped file; a gateway that is already running (or runs as a system service) keeps
+/// its own config until it is restarted from the Manager.
+#[command]
+pub async fn set_active_config_scope(path: Option<String>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    let scope = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => Some(resolve_config_scope(path)?),
+        None => None,
+    };
+    platform::set_config_scope(scope.clone());
+    let config_path = platform::get_config_file_path();
+    match scope {
+        Some(dir) => info!("[Config Scope] Using project config in {}", dir),
+        None => info!("[Config Scope] Using global config"),
+    }
+    Ok(config_path)
+}
+
+/// Project directory whose openclaw.json is active (None = global config)
+#[command]
+pub async fn get_active_config_scope() -> Result<Option<String>, String> {
+    Ok(platform::get_config_scope())
+}
+
+/// Load openclaw.json as the typed OpenClawConfig. Fails (naming the problem) when a typed
+/// section has the wrong shape, instead of silently reading it as missing.
+pub(crate) fn load_typed_config() -> Result<OpenClawConfig, String> {
+    typed_config_from(load_openclaw_config()?)
+}
+
+/// Convert a raw config into OpenClawConfig
+pub(crate) fn typed_config_from(config: Value) -> Result<OpenClawConfig, String> {
+    serde_json::from_value(config).map_err(|e| format!("openclaw.json has an unexpected shape: {}", e))
+}
+
+/// Primary model of an agents.*.model value, which is either "provider/model" or { primary, fallbacks }
+pub(crate) fn model_primary(model: Option<&Value>) -> Option<&str> {
+    match model? {
+        Value::String(primary) => Some(primary),
+        model => model.get("primary")?.as_str(),
+    }
+}
+
+/// Clear the primary model of an agents.*.model value, in either form
+pub(crate) fn clear_model_primary(model: &mut Value) {
+    if model.is_object() {
+        model["primary"] = json!(null);
+    } else {
+        *model = json!({ "primary": null });
+    }
+}
+
+/// Save a typed config, keeping the key order of the file it replaces
+pub(crate) fn save_typed_config(config: &OpenClawConfig) -> Result<(), String> {
+    let mut value = serde_json::to_value(config).map_err(|e| format!("Failed to serialize configuration: {}", e))?;
+    align_key_order(&mut value, &read_openclaw_config()?);
+    save_openclaw_config(&value)
+}
+
+/// Reorder object keys in `value` to follow `reference` (new keys go last), so re-serializing
+/// a typed config doesn't shuffle the file. Explicit nulls in `reference` that the typed
+/// config skipped as None are kept.
+pub(crate) fn align_key_order(value: &mut Value, reference: &Value) {
+    match (value, reference) {
+        (Value::Object(map), Value::Object(reference)) => {
+            let mut rest = std::mem::take(map);
+            for (key, reference_value) in reference {
+                match rest.shift_remove(key) {
+                    Some(mut v) => {
+                        align_key_order(&mut v, reference_value);
+                        map.insert(key.clone(), v);
+                    }
+                    None if reference_value.is_null() => {
+                        map.insert(key.clone(), Value::Null);
+                    }
+                    None => {}
+                }
+            }
+            map.extend(rest);
+        }
+        (Value::Array(items), Value::Array(reference)) => {
+            for (item, reference_item) in items.iter_mut().zip(reference) {
+                align_key_order(item, reference_item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Serializes read-modify-write cycles on the config files (openclaw.json, the MCP
+/// config and the env file), so back-to-back commands can't overwrite each other's changes.
+/// A static rather than managed Tauri state: writers without an AppHandle (the startup
+/// migration, gateway auth before a service start, tests) must take the same lock.
+static CONFIG_WRITE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Take the config write lock. Commands that change config hold it from load to save;
+/// it is not reentrant, so a guarded command must not call another guarded command.
+pub(crate) async fn lock_config() -> tokio::sync::MutexGuard<'static, ()> {
+    CONFIG_WRITE_LOCK.lock().await
+}
+
+/// Copy openclaw.json to openclaw.json.bak-<timestamp> and return the backup path
+fn backup_openclaw_config() -> Result<String, String> {
+    let config_path = platform::get_config_file_path();
+    let backup_path = format!("{}.bak-{}", config_path, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let original = file::read_file(&config_path)
+        .map_err(|e| format!("Failed to read configuration for backup: {}", e))?;
+    file::write_file(&backup_path, &original)
+        .map_err(|e| format!("Failed to write backup {}: {}", backup_path, e))?;
+    Ok(backup_path)
+}
+
+/// Make sure `meta` and `meta.gui` are objects. Values of the wrong type are kept
+/// under meta.invalidMeta / meta.invalidGui so nothing is silently lost.
+/// Returns true if the config was changed.
+pub(crate) fn repair_meta_structure(config: &mut Value) -> bool {
+    let mut changed = false;
+
+    match config.get("meta") {
+        Some(Value::Object(_)) => {}
+        Some(Value::Null) | None => {
+            config["meta"] = json!({});
+            changed = true;
+        }
+        Some(other) => {
+            warn!("[Config] meta is not an object, backing it up to meta.invalidMeta");
+            config["meta"] = json!({ "invalidMeta": other.clone() });
+            changed = true;
+        }
+    }
+
+    match config["meta"].get("gui") {
+        Some(Value::Object(_)) => {}
+        Some(Value::Null) | None => {
+            config["meta"]["gui"] = json!({});
+            changed = true;
+        }
+        Some(other) => {
+            warn!("[Config] meta.gui is not an object, backing it up to meta.invalidGui");
+            config["meta"]["invalidGui"] = other.clone();
+            config["meta"]["gui"] = json!({});
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// Verify and repair the meta section structure
+#[command]
+pub async fn ensure_meta_structure() -> Result<(), String> {
+    let _config_lock = lock_config().await;
+    let mut config = load_openclaw_config()?;
+    if repair_meta_structure(&mut config) {
+        info!("[Config] Repaired meta section structure");
+        save_openclaw_config(&config)?;
+    }
+    Ok(())
+}
+
+/// Timezone written into a freshly initialized config
+const DEFAULT_TIMEZONE: &str = "UTC";
+
+/// Well-formed starting config for a machine without openclaw.json.
+/// The timezone lives in manager.timezone, where the workspace settings read it.
+pub(crate) fn default_config_skeleton() -> Value {
+    json!({
+        "meta": { "schemaVersion": CONFIG_SCHEMA_VERSION, "gui": {} },
+        "models": { "providers": {} },
+        "agents": { "defaults": { "models": {} } },
+        "gateway": { "mode": "local" },
+        "manager": { "timezone": DEFAULT_TIMEZONE }
+    })
+}
+
+/// Write the default skeleton when openclaw.json does not exist yet (no-op otherwise)
+#[command]
+pub async fn initialize_config() -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    let path = platform::get_config_file_path();
+    if file::file_exists(&path) {
+        info!("[Config] Configuration already exists, skipping initialization: {}", path);
+        return Ok(format!("Configuration already exists: {}", path));
+    }
+
+    info!("[Config] Writing default configuration to {}", path);
+    save_openclaw_config(&default_config_skeleton())?;
+    Ok(format!("Configuration initialized: {}", path))
+}
+
+/// Whether secrets should be stored in the OS keyring (meta.gui.secretStorage = "keyring")
+pub(crate) fn keyring_mode_enabled(config: &Value) -> bool {
+    config.pointer("/meta/gui/secretStorage").and_then(|v| v.as_str()) == Some("keyring")
+}
+
+/// Get the secret storage mode ("keyring" or "plaintext")
+#[command]
+pub async fn get_secret_storage_mode() -> Result<String, String> {
+    let config = load_openclaw_config()?;
+    Ok(if keyring_mode_enabled(&config) { "keyring" } else { "plaintext" }.to_string())
+}
+
+/// Switch secret storage between plaintext config and the OS keyring
+#[command]
+pub async fn set_secret_storage_mode(mode: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Secrets] Switching secret storage to: {}", mode);
+    let mut config = load_openclaw_config()?;
+    repair_meta_structure(&mut config);
+
+    match mode.as_str() {
+        "keyring" => {
+            config["meta"]["gui"]["secretStorage"] = json!("keyring");
+            // save_openclaw_config moves the secrets into the keyring
+            save_openclaw_config(&config)?;
+
+            // Channel tokens and API keys in the env file move too
+            let env_path = platform::get_env_file_path();
+            if let Ok(content) = file::read_file(&env_path) {
+                let (updated, moved) = secrets::externalize_env_secrets(&content, &mut secrets::store_secret)?;
+                if moved > 0 {
+                    file::write_file(&env_path, &updated)
+                        .map_err(|e| format!("Failed to update env file: {}", e))?;
+                    info!("[Secrets] Moved {} env file secrets to the OS keyring", moved);
+                }
+            }
+            Ok("Secrets are now stored in the OS keyring".to_string())
+        }
+        "plaintext" => {
+            let mut restored = secrets::internalize_secrets(&mut config, &mut secrets::load_secret)?;
+            if let Some(gui) = config.pointer_mut("/meta/gui").and_then(|v| v.as_object_mut()) {
+                gui.shift_remove("secretStorage");
+            }
+            save_openclaw_config(&config)?;
+
+            let env_path = platform::get_env_file_path();
+            if let Ok(content) = file::read_file(&env_path) {
+                let (updated, env_restored) = secrets::internalize_env_secrets(&content, &mut secrets::load_secret)?;
+                if !env_restored.is_empty() {
+                    file::write_file(&env_path, &updated)
+                        .map_err(|e| format!("Failed to update env file: {}", e))?;
+                    restored.extend(env_restored);
+                }
+            }
+            // Only drop keyring entries once the plaintext copy is safely on disk
+            for var in &restored {
+                if let Err(e) = secrets::delete_secret(var) {
+                    warn!("[Secrets] {}", e);
+                }
+            }
+            Ok(format!("Restored {} secrets to the configuration and env files", restored.len()))
+        }
+        other => Err(format!("Unknown secret storage mode '{}'. Expected keyring or plaintext", other)),
+    }
+}
+
 /// Load manager.json configuration (manager-specific settings)
 fn load_manager_config() -> Result<Value, String> {
     let config_path = platform::get_manager_config_file_path();
@@ -75,13 +517,41 @@ pub async fn get_config() -> Result<Value, String> {
     result
 }
 
+/// Get the configuration exactly as stored on disk. Unlike get_config this never
+/// migrates, creates tokens or writes anything.
+#[command]
+pub async fn get_config_readonly() -> Result<Value, String> {
+    info!("[Get Config] Reading openclaw.json (read-only)...");
+    read_openclaw_config()
+}
+
+/// Mask a secret for display, keeping only the first and last 4 characters
+pub(crate) fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() > 8 {
+        let head: String = chars[..4].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}...{}", head, tail)
+    } else {
+        "****".to_string()
+    }
+}
+
+/// Copy of a config with every secret-looking value masked, for logging
+pub(crate) fn mask_secrets_for_log(config: &Value) -> Value {
+    let mut masked = config.clone();
+    secrets::replace_secrets(&mut masked, &mut |s| mask_secret(s));
+    masked
+}
+
 /// Save configuration
 #[command]
 pub async fn save_config(config: Value) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Save Config] Saving openclaw.json configuration...");
     debug!(
         "[Save Config] Configuration content: {}",
-        log_sanitizer::sanitize(&serde_json::to_string_pretty(&config).unwrap_or_default())
+        log_sanitizer::sanitize(&serde_json::to_string_pretty(&mask_secrets_for_log(&config)).unwrap_or_default())
     );
     match save_openclaw_config(&config) {
         Ok(_) => {
@@ -95,6 +565,75 @@ pub async fn save_config(config: Value) -> Result<String, String> {
     }
 }
 
+/// Reject a hand-edited config that would break the Manager or openclaw: it must be an object,
+/// match the typed sections and carry no redacted placeholders.
+/// Schema errors only block the save when the edit introduced them; mismatches already present
+/// in `current` (the stored config, possibly written by the Manager itself) come back as warnings.
+pub(crate) fn check_config_for_save(config: &Value, current: Option<&Value>) -> Result<Vec<String>, String> {
+    if !config.is_object() {
+        return Err("Configuration must be a JSON object".to_string());
+    }
+
+    // Schema errors come first because they name the offending path
+    let existing: HashSet<(String, String)> = current
+        .map(validate_config_value)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|e| (e.path, e.message))
+        .collect();
+    let (warnings, errors): (Vec<_>, Vec<_>) = validate_config_value(config)
+        .into_iter()
+        .map(|e| (e.path, e.message))
+        .partition(|e| existing.contains(e));
+    if !errors.is_empty() {
+        let details: Vec<String> = errors.iter().map(|(path, message)| format!("{}: {}", path, message)).collect();
+        return Err(format!("Configuration does not match the schema: {}", details.join("; ")));
+    }
+    typed_config_from(config.clone())?;
+
+    fn find_placeholder(value: &Value, path: &str) -> Option<String> {
+        match value {
+            Value::String(s) if s == REDACTED_PLACEHOLDER => Some(if path.is_empty() { "/".to_string() } else { path.to_string() }),
+            // JSON pointer escaping (RFC 6901): "~" becomes "~0" and "/" becomes "~1"
+            Value::Object(map) => map.iter().find_map(|(k, v)| {
+                find_placeholder(v, &format!("{}/{}", path, k.replace('~', "~0").replace('/', "~1")))
+            }),
+            Value::Array(arr) => arr.iter().enumerate().find_map(|(i, v)| find_placeholder(v, &format!("{}/{}", path, i))),
+            _ => None,
+        }
+    }
+    if let Some(path) = find_placeholder(config, "") {
+        return Err(format!("{} still contains the redacted placeholder {}", path, REDACTED_PLACEHOLDER));
+    }
+    Ok(warnings.into_iter().map(|(path, message)| format!("{}: {}", path, message)).collect())
+}
+
+/// Save a config from the raw JSON editor, refusing anything check_config_for_save rejects
+#[command]
+pub async fn save_config_validated(config: Value) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Save Config] Validating raw configuration before saving...");
+    let current = read_openclaw_config().ok();
+    let warnings = match check_config_for_save(&config, current.as_ref()) {
+        Ok(warnings) => warnings,
+        Err(e) => {
+            warn!("[Save Config] Rejected raw configuration: {}", e);
+            return Err(e);
+        }
+    };
+    save_openclaw_config(&config)?;
+    info!("[Save Config] Raw configuration saved");
+    if warnings.is_empty() {
+        return Ok("Configuration saved".to_string());
+    }
+    warn!("[Save Config] Saved with existing schema mismatches: {}", warnings.join("; "));
+    Ok(format!(
+        "Configuration saved with {} existing schema warning(s): {}",
+        warnings.len(),
+        warnings.join("; ")
+    ))
+}
+
 /// Get environment variable value
 #[command]
 pub async fn get_env_value(key: String) -> Result<Option<String>, String> {
@@ -115,10 +654,21 @@ pub async fn get_env_value(key: String) -> Result<Option<String>, String> {
 /// Save environment variable value
 #[command]
 pub async fn save_env_value(key: String, value: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Save Env] Saving environment variable: {}", key);
     let env_path = platform::get_env_file_path();
     debug!("[Save Env] Environment file path: {}", env_path);
 
+    // In keyring mode a secret goes to the keyring and the env file only keeps a reference
+    let keyring_mode = read_openclaw_config().is_ok_and(|config| keyring_mode_enabled(&config));
+    let value = if keyring_mode && secrets::is_secret_key(&key) && file::is_valid_env_key(&key) && !value.trim().is_empty() {
+        let var = secrets::env_secret_var(&key);
+        secrets::store_secret(&var, value.trim())?;
+        format!("${{{}}}", var)
+    } else {
+        value
+    };
+
     match file::set_env_value(&env_path, &key, &value) {
         Ok(_) => {
             info!("[Save Env] Environment variable {} saved successfully", key);
@@ -131,6 +681,122 @@ pub async fn save_env_value(key: String, value: String) -> Result<String, String
     }
 }
 
+/// Key stored in the env file, with its value masked
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnvEntry {
+    pub key: String,
+    pub masked_value: String,
+}
+
+/// Entries of an env file in file order; a key defined twice keeps its last value (as `source` would)
+pub(crate) fn env_entries(content: &str) -> Vec<EnvEntry> {
+    let mut entries: Vec<EnvEntry> = Vec::new();
+    for (key, value) in file::parse_env_exports(content) {
+        let masked_value = mask_secret(&value);
+        match entries.iter_mut().find(|e| e.key == key) {
+            Some(entry) => entry.masked_value = masked_value,
+            None => entries.push(EnvEntry { key, masked_value }),
+        }
+    }
+    entries
+}
+
+/// List the keys stored in the env file (values masked)
+#[command]
+pub async fn list_env_keys() -> Result<Vec<EnvEntry>, String> {
+    let env_path = platform::get_env_file_path();
+    let content = match file::read_file(&env_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read env file: {}", e)),
+    };
+    let entries = env_entries(&content);
+    info!("[List Env] {} keys in {}", entries.len(), env_path);
+    Ok(entries)
+}
+
+/// Remove an environment variable from the env file
+#[command]
+pub async fn delete_env_value(key: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    let key = key.trim();
+    info!("[Delete Env] Removing environment variable: {}", key);
+    let env_path = platform::get_env_file_path();
+    if key.is_empty() || file::read_env_value(&env_path, key).is_none() {
+        return Err(format!("{} is not set in the env file", key));
+    }
+    file::remove_env_value(&env_path, key).map_err(|e| format!("Failed to remove environment variable: {}", e))?;
+    info!("[Delete Env] Environment variable {} removed", key);
+    Ok(format!("Removed {}", key))
+}
+
+/// Portable env file holding only the `include` keys, in the given order. Values are written
+/// verbatim only when `include_secrets` is set; otherwise each key is exported empty so the
+/// file serves as a template to fill in on the target machine.
+pub(crate) fn export_env_content(env_content: &str, include: &[String], include_secrets: bool) -> Result<String, String> {
+    if include.is_empty() {
+        return Err("Select at least one environment variable to export".to_string());
+    }
+    let stored = file::parse_env_exports(env_content);
+    let mut content = String::from("# Exported by OpenClaw Manager\n");
+    if !include_secrets {
+        content.push_str("# Values were not exported; fill them in before use\n");
+    }
+    let mut missing = Vec::new();
+    for key in include {
+        let key = key.trim();
+        // A key defined twice keeps its last value, as `source` would
+        match stored.iter().rev().find(|(k, _)| k == key) {
+            Some((_, value)) => {
+                content = file::set_env_line(&content, key, if include_secrets { value } else { "" });
+            }
+            None => missing.push(key.to_string()),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!("Not set in the env file: {}", missing.join(", ")));
+    }
+    Ok(content)
+}
+
+/// Write the selected env keys (channel tokens, web API keys, ...) to a .env file for a
+/// headless deployment. Secret values are only included when `include_secrets` is true.
+#[command]
+pub async fn export_env_file(
+    dest_path: String,
+    include: Vec<String>,
+    include_secrets: Option<bool>,
+) -> Result<String, String> {
+    let include_secrets = include_secrets.unwrap_or(false);
+    info!("[Export Env] Exporting {} keys to {} (secrets: {})", include.len(), dest_path, include_secrets);
+    let env_path = platform::get_env_file_path();
+    let env_content = match file::read_file(&env_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read env file: {}", e)),
+    };
+    // Keyring references mean nothing on another machine
+    let env_content = if include_secrets {
+        secrets::internalize_env_secrets(&env_content, &mut secrets::load_secret)?.0
+    } else {
+        env_content
+    };
+    let content = export_env_content(&env_content, &include, include_secrets)?;
+    file::write_file(&dest_path, &content).map_err(|e| format!("Failed to write {}: {}", dest_path, e))?;
+
+    // The export may hold API keys; keep it readable by the owner only
+    #[cfg(unix)]
+    if include_secrets {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = std::fs::set_permissions(&dest_path, std::fs::Permissions::from_mode(0o600)) {
+            warn!("[Export Env] Failed to restrict permissions on {}: {}", dest_path, e);
+        }
+    }
+
+    info!("[Export Env] Wrote {}", dest_path);
+    Ok(format!("Exported {} variables to {}", include.len(), dest_path))
+}
+
 // ============ Gateway Token Commands ============
 
 /// Generate random token
@@ -151,22 +817,30 @@ fn generate_token() -> String {
     )
 }
 
-/// Get or create Gateway Token
+/// The gateway token already in the config, without creating one.
+/// Returns an empty token when gateway auth is disabled.
+pub(crate) fn existing_gateway_token(config: &Value) -> Option<String> {
+    if config.pointer("/gateway/auth/mode").and_then(|v| v.as_str()) == Some("none") {
+        return Some(String::new());
+    }
+    config.pointer("/gateway/auth/token")
+        .and_then(|v| v.as_str())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+}
+
+/// Get or create Gateway Token (empty when gateway auth is disabled)
 #[command]
 pub async fn get_or_create_gateway_token() -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Gateway Token] Getting or creating Gateway Token...");
 
     let mut config = load_openclaw_config()?;
 
     // Check if token already exists
-    if let Some(token) = config
-        .pointer("/gateway/auth/token")
-        .and_then(|v| v.as_str())
-    {
-        if !token.is_empty() {
-            info!("[Gateway Token] Using existing Token");
-            return Ok(token.to_string());
-        }
+    if let Some(token) = existing_gateway_token(&config) {
+        info!("[Gateway Token] Using existing Token");
+        return Ok(token);
     }
 
     // Generate new token
@@ -193,23 +867,192 @@ pub async fn get_or_create_gateway_token() -> Result<String, String> {
     Ok(new_token)
 }
 
-/// Get Dashboard URL (with token)
+/// Give token auth a token and let the Control UI connect with it (skips device pairing
+/// for the local Manager). Configs with gateway auth disabled are left alone.
+/// Returns true if the config was changed.
+pub(crate) fn prepare_gateway_auth(config: &mut Value, generate: impl FnOnce() -> String) -> bool {
+    if config.pointer("/gateway/auth/mode").and_then(|v| v.as_str()) == Some("none") {
+        return false;
+    }
+
+    let mut changed = false;
+    if !config.get("gateway").is_some_and(|v| v.is_object()) {
+        config["gateway"] = json!({});
+        changed = true;
+    }
+    let gateway = &mut config["gateway"];
+    if gateway.pointer("/auth/token").and_then(|v| v.as_str()).is_none_or(|t| t.is_empty()) {
+        if !gateway.get("auth").is_some_and(|v| v.is_object()) {
+            gateway["auth"] = json!({});
+        }
+        gateway["auth"]["token"] = json!(generate());
+        gateway["auth"]["mode"] = json!("token");
+        if gateway.get("mode").is_none() {
+            gateway["mode"] = json!("local");
+        }
+        changed = true;
+    }
+    if gateway.pointer("/controlUi/allowInsecureAuth").and_then(|v| v.as_bool()) != Some(true) {
+        if !gateway.get("controlUi").is_some_and(|v| v.is_object()) {
+            gateway["controlUi"] = json!({});
+        }
+        gateway["controlUi"]["allowInsecureAuth"] = json!(true);
+        changed = true;
+    }
+    changed
+}
+
+/// Prepare gateway auth in openclaw.json before the gateway starts, under the config lock
+pub(crate) async fn ensure_gateway_auth() -> Result<(), String> {
+    let _config_lock = lock_config().await;
+    let mut config = load_openclaw_config()?;
+    if prepare_gateway_auth(&mut config, generate_token) {
+        save_openclaw_config(&config)?;
+        info!("[Gateway Token] Gateway auth prepared in configuration");
+    }
+    Ok(())
+}
+
+/// Gateway auth modes the Manager can switch between
+const GATEWAY_AUTH_MODES: [&str; 2] = ["token", "none"];
+
+/// Switch gateway.auth.mode. "none" clears the token; "token" keeps an existing token
+/// or stores one from `generate`.
+pub(crate) fn set_gateway_auth_in(
+    config: &mut Value,
+    mode: &str,
+    generate: impl FnOnce() -> String,
+) -> Result<(), String> {
+    if !GATEWAY_AUTH_MODES.contains(&mode) {
+        return Err(format!("Unsupported gateway auth mode '{}' (expected token or none)", mode));
+    }
+
+    if !config.get("gateway").is_some_and(|v| v.is_object()) {
+        config["gateway"] = json!({});
+    }
+    if !config["gateway"].get("auth").is_some_and(|v| v.is_object()) {
+        config["gateway"]["auth"] = json!({});
+    }
+    let auth = &mut config["gateway"]["auth"];
+
+    auth["mode"] = json!(mode);
+    if mode == "none" {
+        if let Some(obj) = auth.as_object_mut() {
+            obj.shift_remove("token");
+        }
+    } else if auth.get("token").and_then(|v| v.as_str()).is_none_or(|t| t.is_empty()) {
+        auth["token"] = json!(generate());
+    }
+    Ok(())
+}
+
+/// Set gateway auth mode ("token" or "none") and return the resulting status
+#[command]
+pub async fn set_gateway_auth(mode: String) -> Result<GatewayStatus, String> {
+    let _config_lock = lock_config().await;
+    info!("[Gateway] Setting gateway auth mode: {}", mode);
+    let mut config = load_openclaw_config()?;
+    set_gateway_auth_in(&mut config, mode.trim(), generate_token)?;
+    save_openclaw_config(&config)?;
+    Ok(gateway_status_from(&config))
+}
+
+/// Query string carrying the dashboard token (empty when gateway auth is disabled)
+fn dashboard_token_query(token: &str) -> String {
+    if token.is_empty() {
+        String::new()
+    } else {
+        format!("?token={}", token)
+    }
+}
+
+/// Get Dashboard URL (with token). With create_token = false an existing token is
+/// required and nothing is written to the config.
 #[command]
-pub async fn get_dashboard_url() -> Result<String, String> {
+pub async fn get_dashboard_url(create_token: Option<bool>) -> Result<String, String> {
     info!("[Dashboard URL] Getting Dashboard URL...");
 
-    let token = get_or_create_gateway_token().await?;
-    let url = format!("http://localhost:18789?token={}", token);
+    let token = if create_token.unwrap_or(true) {
+        get_or_create_gateway_token().await?
+    } else {
+        existing_gateway_token(&read_openclaw_config()?)
+            .ok_or("No gateway token exists yet. Open the dashboard once to create one")?
+    };
+    let url = format!("http://localhost:18789{}", dashboard_token_query(&token));
 
     info!("[Dashboard URL] URL generated");
     Ok(url)
 }
 
-/// Repair device token mismatch by deleting stale identity and paired device files.
-/// After calling this, the gateway should be restarted to regenerate fresh device identity.
-#[command]
-pub async fn repair_device_token() -> Result<String, String> {
-    info!("[Device Token Repair] Starting device token repair...");
+/// Gateway bind values that only accept connections from this machine
+const LOOPBACK_BINDS: [&str; 4] = ["loopback", "localhost", "127.0.0.1", "::1"];
+
+/// Interface name prefixes of virtual/container networks that other devices can't reach
+const VIRTUAL_INTERFACE_PREFIXES: [&str; 9] = ["docker", "veth", "br-", "virbr", "vmnet", "vboxnet", "utun", "tun", "tap"];
+
+/// Pick the primary LAN IPv4 address from enumerated interfaces: private ranges first,
+/// then any other routable address. Loopback, link-local and virtual interfaces are skipped.
+pub(crate) fn pick_lan_ip(interfaces: &[(String, std::net::IpAddr)]) -> Option<std::net::Ipv4Addr> {
+    let candidates: Vec<std::net::Ipv4Addr> = interfaces.iter()
+        .filter(|(name, _)| !VIRTUAL_INTERFACE_PREFIXES.iter().any(|p| name.to_lowercase().starts_with(p)))
+        .filter_map(|(_, ip)| match ip {
+            std::net::IpAddr::V4(v4) => Some(*v4),
+            std::net::IpAddr::V6(_) => None,
+        })
+        .filter(|ip| !ip.is_loopback() && !ip.is_link_local() && !ip.is_unspecified())
+        .collect();
+
+    candidates.iter().find(|ip| ip.is_private()).or(candidates.first()).copied()
+}
+
+/// Build the tokenized dashboard URL for a LAN address. Fails if the gateway only listens on localhost.
+pub(crate) fn build_lan_dashboard_url(
+    config: &Value,
+    interfaces: &[(String, std::net::IpAddr)],
+    token: &str,
+) -> Result<String, String> {
+    let bind = config.pointer("/gateway/bind")
+        .and_then(|v| v.as_str())
+        .unwrap_or("loopback")
+        .trim()
+        .to_lowercase();
+    if LOOPBACK_BINDS.contains(&bind.as_str()) {
+        return Err("The gateway only listens on localhost. Set gateway.bind to \"lan\" to reach the dashboard from other devices".to_string());
+    }
+
+    let port = config.pointer("/gateway/port")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(18789);
+
+    // A gateway bound to one specific address is only reachable there
+    let host = match bind.parse::<std::net::Ipv4Addr>() {
+        Ok(ip) if !ip.is_unspecified() => ip,
+        _ => pick_lan_ip(interfaces).ok_or("No LAN network address found on this machine")?,
+    };
+
+    Ok(format!("http://{}:{}{}", host, port, dashboard_token_query(token)))
+}
+
+/// Get Dashboard URL using this machine's LAN address (for access from other devices)
+#[command]
+pub async fn get_lan_dashboard_url() -> Result<String, String> {
+    info!("[Dashboard URL] Getting LAN Dashboard URL...");
+
+    let config = load_openclaw_config()?;
+    let interfaces = local_ip_address::list_afinet_netifas()
+        .map_err(|e| format!("Failed to list network interfaces: {}", e))?;
+    let token = get_or_create_gateway_token().await?;
+    let url = build_lan_dashboard_url(&config, &interfaces, &token)?;
+
+    info!("[Dashboard URL] LAN URL generated");
+    Ok(url)
+}
+
+/// Repair device token mismatch by deleting stale identity and paired device files.
+/// After calling this, the gateway should be restarted to regenerate fresh device identity.
+#[command]
+pub async fn repair_device_token() -> Result<String, String> {
+    info!("[Device Token Repair] Starting device token repair...");
 
     let config_dir = platform::get_config_dir();
     let identity_file = format!(
@@ -286,270 +1129,101 @@ pub async fn repair_device_token() -> Result<String, String> {
 
 // ============ AI Configuration Commands ============
 
-/// Get official Provider list (preset templates)
+/// API types the Manager knows how to configure, with their capabilities
+pub(crate) fn supported_api_types() -> Vec<ApiTypeInfo> {
+    vec![
+        ApiTypeInfo {
+            id: "anthropic-messages".to_string(),
+            name: "Anthropic Compatible".to_string(),
+            auth_style: "header".to_string(),
+            auth_param: Some("x-api-key".to_string()),
+            list_models_endpoint: Some("/v1/models".to_string()),
+            supports_fetch: true,
+            supports_test: true,
+        },
+        ApiTypeInfo {
+            id: "openai-completions".to_string(),
+            name: "OpenAI Compatible".to_string(),
+            auth_style: "bearer".to_string(),
+            auth_param: None,
+            list_models_endpoint: Some("/models".to_string()),
+            supports_fetch: true,
+            supports_test: true,
+        },
+    ]
+}
+
+/// Reject an api type the Manager doesn't know (a typo would give a model that never routes)
+pub(crate) fn validate_api_type(api_type: &str) -> Result<(), String> {
+    let supported: Vec<String> = supported_api_types().into_iter().map(|t| t.id).collect();
+    if supported.iter().any(|id| id == api_type) {
+        Ok(())
+    } else {
+        Err(format!("Unknown API type '{}'. Valid values: {}", api_type, supported.join(", ")))
+    }
+}
+
+/// Get supported API types (single source of truth for the provider form)
+#[command]
+pub async fn get_supported_api_types() -> Result<Vec<ApiTypeInfo>, String> {
+    Ok(supported_api_types())
+}
+
+/// Built-in provider presets
+pub(crate) const BUILTIN_OFFICIAL_PROVIDERS: &str = include_str!("../../presets/official_providers.json");
+
+/// Loaded provider presets (built-ins merged with the user's providers.json)
+static OFFICIAL_PROVIDERS: once_cell::sync::Lazy<std::sync::RwLock<Option<Vec<OfficialProvider>>>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+/// Merge user presets into the built-ins: a user preset with a built-in's ID replaces it
+/// in place, any other is appended
+pub(crate) fn merge_provider_presets(mut presets: Vec<OfficialProvider>, user: Vec<OfficialProvider>) -> Vec<OfficialProvider> {
+    for preset in user {
+        match presets.iter_mut().find(|p| p.id == preset.id) {
+            Some(existing) => *existing = preset,
+            None => presets.push(preset),
+        }
+    }
+    presets
+}
+
+/// Read the built-in presets and merge the user's providers.json over them
+fn load_official_providers() -> Result<Vec<OfficialProvider>, String> {
+    let builtin: Vec<OfficialProvider> = serde_json::from_str(BUILTIN_OFFICIAL_PROVIDERS)
+        .map_err(|e| format!("Built-in provider presets are invalid: {}", e))?;
+
+    let user_path = platform::get_provider_presets_file_path();
+    if !file::file_exists(&user_path) {
+        return Ok(builtin);
+    }
+    let content = file::read_file(&user_path).map_err(|e| format!("Failed to read {}: {}", user_path, e))?;
+    let user: Vec<OfficialProvider> =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", user_path, e))?;
+    info!("[Official Provider] Loaded {} presets from {}", user.len(), user_path);
+    Ok(merge_provider_presets(builtin, user))
+}
+
+/// Get official Provider list (preset templates).
+/// Loaded once; an invalid providers.json falls back to the built-ins (reload_official_providers reports the error).
 #[command]
 pub async fn get_official_providers() -> Result<Vec<OfficialProvider>, String> {
     info!("[Official Provider] Getting official Provider preset list...");
+    if let Some(providers) = OFFICIAL_PROVIDERS.read().ok().and_then(|cached| cached.clone()) {
+        return Ok(providers);
+    }
 
-    let providers = vec![
-        OfficialProvider {
-            id: "anthropic".to_string(),
-            name: "Anthropic Claude".to_string(),
-            icon: "🟣".to_string(),
-            default_base_url: Some("https://api.anthropic.com".to_string()),
-            api_type: "anthropic-messages".to_string(),
-            requires_api_key: true,
-            docs_url: Some("https://docs.openclaw.ai/providers/anthropic".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "claude-opus-4-5-20251101".to_string(),
-                    name: "Claude Opus 4.5".to_string(),
-                    description: Some("Most powerful version, suitable for complex tasks".to_string()),
-                    context_window: Some(200000),
-                    max_tokens: Some(8192),
-                    recommended: true,
-                },
-                SuggestedModel {
-                    id: "claude-sonnet-4-5-20250929".to_string(),
-                    name: "Claude Sonnet 4.5".to_string(),
-                    description: Some("Balanced version, high cost-performance ratio".to_string()),
-                    context_window: Some(200000),
-                    max_tokens: Some(8192),
-                    recommended: false,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "openai".to_string(),
-            name: "OpenAI".to_string(),
-            icon: "🟢".to_string(),
-            default_base_url: Some("https://api.openai.com/v1".to_string()),
-            api_type: "openai-completions".to_string(),
-            requires_api_key: true,
-            docs_url: Some("https://docs.openclaw.ai/providers/openai".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "gpt-4o".to_string(),
-                    name: "GPT-4o".to_string(),
-                    description: Some("Latest multimodal model".to_string()),
-                    context_window: Some(128000),
-                    max_tokens: Some(4096),
-                    recommended: true,
-                },
-                SuggestedModel {
-                    id: "gpt-4o-mini".to_string(),
-                    name: "GPT-4o Mini".to_string(),
-                    description: Some("Fast and economical version".to_string()),
-                    context_window: Some(128000),
-                    max_tokens: Some(4096),
-                    recommended: false,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "moonshot".to_string(),
-            name: "Moonshot".to_string(),
-            icon: "🌙".to_string(),
-            default_base_url: Some("https://api.moonshot.cn/v1".to_string()),
-            api_type: "openai-completions".to_string(),
-            requires_api_key: true,
-            docs_url: Some("https://docs.openclaw.ai/providers/moonshot".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "kimi-k2.5".to_string(),
-                    name: "Kimi K2.5".to_string(),
-                    description: Some("Latest flagship model".to_string()),
-                    context_window: Some(200000),
-                    max_tokens: Some(8192),
-                    recommended: true,
-                },
-                SuggestedModel {
-                    id: "moonshot-v1-128k".to_string(),
-                    name: "Moonshot 128K".to_string(),
-                    description: Some("Ultra-long context".to_string()),
-                    context_window: Some(128000),
-                    max_tokens: Some(8192),
-                    recommended: false,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "qwen".to_string(),
-            name: "Qwen (Tongyi Qianwen)".to_string(),
-            icon: "🔮".to_string(),
-            default_base_url: Some("https://dashscope.aliyuncs.com/compatible-mode/v1".to_string()),
-            api_type: "openai-completions".to_string(),
-            requires_api_key: true,
-            docs_url: Some("https://docs.openclaw.ai/providers/qwen".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "qwen-max".to_string(),
-                    name: "Qwen Max".to_string(),
-                    description: Some("Most powerful version".to_string()),
-                    context_window: Some(128000),
-                    max_tokens: Some(8192),
-                    recommended: true,
-                },
-                SuggestedModel {
-                    id: "qwen-plus".to_string(),
-                    name: "Qwen Plus".to_string(),
-                    description: Some("Balanced version".to_string()),
-                    context_window: Some(128000),
-                    max_tokens: Some(8192),
-                    recommended: false,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "deepseek".to_string(),
-            name: "DeepSeek".to_string(),
-            icon: "🔵".to_string(),
-            default_base_url: Some("https://api.deepseek.com".to_string()),
-            api_type: "openai-completions".to_string(),
-            requires_api_key: true,
-            docs_url: None,
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "deepseek-chat".to_string(),
-                    name: "DeepSeek V3".to_string(),
-                    description: Some("Latest chat model".to_string()),
-                    context_window: Some(128000),
-                    max_tokens: Some(8192),
-                    recommended: true,
-                },
-                SuggestedModel {
-                    id: "deepseek-reasoner".to_string(),
-                    name: "DeepSeek R1".to_string(),
-                    description: Some("Reasoning-enhanced model".to_string()),
-                    context_window: Some(128000),
-                    max_tokens: Some(8192),
-                    recommended: false,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "glm".to_string(),
-            name: "GLM (Zhipu)".to_string(),
-            icon: "🔷".to_string(),
-            default_base_url: Some("https://open.bigmodel.cn/api/paas/v4".to_string()),
-            api_type: "openai-completions".to_string(),
-            requires_api_key: true,
-            docs_url: Some("https://docs.openclaw.ai/providers/glm".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "glm-5".to_string(),
-                    name: "GLM-5".to_string(),
-                    description: Some("Latest flagship model".to_string()),
-                    context_window: Some(128000),
-                    max_tokens: Some(8192),
-                    recommended: true,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "minimax".to_string(),
-            name: "MiniMax".to_string(),
-            icon: "🟡".to_string(),
-            default_base_url: Some("https://api.minimax.io/anthropic".to_string()),
-            api_type: "anthropic-messages".to_string(),
-            requires_api_key: true,
-            docs_url: Some("https://docs.openclaw.ai/providers/minimax".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "minimax-m2.1".to_string(),
-                    name: "MiniMax M2.1".to_string(),
-                    description: Some("Latest model".to_string()),
-                    context_window: Some(200000),
-                    max_tokens: Some(8192),
-                    recommended: true,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "venice".to_string(),
-            name: "Venice AI".to_string(),
-            icon: "🏛️".to_string(),
-            default_base_url: Some("https://api.venice.ai/api/v1".to_string()),
-            api_type: "openai-completions".to_string(),
-            requires_api_key: true,
-            docs_url: Some("https://docs.openclaw.ai/providers/venice".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "llama-3.3-70b".to_string(),
-                    name: "Llama 3.3 70B".to_string(),
-                    description: Some("Privacy-first inference".to_string()),
-                    context_window: Some(128000),
-                    max_tokens: Some(8192),
-                    recommended: true,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "openrouter".to_string(),
-            name: "OpenRouter".to_string(),
-            icon: "🔄".to_string(),
-            default_base_url: Some("https://openrouter.ai/api/v1".to_string()),
-            api_type: "openai-completions".to_string(),
-            requires_api_key: true,
-            docs_url: Some("https://docs.openclaw.ai/providers/openrouter".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "anthropic/claude-opus-4-5".to_string(),
-                    name: "Claude Opus 4.5".to_string(),
-                    description: Some("Access via OpenRouter".to_string()),
-                    context_window: Some(200000),
-                    max_tokens: Some(8192),
-                    recommended: true,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "ollama".to_string(),
-            name: "Ollama (Local)".to_string(),
-            icon: "🟠".to_string(),
-            default_base_url: Some("http://localhost:11434".to_string()),
-            api_type: "openai-completions".to_string(),
-            requires_api_key: false,
-            docs_url: Some("https://docs.openclaw.ai/providers/ollama".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "llama3".to_string(),
-                    name: "Llama 3".to_string(),
-                    description: Some("Run locally".to_string()),
-                    context_window: Some(8192),
-                    max_tokens: Some(4096),
-                    recommended: true,
-                },
-            ],
-        },
-        OfficialProvider {
-            id: "google".to_string(),
-            name: "Google Gemini".to_string(),
-            icon: "✨".to_string(),
-            default_base_url: Some("https://generativelanguage.googleapis.com/v1beta/openai/".to_string()),
-            api_type: "openai-completions".to_string(),
-            requires_api_key: true,
-            docs_url: Some("https://ai.google.dev/gemini-api/docs/openai".to_string()),
-            suggested_models: vec![
-                SuggestedModel {
-                    id: "gemini-3-flash-preview".to_string(),
-                    name: "Gemini 3 Flash".to_string(),
-                    description: Some("Fast and efficient multimodal model (Preview)".to_string()),
-                    context_window: Some(1048576),
-                    max_tokens: Some(8192),
-                    recommended: true,
-                },
-                SuggestedModel {
-                    id: "gemini-3-pro-preview".to_string(),
-                    name: "Gemini 3 Pro".to_string(),
-                    description: Some("Complex reasoning tasks (Preview)".to_string()),
-                    context_window: Some(1048576),
-                    max_tokens: Some(8192),
-                    recommended: false,
-                },
-            ],
-        },
-    ];
+    let providers = match load_official_providers() {
+        Ok(providers) => providers,
+        Err(e) => {
+            warn!("[Official Provider] {}; using built-in presets", e);
+            serde_json::from_str(BUILTIN_OFFICIAL_PROVIDERS)
+                .map_err(|e| format!("Built-in provider presets are invalid: {}", e))?
+        }
+    };
+    if let Ok(mut cached) = OFFICIAL_PROVIDERS.write() {
+        *cached = Some(providers.clone());
+    }
 
     info!(
         "[Official Provider] Returned {} official Provider presets",
@@ -558,6 +1232,99 @@ pub async fn get_official_providers() -> Result<Vec<OfficialProvider>, String> {
     Ok(providers)
 }
 
+/// Re-read the provider presets (after editing providers.json), without restarting
+#[command]
+pub async fn reload_official_providers() -> Result<Vec<OfficialProvider>, String> {
+    let providers = load_official_providers()?;
+    if let Ok(mut cached) = OFFICIAL_PROVIDERS.write() {
+        *cached = Some(providers.clone());
+    }
+    info!("[Official Provider] Reloaded {} presets", providers.len());
+    Ok(providers)
+}
+
+/// Cross-reference the presets' suggested models with the configured providers. A configured
+/// provider belongs to a preset when it has the preset's ID as its name or uses its default base URL.
+pub(crate) fn suggested_model_statuses(official: &[OfficialProvider], config: &Value) -> Vec<SuggestedModelStatus> {
+    let providers = config.pointer("/models/providers").and_then(|v| v.as_object());
+    let mut statuses = Vec::new();
+
+    for preset in official {
+        let matching: Vec<(&String, &Value)> = providers
+            .into_iter()
+            .flatten()
+            .filter(|(name, provider_config)| {
+                let base_url = provider_config.get("baseUrl").and_then(|v| v.as_str()).map(|u| u.trim_end_matches('/'));
+                *name == &preset.id
+                    || (base_url.is_some() && base_url == preset.default_base_url.as_deref().map(|u| u.trim_end_matches('/')))
+            })
+            .collect();
+
+        for model in &preset.suggested_models {
+            let configured_in: Vec<String> = matching
+                .iter()
+                .filter(|(_, provider_config)| {
+                    provider_config.get("models").and_then(|v| v.as_array()).is_some_and(|models| {
+                        models.iter().any(|m| m.get("id").and_then(|v| v.as_str()) == Some(model.id.as_str()))
+                    })
+                })
+                .map(|(name, _)| name.to_string())
+                .collect();
+            statuses.push(SuggestedModelStatus {
+                provider_id: preset.id.clone(),
+                model_id: model.id.clone(),
+                configured: !configured_in.is_empty(),
+                configured_in,
+            });
+        }
+    }
+    statuses
+}
+
+/// Mark which of the official presets' suggested models are already configured
+#[command]
+pub async fn get_suggested_model_status() -> Result<Vec<SuggestedModelStatus>, String> {
+    let official = get_official_providers().await?;
+    let config = load_openclaw_config()?;
+    Ok(suggested_model_statuses(&official, &config))
+}
+
+/// Build a ConfiguredModel from a stored model object (None if it has no id).
+/// Models saved before reasoning/cost were recorded report None for those.
+pub(crate) fn configured_model_from(
+    provider_name: &str,
+    m: &Value,
+    primary_model: Option<&str>,
+    available_models: &[String],
+) -> Option<ConfiguredModel> {
+    let id = m.get("id")?.as_str()?.to_string();
+    let name = m
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or(&id)
+        .to_string();
+    let full_id = format!("{}/{}", provider_name, id);
+
+    Some(ConfiguredModel {
+        is_primary: primary_model == Some(full_id.as_str()),
+        is_available: available_models.contains(&full_id),
+        api_type: m.get("api").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        context_window: m
+            .get("contextWindow")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as u32),
+        max_tokens: m
+            .get("maxTokens")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as u32),
+        reasoning: m.get("reasoning").and_then(|v| v.as_bool()),
+        cost: m.get("cost").and_then(|v| serde_json::from_value(v.clone()).ok()),
+        full_id,
+        id,
+        name,
+    })
+}
+
 /// Get AI configuration overview
 #[command]
 pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
@@ -570,11 +1337,14 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
     debug!("[AI Config] Configuration content: {}", serde_json::to_string_pretty(&config).unwrap_or_default());
 
     // Parse primary model
-    let primary_model = config
-        .pointer("/agents/defaults/model/primary")
-        .and_then(|v| v.as_str())
+    let primary_model = model_primary(config.pointer("/agents/defaults/model"))
         .map(|s| s.to_string());
     info!("[AI Config] Primary model: {:?}", primary_model);
+    let fallback_models: Vec<String> = config
+        .pointer("/agents/defaults/model/fallbacks")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+        .unwrap_or_default();
 
     // Parse available model list
     let available_models: Vec<String> = config
@@ -583,6 +1353,11 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
         .map(|obj| obj.keys().cloned().collect())
         .unwrap_or_default();
     info!("[AI Config] Number of available models: {}", available_models.len());
+    let available_entries = available_models_from(&config);
+    let orphaned = available_entries.iter().filter(|m| !m.exists).count();
+    if orphaned > 0 {
+        warn!("[AI Config] {} available models reference a missing provider or model", orphaned);
+    }
 
     // Parse configured Providers
     let mut configured_providers: Vec<ConfiguredProvider> = Vec::new();
@@ -607,13 +1382,7 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
 
-            let api_key_masked = api_key.as_ref().map(|key| {
-                if key.len() > 8 {
-                    format!("{}...{}", &key[..4], &key[key.len() - 4..])
-                } else {
-                    "****".to_string()
-                }
-            });
+            let api_key_masked = api_key.as_deref().map(mask_secret);
 
             // Parse model list
             let models_array = provider_config.get("models").and_then(|v| v.as_array());
@@ -623,32 +1392,14 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
                 .map(|arr| {
                     arr.iter()
                         .filter_map(|m| {
-                            let id = m.get("id")?.as_str()?.to_string();
-                            let name = m
-                                .get("name")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or(&id)
-                                .to_string();
-                            let full_id = format!("{}/{}", provider_name, id);
-                            let is_primary = primary_model.as_ref() == Some(&full_id);
-
-                            info!("[AI Config] Parsed model: {} (is_primary: {})", full_id, is_primary);
-
-                            Some(ConfiguredModel {
-                                full_id,
-                                id,
-                                name,
-                                api_type: m.get("api").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                context_window: m
-                                    .get("contextWindow")
-                                    .and_then(|v| v.as_u64())
-                                    .map(|n| n as u32),
-                                max_tokens: m
-                                    .get("maxTokens")
-                                    .and_then(|v| v.as_u64())
-                                    .map(|n| n as u32),
-                                is_primary,
-                            })
+                            let model = configured_model_from(
+                                provider_name,
+                                m,
+                                primary_model.as_deref(),
+                                &available_models,
+                            )?;
+                            info!("[AI Config] Parsed model: {} (is_primary: {})", model.full_id, model.is_primary);
+                            Some(model)
                         })
                         .collect()
                 })
@@ -677,11 +1428,199 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
 
     Ok(AIConfigOverview {
         primary_model,
+        fallback_models,
         configured_providers,
-        available_models,
+        available_models: available_entries,
     })
 }
 
+/// Keys used by the config structure around providers; a provider with one of these
+/// names is too easily confused with structure when building paths
+const RESERVED_PROVIDER_NAMES: [&str; 12] = [
+    "providers", "models", "model", "apiKey", "baseUrl", "api", "id", "name",
+    "agents", "defaults", "__proto__", "constructor",
+];
+
+/// Escape a single JSON pointer token (RFC 6901)
+pub(crate) fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Validate a provider name before it is used as a config key
+pub(crate) fn validate_provider_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Provider name cannot be empty".to_string());
+    }
+    if name.chars().any(|c| c.is_whitespace()) {
+        return Err(format!("Provider name '{}' cannot contain whitespace", name));
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(format!("Provider name '{}' cannot start with a digit", name));
+    }
+    // "/" separates provider and model in model ids (provider/model-id)
+    if name.contains('/') {
+        return Err(format!("Provider name '{}' cannot contain '/'", name));
+    }
+    if RESERVED_PROVIDER_NAMES.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+        return Err(format!("Provider name '{}' is reserved", name));
+    }
+    Ok(())
+}
+
+/// Normalize a provider base URL: trim, add a scheme when missing (http:// for loopback
+/// hosts such as a local Ollama, https:// otherwise), strip one trailing slash, and
+/// reject anything that is not a valid http(s) URL
+pub(crate) fn normalize_base_url(input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err("Base URL cannot be empty".to_string());
+    }
+
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        let host = trimmed.split(['/', ':']).next().unwrap_or("");
+        let loopback = host.eq_ignore_ascii_case("localhost") || host == "127.0.0.1" || trimmed.starts_with("[::1]");
+        format!("{}://{}", if loopback { "http" } else { "https" }, trimmed)
+    };
+
+    let parsed = reqwest::Url::parse(&with_scheme)
+        .map_err(|e| format!("Invalid base URL '{}': {}", trimmed, e))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(format!("Base URL '{}' must use http:// or https://", trimmed));
+    }
+    if parsed.host_str().is_none_or(|h| h.is_empty()) {
+        return Err(format!("Base URL '{}' has no host", trimmed));
+    }
+
+    Ok(with_scheme.strip_suffix('/').unwrap_or(&with_scheme).to_string())
+}
+
+/// Default soft cap on models per provider
+const DEFAULT_MAX_MODELS_PER_PROVIDER: usize = 100;
+
+/// Soft cap on models per provider (meta.gui.maxModelsPerProvider)
+pub(crate) fn max_models_per_provider(config: &Value) -> usize {
+    config.pointer("/meta/gui/maxModelsPerProvider")
+        .and_then(|v| v.as_u64())
+        .filter(|v| *v > 0)
+        .map(|v| v as usize)
+        .unwrap_or(DEFAULT_MAX_MODELS_PER_PROVIDER)
+}
+
+/// Check a provider's new model list against the soft cap. Exceeding it is an error
+/// (with a warning describing the change) unless `force` is set.
+pub(crate) fn check_model_cap(config: &Value, provider_name: &str, model_ids: &[String], force: bool) -> Result<(), String> {
+    let limit = max_models_per_provider(config);
+    if model_ids.len() <= limit {
+        return Ok(());
+    }
+
+    let existing: Vec<&str> = config
+        .pointer(&format!("/models/providers/{}/models", escape_pointer_token(provider_name)))
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|m| m.get("id").and_then(|v| v.as_str())).collect())
+        .unwrap_or_default();
+    let added = model_ids.iter().filter(|id| !existing.contains(&id.as_str())).count();
+
+    let warning = format!(
+        "Provider {} would have {} models ({} new), above the limit of {} per provider",
+        provider_name, model_ids.len(), added, limit
+    );
+    if force {
+        warn!("[Save Provider] {} (forced)", warning);
+        Ok(())
+    } else {
+        Err(format!("{}. Save again with force to keep them all, or raise meta.gui.maxModelsPerProvider", warning))
+    }
+}
+
+/// Count configured models per provider against the soft cap
+pub(crate) fn provider_model_counts(config: &Value) -> Vec<ProviderModelCount> {
+    let limit = max_models_per_provider(config);
+    let mut counts: Vec<ProviderModelCount> = config.pointer("/models/providers")
+        .and_then(|v| v.as_object())
+        .map(|providers| providers.iter()
+            .map(|(name, p)| {
+                let count = p.get("models").and_then(|v| v.as_array()).map(|a| a.len()).unwrap_or(0);
+                ProviderModelCount { provider: name.clone(), count, limit, over_limit: count > limit }
+            })
+            .collect())
+        .unwrap_or_default();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.provider.cmp(&b.provider)));
+    counts
+}
+
+/// Get the number of configured models per provider
+#[command]
+pub async fn get_provider_model_counts() -> Result<Vec<ProviderModelCount>, String> {
+    let config = load_openclaw_config()?;
+    Ok(provider_model_counts(&config))
+}
+
+/// Check a model's token limits: both positive, and max_tokens not above context_window
+pub(crate) fn validate_model_limits(model: &ModelConfig) -> Result<(), String> {
+    if model.context_window == Some(0) {
+        return Err(format!("Model {}: context window must be greater than 0", model.id));
+    }
+    if model.max_tokens == Some(0) {
+        return Err(format!("Model {}: max tokens must be greater than 0", model.id));
+    }
+    if let (Some(max_tokens), Some(context_window)) = (model.max_tokens, model.context_window) {
+        if max_tokens > context_window {
+            return Err(format!(
+                "Model {}: max tokens ({}) cannot exceed the context window ({})",
+                model.id, max_tokens, context_window
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Limits above what the matching official preset lists for the model (warnings, not errors:
+/// presets lag behind provider updates)
+pub(crate) fn model_limit_warnings(provider_name: &str, models: &[ModelConfig], official: &[OfficialProvider]) -> Vec<String> {
+    let Some(preset) = official.iter().find(|p| p.id == provider_name) else {
+        return Vec::new();
+    };
+    let mut warnings = Vec::new();
+    for model in models {
+        let Some(known) = preset.suggested_models.iter().find(|m| m.id == model.id) else {
+            continue;
+        };
+        if let (Some(value), Some(limit)) = (model.context_window, known.context_window) {
+            if value > limit {
+                warnings.push(format!("{}: context window {} is above the known limit {}", model.id, value, limit));
+            }
+        }
+        if let (Some(value), Some(limit)) = (model.max_tokens, known.max_tokens) {
+            if value > limit {
+                warnings.push(format!("{}: max tokens {} is above the known limit {}", model.id, value, limit));
+            }
+        }
+    }
+    warnings
+}
+
+/// Other providers whose base URL normalizes to the same `base_url`
+pub(crate) fn providers_with_base_url(config: &Value, provider_name: &str, base_url: &str) -> Vec<String> {
+    let Some(providers) = config.pointer("/models/providers").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+    providers
+        .iter()
+        .filter(|(name, _)| name.as_str() != provider_name)
+        .filter(|(_, provider_config)| {
+            provider_config
+                .get("baseUrl")
+                .and_then(|v| v.as_str())
+                .and_then(|url| normalize_base_url(url).ok())
+                .is_some_and(|url| url.eq_ignore_ascii_case(base_url))
+        })
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
 /// Add or update Provider
 #[command]
 pub async fn save_provider(
@@ -690,32 +1629,40 @@ pub async fn save_provider(
     api_key: Option<String>,
     api_type: String,
     models: Vec<ModelConfig>,
+    force: Option<bool>,
 ) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!(
         "[Save Provider] Saving Provider: {} ({} models)",
         provider_name,
         models.len()
     );
 
+    validate_provider_name(&provider_name)?;
+    let base_url = normalize_base_url(&base_url)?;
+    validate_api_type(&api_type)?;
+    for m in &models {
+        if let Some(api) = &m.api {
+            validate_api_type(api).map_err(|e| format!("Model {}: {}", m.id, e))?;
+        }
+        validate_model_limits(m)?;
+    }
+    let mut warnings = model_limit_warnings(&provider_name, &models, &get_official_providers().await?);
+
     let mut config = load_openclaw_config()?;
 
-    // Ensure paths exist
-    if config.get("models").is_none() {
-        config["models"] = json!({});
-    }
-    if config["models"].get("providers").is_none() {
-        config["models"]["providers"] = json!({});
-    }
-    if config.get("agents").is_none() {
-        config["agents"] = json!({});
+    // Allowed (e.g. two keys for one endpoint), but routing between them is easy to confuse
+    let duplicates = providers_with_base_url(&config, &provider_name, &base_url);
+    if !duplicates.is_empty() {
+        warnings.push(format!("{} is also used by provider {}", base_url, duplicates.join(", ")));
     }
-    if config["agents"].get("defaults").is_none() {
-        config["agents"]["defaults"] = json!({});
-    }
-    if config["agents"]["defaults"].get("models").is_none() {
-        config["agents"]["defaults"]["models"] = json!({});
+    for warning in &warnings {
+        warn!("[Save Provider] {}", warning);
     }
 
+    let model_ids: Vec<String> = models.iter().map(|m| m.id.clone()).collect();
+    check_model_cap(&config, &provider_name, &model_ids, force.unwrap_or(false))?;
+
     // Build model configuration
     let models_json: Vec<Value> = models
         .iter()
@@ -771,7 +1718,7 @@ pub async fn save_provider(
         } else {
             // Empty string means no change, try to preserve the existing API Key
             if let Some(existing_key) = config
-                .pointer(&format!("/models/providers/{}/apiKey", provider_name))
+                .pointer(&format!("/models/providers/{}/apiKey", escape_pointer_token(&provider_name)))
                 .and_then(|v| v.as_str())
             {
                 provider_config["apiKey"] = json!(existing_key);
@@ -781,7 +1728,7 @@ pub async fn save_provider(
     } else {
         // None means no change, try to preserve the existing API Key
         if let Some(existing_key) = config
-            .pointer(&format!("/models/providers/{}/apiKey", provider_name))
+            .pointer(&format!("/models/providers/{}/apiKey", escape_pointer_token(&provider_name)))
             .and_then(|v| v.as_str())
         {
             provider_config["apiKey"] = json!(existing_key);
@@ -789,105 +1736,1196 @@ pub async fn save_provider(
         }
     }
 
-    // Save Provider configuration
-    config["models"]["providers"][&provider_name] = provider_config;
-
-    // Add models to agents.defaults.models
-    for model in &models {
-        let full_id = format!("{}/{}", provider_name, model.id);
-        config["agents"]["defaults"]["models"][&full_id] = json!({});
-    }
-
-    // Update metadata
     let now = chrono::Utc::now().to_rfc3339();
-    if config.get("meta").is_none() {
-        config["meta"] = json!({});
+    if !upsert_provider(&mut config, &provider_name, provider_config, &model_ids, &now) {
+        info!("[Save Provider] Provider {} unchanged, nothing written", provider_name);
+        return Ok(format!("No changes to provider {}", provider_name));
     }
-    config["meta"]["lastTouchedAt"] = json!(now);
 
     save_openclaw_config(&config)?;
     info!("[Save Provider] Provider {} saved successfully", provider_name);
 
-    Ok(format!("Provider {} saved", provider_name))
+    if warnings.is_empty() {
+        Ok(format!("Provider {} saved", provider_name))
+    } else {
+        Ok(format!("Provider {} saved (warning: {})", provider_name, warnings.join("; ")))
+    }
 }
 
-/// Delete Provider
-#[command]
-pub async fn delete_provider(provider_name: String) -> Result<String, String> {
-    info!("[Delete Provider] Deleting Provider: {}", provider_name);
-
-    let mut config = load_openclaw_config()?;
+/// Write a provider and register its models in agents.defaults.models. meta.lastTouchedAt is
+/// only bumped when something actually changed; returns false (config untouched) for a no-op.
+pub(crate) fn upsert_provider(
+    config: &mut Value,
+    provider_name: &str,
+    provider_config: Value,
+    model_ids: &[String],
+    now: &str,
+) -> bool {
+    let before = config.clone();
 
-    // Delete Provider configuration
-    if let Some(providers) = config
-        .pointer_mut("/models/providers")
-        .and_then(|v| v.as_object_mut())
-    {
-        providers.remove(&provider_name);
+    // Ensure paths exist
+    if config.get("models").is_none() {
+        config["models"] = json!({});
+    }
+    if config["models"].get("providers").is_none() {
+        config["models"]["providers"] = json!({});
+    }
+    if config.get("agents").is_none() {
+        config["agents"] = json!({});
+    }
+    if config["agents"].get("defaults").is_none() {
+        config["agents"]["defaults"] = json!({});
+    }
+    if config["agents"]["defaults"].get("models").is_none() {
+        config["agents"]["defaults"]["models"] = json!({});
     }
 
-    // Delete related models
-    if let Some(models) = config
-        .pointer_mut("/agents/defaults/models")
-        .and_then(|v| v.as_object_mut())
-    {
-        let keys_to_remove: Vec<String> = models
-            .keys()
-            .filter(|k| k.starts_with(&format!("{}/", provider_name)))
-            .cloned()
-            .collect();
+    // Save Provider configuration
+    config["models"]["providers"][provider_name] = provider_config;
 
-        for key in keys_to_remove {
-            models.remove(&key);
+    // Add models to agents.defaults.models (keeping any per-model settings already there)
+    for model_id in model_ids {
+        let full_id = format!("{}/{}", provider_name, model_id);
+        if config["agents"]["defaults"]["models"].get(&full_id).is_none() {
+            config["agents"]["defaults"]["models"][&full_id] = json!({});
         }
     }
 
-    // If primary model belongs to this Provider, clear primary model
-    if let Some(primary) = config
-        .pointer("/agents/defaults/model/primary")
-        .and_then(|v| v.as_str())
-    {
-        if primary.starts_with(&format!("{}/", provider_name)) {
-            config["agents"]["defaults"]["model"]["primary"] = json!(null);
-        }
+    if *config == before {
+        return false;
     }
 
-    save_openclaw_config(&config)?;
-    info!("[Delete Provider] Provider {} deleted", provider_name);
+    // Update metadata
+    repair_meta_structure(config);
+    config["meta"]["lastTouchedAt"] = json!(now);
+    true
+}
+
+/// Compare a configured provider object against its official preset
+pub(crate) fn compute_provider_deltas(provider_config: &Value, official: &OfficialProvider) -> Vec<ProviderDelta> {
+    let mut deltas = Vec::new();
+
+    // Base URL (ignore trailing slashes, which are equivalent for the gateway)
+    let configured_url = provider_config.get("baseUrl").and_then(|v| v.as_str()).map(|s| s.to_string());
+    let normalize = |u: &str| u.trim().trim_end_matches('/').to_string();
+    if let Some(official_url) = &official.default_base_url {
+        let same = configured_url.as_deref().map(normalize) == Some(normalize(official_url));
+        if !same {
+            deltas.push(ProviderDelta {
+                field: "base_url".to_string(),
+                configured: configured_url.clone(),
+                official: Some(official_url.clone()),
+                message: format!("Base URL differs from the official endpoint {}", official_url),
+            });
+        }
+    }
+
+    let models = provider_config.get("models").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+
+    // api_type is stored per model; report each model whose api deviates
+    for m in &models {
+        let id = m.get("id").and_then(|v| v.as_str()).unwrap_or("");
+        if let Some(api) = m.get("api").and_then(|v| v.as_str()) {
+            if api != official.api_type {
+                deltas.push(ProviderDelta {
+                    field: "api_type".to_string(),
+                    configured: Some(api.to_string()),
+                    official: Some(official.api_type.clone()),
+                    message: format!("Model {} uses api '{}' but the official preset uses '{}'", id, api, official.api_type),
+                });
+            }
+        }
+    }
+
+    // Model ids: configured models unknown to the preset, and suggested models not configured
+    let configured_ids: Vec<&str> = models.iter()
+        .filter_map(|m| m.get("id").and_then(|v| v.as_str()))
+        .collect();
+    for id in &configured_ids {
+        if !official.suggested_models.iter().any(|s| s.id == *id) {
+            deltas.push(ProviderDelta {
+                field: "model".to_string(),
+                configured: Some(id.to_string()),
+                official: None,
+                message: format!("Model {} is not among the official suggested models", id),
+            });
+        }
+    }
+    for suggested in &official.suggested_models {
+        if !configured_ids.contains(&suggested.id.as_str()) {
+            deltas.push(ProviderDelta {
+                field: "model".to_string(),
+                configured: None,
+                official: Some(suggested.id.clone()),
+                message: format!("Suggested model {} is not configured", suggested.id),
+            });
+        }
+    }
+
+    deltas
+}
+
+/// Diff a configured provider against the matching official preset
+#[command]
+pub async fn diff_against_official(provider_name: String) -> Result<Vec<ProviderDelta>, String> {
+    info!("[Provider Diff] Comparing provider {} against official defaults", provider_name);
+
+    let config = load_openclaw_config()?;
+    let provider_config = config
+        .pointer("/models/providers")
+        .and_then(|v| v.get(&provider_name))
+        .ok_or_else(|| format!("Provider {} is not configured", provider_name))?;
+
+    let official = get_official_providers().await?
+        .into_iter()
+        .find(|p| p.id == provider_name)
+        .ok_or_else(|| format!("No official preset found for provider {}", provider_name))?;
+
+    let deltas = compute_provider_deltas(provider_config, &official);
+    info!("[Provider Diff] Found {} differences for {}", deltas.len(), provider_name);
+    Ok(deltas)
+}
+
+/// Parse a usage record timestamp (RFC 3339 string or epoch milliseconds)
+fn parse_usage_timestamp(value: &Value) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Some(s) = value.as_str() {
+        return chrono::DateTime::parse_from_rfc3339(s).ok().map(|t| t.with_timezone(&chrono::Utc));
+    }
+    value.as_i64().and_then(chrono::DateTime::from_timestamp_millis)
+}
+
+/// Aggregate usage records (one JSON object per line) into per provider/model totals.
+/// Session transcript lines carry the assistant message, with its provider, model and usage,
+/// under "message"; other transcript entries (user messages, tool results, ...) are skipped.
+/// Malformed lines are skipped; records older than `since` are ignored.
+pub(crate) fn aggregate_provider_usage(
+    content: &str,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    config: &Value,
+) -> Vec<ProviderUsage> {
+    let mut totals: Vec<ProviderUsage> = Vec::new();
+
+    for line in content.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        let record: Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let record = match record.get("message") {
+            Some(message) if message.get("usage").is_some() => {
+                let mut message = message.clone();
+                if let Some(ts) = record.get("timestamp") {
+                    message["timestamp"] = ts.clone();
+                }
+                message
+            }
+            Some(_) => continue,
+            None => record,
+        };
+
+        if let Some(since) = since {
+            match record.get("timestamp").and_then(parse_usage_timestamp) {
+                Some(ts) if ts >= since => {}
+                _ => continue,
+            }
+        }
+
+        // Model may be recorded as "provider/model" or with a separate provider field
+        let model_ref = record.get("model").and_then(|v| v.as_str()).unwrap_or("");
+        let (provider, model) = match record.get("provider").and_then(|v| v.as_str()) {
+            Some(p) => (p.to_string(), model_ref.strip_prefix(&format!("{}/", p)).unwrap_or(model_ref).to_string()),
+            None => match model_ref.split_once('/') {
+                Some((p, m)) => (p.to_string(), m.to_string()),
+                None => continue,
+            },
+        };
+
+        let tokens = |keys: &[&str]| -> u64 {
+            keys.iter()
+                .find_map(|k| record.get(*k).or_else(|| record.pointer(&format!("/usage/{}", k))))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0)
+        };
+        let input_tokens = tokens(&["inputTokens", "input_tokens", "input"]);
+        let output_tokens = tokens(&["outputTokens", "output_tokens", "output"]);
+
+        // Prefer the recorded cost; otherwise estimate from configured per-million pricing
+        let recorded_cost = record.get("cost").and_then(|v| v.as_f64())
+            .or_else(|| record.pointer("/usage/cost/total").and_then(|v| v.as_f64()));
+        let cost = recorded_cost.unwrap_or_else(|| {
+            config.pointer(&format!("/models/providers/{}/models", escape_pointer_token(&provider)))
+                .and_then(|v| v.as_array())
+                .and_then(|models| models.iter().find(|m| m.get("id").and_then(|v| v.as_str()) == Some(model.as_str())))
+                .and_then(|m| m.get("cost"))
+                .map(|c| {
+                    let input = c.get("input").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                    let output = c.get("output").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                    (input_tokens as f64 * input + output_tokens as f64 * output) / 1_000_000.0
+                })
+                .unwrap_or(0.0)
+        });
+
+        match totals.iter_mut().find(|u| u.provider == provider && u.model == model) {
+            Some(entry) => {
+                entry.requests += 1;
+                entry.input_tokens += input_tokens;
+                entry.output_tokens += output_tokens;
+                entry.estimated_cost += cost;
+            }
+            None => totals.push(ProviderUsage {
+                provider,
+                model,
+                requests: 1,
+                input_tokens,
+                output_tokens,
+                estimated_cost: cost,
+            }),
+        }
+    }
+
+    totals.sort_by(|a, b| a.provider.cmp(&b.provider).then(a.model.cmp(&b.model)));
+    totals
+}
+
+/// Read the session transcripts of every agent under `agents_dir` (<id>/sessions/*.jsonl).
+/// No transcripts yields an empty report with `data_available = false`.
+pub(crate) fn load_provider_usage(
+    agents_dir: &str,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    config: &Value,
+) -> Result<ProviderUsageReport, String> {
+    let mut content = String::new();
+    let mut found = false;
+    for agent in std::fs::read_dir(agents_dir).into_iter().flatten().flatten() {
+        let sessions = agent.path().join("sessions");
+        for entry in std::fs::read_dir(&sessions).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let transcript = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read session {}: {}", path.display(), e))?;
+            content.push_str(&transcript);
+            content.push('\n');
+            found = true;
+        }
+    }
+
+    Ok(ProviderUsageReport {
+        data_available: found,
+        source: agents_dir.to_string(),
+        usage: aggregate_provider_usage(&content, since, config),
+    })
+}
+
+/// Get per-provider usage stats recorded by openclaw
+/// `since_hours` limits the window (None = all recorded usage)
+#[command]
+pub async fn get_provider_usage(since_hours: Option<u32>) -> Result<ProviderUsageReport, String> {
+    info!("[Usage] Loading provider usage (window: {:?}h)", since_hours);
+    let config = load_openclaw_config()?;
+    let since = since_hours.map(|h| chrono::Utc::now() - chrono::Duration::hours(h as i64));
+
+    let report = load_provider_usage(&platform::get_agents_dir(), since, &config)?;
+    if !report.data_available {
+        info!("[Usage] No usage data found at {}", report.source);
+    }
+    Ok(report)
+}
+
+/// Where the primary model's provider key comes from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PrimaryModelKeyStatus {
+    pub primary_model: Option<String>,
+    pub provider: Option<String>,
+    /// "inline", "env" or "missing"
+    pub source: String,
+    /// Referenced variable when the key is a ${VAR} reference
+    pub env_var: Option<String>,
+    /// Whether a non-empty key is actually available
+    pub populated: bool,
+    pub message: String,
+}
+
+/// Extract VAR from a "${VAR}" reference
+pub(crate) fn parse_env_reference(value: &str) -> Option<&str> {
+    value.trim()
+        .strip_prefix("${")
+        .and_then(|v| v.strip_suffix('}'))
+        .filter(|v| !v.is_empty())
+}
+
+/// Resolve the primary model's provider key; `lookup` resolves env variable names
+pub(crate) fn check_primary_model_key(
+    config: &Value,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> PrimaryModelKeyStatus {
+    let primary_model = model_primary(config.pointer("/agents/defaults/model"))
+        .map(|s| s.to_string());
+
+    let provider = primary_model.as_deref()
+        .and_then(|m| m.split_once('/'))
+        .map(|(p, _)| p.to_string());
+
+    let Some(provider_name) = provider.clone() else {
+        return PrimaryModelKeyStatus {
+            primary_model,
+            provider,
+            source: "missing".to_string(),
+            env_var: None,
+            populated: false,
+            message: "No primary model is configured".to_string(),
+        };
+    };
+
+    let api_key = config.pointer("/models/providers")
+        .and_then(|v| v.get(&provider_name))
+        .and_then(|p| p.get("apiKey"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    let (source, env_var, populated, message) = match parse_env_reference(api_key) {
+        Some(var) => {
+            let populated = lookup(var).map(|v| !v.trim().is_empty()).unwrap_or(false);
+            let message = if populated {
+                format!("Key for {} resolves from ${{{}}}", provider_name, var)
+            } else {
+                format!("Key for {} references ${{{}}}, but that variable is not set", provider_name, var)
+            };
+            ("env", Some(var.to_string()), populated, message)
+        }
+        None if !api_key.trim().is_empty() => {
+            ("inline", None, true, format!("Key for {} is stored inline", provider_name))
+        }
+        None => ("missing", None, false, format!("Provider {} has no API key configured", provider_name)),
+    };
+
+    PrimaryModelKeyStatus {
+        primary_model,
+        provider,
+        source: source.to_string(),
+        env_var,
+        populated,
+        message,
+    }
+}
+
+/// Resolve an env variable referenced from the config: OS keyring (keyring mode),
+/// ~/.openclaw/env, then the process environment
+pub(crate) fn lookup_env_reference(var: &str) -> Option<String> {
+    secrets::parse_secret_ref(&format!("${{{}}}", var))
+        .and_then(|v| secrets::load_secret(v).ok().flatten())
+        .or_else(|| {
+            // Env file entries may themselves be keyring references
+            file::read_env_value(&platform::get_env_file_path(), var).and_then(|value| {
+                match secrets::parse_secret_ref(&value) {
+                    Some(secret_var) => secrets::load_secret(secret_var).ok().flatten(),
+                    None => Some(value),
+                }
+            })
+        })
+        .or_else(|| std::env::var(var).ok())
+}
+
+/// Verify that the primary model's provider has a usable API key (inline or via env reference)
+#[command]
+pub async fn verify_primary_model_key() -> Result<PrimaryModelKeyStatus, String> {
+    info!("[Verify Key] Checking primary model key...");
+    let config = load_openclaw_config()?;
+    let status = check_primary_model_key(&config, lookup_env_reference);
+
+    if status.populated {
+        info!("[Verify Key] {}", status.message);
+    } else {
+        warn!("[Verify Key] {}", status.message);
+    }
+    Ok(status)
+}
+
+/// Model list fetched from a provider (possibly served from the offline cache)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelCatalog {
+    pub provider: String,
+    pub models: Vec<String>,
+    /// When the list was last fetched successfully (RFC 3339)
+    pub fetched_at: Option<String>,
+    /// True when the live fetch failed and the cached list was returned
+    pub stale: bool,
+    /// Error from the live fetch, if any
+    pub error: Option<String>,
+}
+
+/// Extract model ids from a list-models response (`data[].id`, or `models[].id` / `models[].name`)
+pub(crate) fn parse_model_list(body: &str) -> Result<Vec<String>, String> {
+    let json: Value = serde_json::from_str(body)
+        .map_err(|e| format!("Invalid model list response: {}", e))?;
+    let entries = json.get("data").or_else(|| json.get("models"))
+        .and_then(|v| v.as_array())
+        .ok_or("Model list response has no data array")?;
+
+    let mut ids: Vec<String> = entries.iter()
+        .filter_map(|m| m.get("id").or_else(|| m.get("name")).and_then(|v| v.as_str()))
+        .map(|s| s.trim_start_matches("models/").to_string())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// Record a fetch result in meta.gui.modelCatalogs: successes refresh the cache,
+/// failures fall back to the cached list (flagged stale) when one exists
+pub(crate) fn apply_catalog_fetch(
+    config: &mut Value,
+    provider_name: &str,
+    fetched: Result<Vec<String>, String>,
+    now: &str,
+) -> Result<ModelCatalog, String> {
+    match fetched {
+        Ok(models) => {
+            repair_meta_structure(config);
+            if !config["meta"]["gui"].get("modelCatalogs").map(|v| v.is_object()).unwrap_or(false) {
+                config["meta"]["gui"]["modelCatalogs"] = json!({});
+            }
+            config["meta"]["gui"]["modelCatalogs"][provider_name] = json!({
+                "models": models,
+                "fetchedAt": now,
+            });
+            Ok(ModelCatalog {
+                provider: provider_name.to_string(),
+                models,
+                fetched_at: Some(now.to_string()),
+                stale: false,
+                error: None,
+            })
+        }
+        Err(e) => {
+            let cached = config.pointer("/meta/gui/modelCatalogs")
+                .and_then(|v| v.get(provider_name))
+                .ok_or_else(|| e.clone())?;
+            Ok(ModelCatalog {
+                provider: provider_name.to_string(),
+                models: cached.get("models").and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|m| m.as_str().map(|s| s.to_string())).collect())
+                    .unwrap_or_default(),
+                fetched_at: cached.get("fetchedAt").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                stale: true,
+                error: Some(e),
+            })
+        }
+    }
+}
+
+/// Remove cached model catalogs (one provider, or all when None). Returns true if anything was removed.
+pub(crate) fn clear_model_catalogs(config: &mut Value, provider_name: Option<&str>) -> bool {
+    let Some(gui) = config.pointer_mut("/meta/gui").and_then(|v| v.as_object_mut()) else {
+        return false;
+    };
+    match provider_name {
+        Some(name) => gui.get_mut("modelCatalogs")
+            .and_then(|v| v.as_object_mut())
+            .map(|catalogs| catalogs.shift_remove(name).is_some())
+            .unwrap_or(false),
+        None => gui.shift_remove("modelCatalogs").is_some(),
+    }
+}
+
+/// Build the authenticated list-models request for a provider: (url, headers).
+/// `lookup` resolves env variable names referenced by the apiKey.
+pub(crate) fn list_models_request(
+    provider_config: &Value,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<(String, Vec<(String, String)>), String> {
+    let base_url = provider_config.get("baseUrl").and_then(|v| v.as_str())
+        .ok_or("Provider has no baseUrl")?
+        .trim_end_matches('/');
+    let api_type = provider_config.pointer("/models/0/api").and_then(|v| v.as_str())
+        .unwrap_or("openai-completions");
+    let info = supported_api_types().into_iter()
+        .find(|t| t.id == api_type)
+        .ok_or_else(|| format!("Unsupported api type: {}", api_type))?;
+    let endpoint = info.list_models_endpoint.clone().ok_or("API type has no list-models endpoint")?;
+
+    let raw_key = provider_config.get("apiKey").and_then(|v| v.as_str()).unwrap_or("");
+    let api_key = match parse_env_reference(raw_key) {
+        Some(var) => lookup(var).unwrap_or_default(),
+        None => raw_key.to_string(),
+    };
+
+    let mut headers = Vec::new();
+    if !api_key.is_empty() {
+        match info.auth_style.as_str() {
+            "header" => {
+                let header = info.auth_param.as_deref().unwrap_or("x-api-key");
+                headers.push((header.to_string(), api_key));
+                headers.push(("anthropic-version".to_string(), "2023-06-01".to_string()));
+            }
+            _ => {
+                headers.push(("Authorization".to_string(), format!("Bearer {}", api_key)));
+            }
+        }
+    }
+    Ok((format!("{}{}", base_url, endpoint), headers))
+}
+
+/// Call a provider's list-models endpoint
+async fn fetch_models_from_provider(provider_config: &Value) -> Result<Vec<String>, String> {
+    let (url, headers) = list_models_request(provider_config, lookup_env_reference)?;
+
+    let client = build_http_client(15000)?;
+    let mut req = client.get(&url);
+    for (name, value) in &headers {
+        req = req.header(name.as_str(), value.as_str());
+    }
+
+    let response = req.send().await.map_err(|e| format!("Failed to fetch models: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Model list request failed (HTTP {})", status.as_u16()));
+    }
+    let body = response.text().await.map_err(|e| format!("Failed to read model list: {}", e))?;
+    parse_model_list(&body)
+}
+
+/// Fetch a provider's model list; falls back to the last known good list when offline
+#[command]
+pub async fn fetch_provider_models(provider_name: String) -> Result<ModelCatalog, String> {
+    info!("[Model Catalog] Fetching models for provider: {}", provider_name);
+    let provider_config = load_openclaw_config()?
+        .pointer("/models/providers")
+        .and_then(|v| v.get(&provider_name))
+        .cloned()
+        .ok_or_else(|| format!("Provider {} is not configured", provider_name))?;
+
+    let fetched = fetch_models_from_provider(&provider_config).await;
+    if let Err(e) = &fetched {
+        warn!("[Model Catalog] Fetch failed for {}: {}", provider_name, e);
+    }
+    let succeeded = fetched.is_ok();
+
+    // Reload under the lock so changes saved during the fetch are kept
+    let _config_lock = lock_config().await;
+    let mut config = load_openclaw_config()?;
+    let catalog = apply_catalog_fetch(&mut config, &provider_name, fetched, &chrono::Utc::now().to_rfc3339())?;
+    if succeeded {
+        save_openclaw_config(&config)?;
+    }
+    info!("[Model Catalog] {} models for {} (stale: {})", catalog.models.len(), provider_name, catalog.stale);
+    Ok(catalog)
+}
+
+/// Clear cached model catalogs (all providers when provider_name is omitted)
+#[command]
+pub async fn clear_model_catalog_cache(provider_name: Option<String>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Model Catalog] Clearing cache: {:?}", provider_name);
+    let mut config = load_openclaw_config()?;
+    if clear_model_catalogs(&mut config, provider_name.as_deref()) {
+        save_openclaw_config(&config)?;
+    }
+    Ok("Model catalog cache cleared".to_string())
+}
+
+/// Probe one provider's list-models endpoint and time the round trip
+async fn probe_provider(
+    client: reqwest::Client,
+    name: String,
+    request: Result<(String, Vec<(String, String)>), String>,
+    timeout_ms: u64,
+) -> ProviderHealth {
+    let (url, headers) = match request {
+        Ok(r) => r,
+        Err(e) => return ProviderHealth { name, reachable: false, latency_ms: None, error: Some(e), cached: false },
+    };
+
+    let mut req = client.get(&url);
+    for (header, value) in &headers {
+        req = req.header(header.as_str(), value.as_str());
+    }
+
+    let started = std::time::Instant::now();
+    match req.send().await {
+        Ok(response) => {
+            let latency_ms = Some(started.elapsed().as_millis() as u64);
+            let status = response.status();
+            if status.is_success() {
+                ProviderHealth { name, reachable: true, latency_ms, error: None, cached: false }
+            } else {
+                ProviderHealth { name, reachable: false, latency_ms, error: Some(format!("HTTP {}", status.as_u16())), cached: false }
+            }
+        }
+        Err(e) => {
+            let error = if e.is_timeout() {
+                format!("Timed out after {}ms", timeout_ms)
+            } else if e.is_connect() {
+                format!("Could not connect to {}", url)
+            } else {
+                format!("Request failed: {}", e)
+            };
+            ProviderHealth { name, reachable: false, latency_ms: None, error: Some(error), cached: false }
+        }
+    }
+}
+
+/// How long a provider probe result is reused before probing again
+const PROVIDER_HEALTH_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Last probe result per provider, keyed by name and base URL so an edited endpoint is re-probed
+#[derive(Default)]
+pub(crate) struct ProviderHealthCache {
+    entries: HashMap<String, (String, std::time::Instant, ProviderHealth)>,
+}
+
+impl ProviderHealthCache {
+    /// Cached result for the provider when it was probed against the same base URL within `ttl`
+    pub(crate) fn get(
+        &self,
+        name: &str,
+        base_url: &str,
+        now: std::time::Instant,
+        ttl: std::time::Duration,
+    ) -> Option<ProviderHealth> {
+        let (cached_url, checked_at, health) = self.entries.get(name)?;
+        if cached_url != base_url || now.saturating_duration_since(*checked_at) > ttl {
+            return None;
+        }
+        Some(ProviderHealth { cached: true, ..health.clone() })
+    }
+
+    pub(crate) fn insert(&mut self, base_url: &str, checked_at: std::time::Instant, health: ProviderHealth) {
+        self.entries.insert(health.name.clone(), (base_url.to_string(), checked_at, health));
+    }
+}
+
+static PROVIDER_HEALTH_CACHE: once_cell::sync::Lazy<std::sync::Mutex<ProviderHealthCache>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(ProviderHealthCache::default()));
+
+/// Probe every configured provider concurrently; each request is bounded by `timeout_ms`.
+/// Providers for which `skip` returns true are left out. Results are sorted by provider name.
+pub(crate) async fn probe_providers(
+    config: &Value,
+    lookup: impl Fn(&str) -> Option<String>,
+    skip: impl Fn(&str) -> bool,
+    timeout_ms: u64,
+) -> Result<Vec<ProviderHealth>, String> {
+    let client = build_http_client(timeout_ms)?;
+
+    let mut probes = tokio::task::JoinSet::new();
+    if let Some(providers) = config.pointer("/models/providers").and_then(|v| v.as_object()) {
+        for (name, provider_config) in providers.iter().filter(|(name, _)| !skip(name)) {
+            let request = list_models_request(provider_config, &lookup);
+            probes.spawn(probe_provider(client.clone(), name.clone(), request, timeout_ms));
+        }
+    }
+
+    let mut results = Vec::new();
+    while let Some(result) = probes.join_next().await {
+        results.push(result.map_err(|e| format!("Provider probe failed: {}", e))?);
+    }
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(results)
+}
+
+/// Check connectivity and latency of every configured provider.
+/// Results younger than 60s are reused unless `force_refresh` is set.
+#[command]
+pub async fn check_all_providers(timeout_ms: Option<u64>, force_refresh: Option<bool>) -> Result<Vec<ProviderHealth>, String> {
+    info!("[Provider Health] Probing all providers (force refresh: {:?})...", force_refresh);
+    let config = load_openclaw_config()?;
+    let base_url = |name: &str| {
+        config
+            .pointer("/models/providers")
+            .and_then(|p| p.get(name))
+            .and_then(|p| p.get("baseUrl"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    let now = std::time::Instant::now();
+    let mut cached = Vec::new();
+    if !force_refresh.unwrap_or(false) {
+        let cache = PROVIDER_HEALTH_CACHE.lock().map_err(|e| format!("Provider health cache poisoned: {}", e))?;
+        if let Some(providers) = config.pointer("/models/providers").and_then(|v| v.as_object()) {
+            cached.extend(providers.keys().filter_map(|name| cache.get(name, &base_url(name), now, PROVIDER_HEALTH_TTL)));
+        }
+    }
+
+    let probed = probe_providers(
+        &config,
+        lookup_env_reference,
+        |name| cached.iter().any(|h| h.name == name),
+        timeout_ms.unwrap_or(5000),
+    )
+    .await?;
+    for r in &probed {
+        match &r.error {
+            None => info!("[Provider Health] {}: reachable ({:?}ms)", r.name, r.latency_ms),
+            Some(e) => warn!("[Provider Health] {}: {}", r.name, e),
+        }
+    }
+    if let Ok(mut cache) = PROVIDER_HEALTH_CACHE.lock() {
+        for r in &probed {
+            cache.insert(&base_url(&r.name), now, r.clone());
+        }
+    }
+
+    let mut results = probed;
+    results.extend(cached);
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(results)
+}
+
+/// Remove a provider, its available-model entries and a primary model pointing at it.
+/// Returns a description of every change (plus warnings for agents left pointing at it).
+pub(crate) fn delete_provider_in(config: &mut Value, provider_name: &str) -> Vec<String> {
+    let prefix = format!("{}/", provider_name);
+    let mut changes = Vec::new();
+
+    // Delete Provider configuration
+    if let Some(providers) = config
+        .pointer_mut("/models/providers")
+        .and_then(|v| v.as_object_mut())
+    {
+        if let Some(removed) = providers.shift_remove(provider_name) {
+            let model_count = removed.get("models").and_then(|v| v.as_array()).map_or(0, |m| m.len());
+            changes.push(format!("Remove provider {} ({} models)", provider_name, model_count));
+        }
+    }
+
+    // Delete related models
+    if let Some(models) = config
+        .pointer_mut("/agents/defaults/models")
+        .and_then(|v| v.as_object_mut())
+    {
+        let keys_to_remove: Vec<String> = models
+            .keys()
+            .filter(|k| k.starts_with(&prefix))
+            .cloned()
+            .collect();
+
+        for key in keys_to_remove {
+            models.shift_remove(&key);
+            changes.push(format!("Remove {} from available models", key));
+        }
+    }
+    for model in prune_unavailable_fallbacks(config) {
+        changes.push(format!("Remove {} from fallback models", model));
+    }
+
+    // If primary model belongs to this Provider, clear primary model
+    if let Some(primary) = model_primary(config.pointer("/agents/defaults/model"))
+        .filter(|p| p.starts_with(&prefix))
+        .map(|p| p.to_string())
+    {
+        clear_model_primary(&mut config["agents"]["defaults"]["model"]);
+        changes.push(format!("Reset primary model (was {})", primary));
+    }
+
+    // Agents with their own model are not touched, but will break
+    if let Some(agents) = config.pointer("/agents/list").and_then(|v| v.as_array()) {
+        for agent in agents {
+            if let Some(model) = model_primary(agent.get("model")).filter(|m| m.starts_with(&prefix)) {
+                let id = agent.get("id").and_then(|v| v.as_str()).unwrap_or("?");
+                changes.push(format!("Warning: agent {} still uses {}", id, model));
+            }
+        }
+    }
+
+    changes
+}
+
+/// Delete Provider
+#[command]
+pub async fn delete_provider(provider_name: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Delete Provider] Deleting Provider: {}", provider_name);
+
+    let mut config = load_openclaw_config()?;
+    for change in delete_provider_in(&mut config, &provider_name) {
+        info!("[Delete Provider] {}", change);
+    }
+
+    save_openclaw_config(&config)?;
+    info!("[Delete Provider] Provider {} deleted", provider_name);
 
     Ok(format!("Provider {} deleted", provider_name))
 }
 
-/// Set primary model
+/// List what delete_provider would change, without writing anything
 #[command]
-pub async fn set_primary_model(model_id: String) -> Result<String, String> {
-    info!("[Set Primary Model] Setting primary model: {}", model_id);
+pub async fn preview_delete_provider(provider_name: String) -> Result<Vec<String>, String> {
+    let mut config = load_openclaw_config()?;
+    Ok(delete_provider_in(&mut config, &provider_name))
+}
+
+/// Move a model within models.providers.<name>.models from index `from` to index `to`
+pub(crate) fn move_provider_model(config: &mut Value, provider_name: &str, from: usize, to: usize) -> Result<(), String> {
+    let models = config
+        .pointer_mut(&format!("/models/providers/{}/models", escape_pointer_token(provider_name)))
+        .and_then(|v| v.as_array_mut())
+        .ok_or_else(|| format!("Provider {} has no models", provider_name))?;
+
+    let len = models.len();
+    for (label, index) in [("from", from), ("to", to)] {
+        if index >= len {
+            return Err(format!(
+                "Index {} = {} is out of range: provider {} has {} models (valid: 0..={})",
+                label, index, provider_name, len, len.saturating_sub(1)
+            ));
+        }
+    }
+
+    let model = models.remove(from);
+    models.insert(to, model);
+    Ok(())
+}
+
+/// Reorder a model within a provider's model list
+#[command]
+pub async fn reorder_provider_model(provider_name: String, from: usize, to: usize) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Reorder Model] Moving model {} -> {} in {}", from, to, provider_name);
 
     let mut config = load_openclaw_config()?;
+    move_provider_model(&mut config, &provider_name, from, to)?;
+    save_openclaw_config(&config)?;
+
+    Ok(format!("Model order updated for {}", provider_name))
+}
+
+/// List all "provider/model-id" ids defined under models.providers
+pub(crate) fn configured_model_ids(config: &Value) -> Vec<String> {
+    let mut ids = Vec::new();
+    if let Some(providers) = config.pointer("/models/providers").and_then(|v| v.as_object()) {
+        for (provider_name, provider_config) in providers {
+            if let Some(models) = provider_config.get("models").and_then(|v| v.as_array()) {
+                for m in models {
+                    if let Some(id) = m.get("id").and_then(|v| v.as_str()) {
+                        ids.push(format!("{}/{}", provider_name, id));
+                    }
+                }
+            }
+        }
+    }
+    ids
+}
+
+/// Entries of agents.defaults.models, each flagged with whether its provider and model exist
+pub(crate) fn available_models_from(config: &Value) -> Vec<AvailableModel> {
+    let configured = configured_model_ids(config);
+    let providers = config.pointer("/models/providers").and_then(|v| v.as_object());
+
+    config
+        .pointer("/agents/defaults/models")
+        .and_then(|v| v.as_object())
+        .map(|obj| {
+            obj.keys()
+                .map(|full_id| {
+                    let provider = full_id.split_once('/').map_or(full_id.as_str(), |(p, _)| p);
+                    AvailableModel {
+                        exists: configured.contains(full_id),
+                        provider_exists: providers.is_some_and(|p| p.contains_key(provider)),
+                        full_id: full_id.clone(),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Remove dangling references across the config: available models whose provider/model is
+/// gone, bindings to agents that no longer exist, and a primary model whose provider is gone
+pub(crate) fn prune_config_in(config: &mut Value) -> PruneReport {
+    let mut report = PruneReport::default();
+
+    let orphans: Vec<String> = available_models_from(config)
+        .into_iter()
+        .filter(|m| !m.exists)
+        .map(|m| m.full_id)
+        .collect();
+    if let Some(models) = config.pointer_mut("/agents/defaults/models").and_then(|v| v.as_object_mut()) {
+        for id in orphans {
+            models.shift_remove(&id);
+            report.removed_models.push(id);
+        }
+    }
+    report.removed_models.sort();
+
+    // "main" is the implicit default agent and is valid even when agents.list omits it
+    let mut agent_ids: Vec<String> = vec!["main".to_string()];
+    if let Some(list) = config.pointer("/agents/list").and_then(|v| v.as_array()) {
+        agent_ids.extend(list.iter().filter_map(|a| a.get("id").and_then(|v| v.as_str())).map(|s| s.to_string()));
+    }
+    for pointer in ["/bindings", "/agents/bindings"] {
+        if let Some(bindings) = config.pointer_mut(pointer).and_then(|v| v.as_array_mut()) {
+            bindings.retain(|b| {
+                let agent = b.get("agentId").and_then(|v| v.as_str()).unwrap_or("");
+                if agent_ids.iter().any(|id| id == agent) {
+                    return true;
+                }
+                let channel = b.pointer("/match/channel").and_then(|v| v.as_str()).unwrap_or("any channel");
+                report.removed_bindings.push(format!("{} ({})", agent, channel));
+                false
+            });
+        }
+    }
+
+    let providers = config.pointer("/models/providers").and_then(|v| v.as_object());
+    let dangling_primary = model_primary(config.pointer("/agents/defaults/model"))
+        .filter(|p| {
+            let provider = p.split_once('/').map_or(*p, |(name, _)| name);
+            !providers.is_some_and(|map| map.contains_key(provider))
+        })
+        .map(|p| p.to_string());
+    if let Some(primary) = dangling_primary {
+        clear_model_primary(&mut config["agents"]["defaults"]["model"]);
+        report.cleared_primary = Some(primary);
+    }
+
+    report
+}
+
+/// Remove orphaned models, bindings and a dangling primary model (backs up openclaw.json first)
+#[command]
+pub async fn prune_config() -> Result<PruneReport, String> {
+    let _config_lock = lock_config().await;
+    info!("[Prune] Pruning dangling config references...");
+    let mut config = load_openclaw_config()?;
+    let report = prune_config_in(&mut config);
+
+    if report != PruneReport::default() {
+        let backup = backup_openclaw_config()?;
+        info!("[Prune] Backed up configuration to {}", backup);
+        save_openclaw_config(&config)?;
+    }
+    info!(
+        "[Prune] Removed {} models, {} bindings, primary cleared: {:?}",
+        report.removed_models.len(),
+        report.removed_bindings.len(),
+        report.cleared_primary
+    );
+    Ok(report)
+}
+
+/// Report what prune_config would remove, without writing anything
+#[command]
+pub async fn preview_prune_config() -> Result<PruneReport, String> {
+    let mut config = load_openclaw_config()?;
+    Ok(prune_config_in(&mut config))
+}
+
+/// Check that a model reference resolves to a configured provider model.
+/// `force` skips the check (e.g. for models the gateway resolves on its own).
+pub(crate) fn resolve_model_reference(config: &Value, model_id: &str, force: bool) -> Result<(), String> {
+    if force {
+        return Ok(());
+    }
+    let valid = configured_model_ids(config);
+    if valid.iter().any(|id| id == model_id) {
+        Ok(())
+    } else if valid.is_empty() {
+        Err(format!("Model '{}' does not resolve: no models are configured", model_id))
+    } else {
+        Err(format!("Model '{}' does not resolve to a configured model. Valid models: {}", model_id, valid.join(", ")))
+    }
+}
+
+/// Check a primary model candidate: it must be in agents.defaults.models or resolve to a
+/// configured provider model. `force` skips the check for models about to be added.
+pub(crate) fn validate_primary_model(config: &Value, model_id: &str, force: bool) -> Result<(), String> {
+    let available = config
+        .pointer("/agents/defaults/models")
+        .and_then(|v| v.as_object())
+        .is_some_and(|models| models.contains_key(model_id));
+    if available {
+        return Ok(());
+    }
+    resolve_model_reference(config, model_id, force)
+}
+
+/// Set primary model (`force` allows a model that is not configured yet)
+#[command]
+pub async fn set_primary_model(model_id: String, force: Option<bool>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Set Primary Model] Setting primary model: {} (force: {:?})", model_id, force);
+
+    let raw = load_openclaw_config()?;
+    validate_primary_model(&raw, &model_id, force.unwrap_or(false))?;
+    let mut config = typed_config_from(raw)?;
+    let model = config
+        .agents.get_or_insert_with(Default::default)
+        .defaults.get_or_insert_with(Default::default)
+        .model.get_or_insert_with(Default::default);
+    model.primary = Some(model_id.clone());
+    // The new primary can't also be its own fallback
+    if let Some(fallbacks) = model.fallbacks.as_mut() {
+        fallbacks.retain(|m| *m != model_id);
+        if fallbacks.is_empty() {
+            model.fallbacks = None;
+        }
+    }
+    save_typed_config(&config)?;
+    info!("[Set Primary Model] Primary model set to: {}", model_id);
+
+    Ok(format!("Primary model set to {}", model_id))
+}
+
+/// Drop fallback models that are no longer in agents.defaults.models, so the chain stays valid
+/// for set_model_chain_in; an emptied list removes the key. Returns the dropped models.
+pub(crate) fn prune_unavailable_fallbacks(config: &mut Value) -> Vec<String> {
+    // Without an available-model list there is nothing to check against
+    let Some(available) = config
+        .pointer("/agents/defaults/models")
+        .and_then(|v| v.as_object())
+        .map(|models| models.keys().cloned().collect::<HashSet<String>>())
+    else {
+        return Vec::new();
+    };
+    let Some(model) = config.pointer_mut("/agents/defaults/model").and_then(|v| v.as_object_mut()) else {
+        return Vec::new();
+    };
+    let Some(fallbacks) = model.get_mut("fallbacks").and_then(|v| v.as_array_mut()) else {
+        return Vec::new();
+    };
+
+    let mut removed = Vec::new();
+    fallbacks.retain(|m| match m.as_str() {
+        Some(id) if !available.contains(id) => {
+            removed.push(id.to_string());
+            false
+        }
+        _ => true,
+    });
+    if fallbacks.is_empty() {
+        model.shift_remove("fallbacks");
+    }
+    removed
+}
+
+/// Set the primary model and its fallback chain. Every model must be in agents.defaults.models,
+/// and none may appear twice. An empty chain removes agents.defaults.model.fallbacks.
+pub(crate) fn set_model_chain_in(config: &mut OpenClawConfig, primary: &str, fallbacks: &[String]) -> Result<(), String> {
+    let defaults = config
+        .agents.get_or_insert_with(Default::default)
+        .defaults.get_or_insert_with(Default::default);
+    let available = defaults.models.as_ref();
+
+    let mut seen: Vec<&str> = Vec::new();
+    for model in std::iter::once(primary).chain(fallbacks.iter().map(|s| s.as_str())) {
+        if !available.is_some_and(|models| models.contains_key(model)) {
+            return Err(format!("{} is not in the available models (agents.defaults.models)", model));
+        }
+        if seen.contains(&model) {
+            return Err(format!("{} appears more than once in the model chain", model));
+        }
+        seen.push(model);
+    }
+
+    let model = defaults.model.get_or_insert_with(Default::default);
+    model.primary = Some(primary.to_string());
+    model.fallbacks = (!fallbacks.is_empty()).then(|| fallbacks.to_vec());
+    Ok(())
+}
+
+/// Set the primary model and the ordered fallback models
+#[command]
+pub async fn set_model_chain(primary: String, fallbacks: Vec<String>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Model Chain] Setting primary {} with fallbacks {:?}", primary, fallbacks);
+
+    let mut config = load_typed_config()?;
+    set_model_chain_in(&mut config, &primary, &fallbacks)?;
+    save_typed_config(&config)?;
+    Ok(format!("Primary model set to {} with {} fallbacks", primary, fallbacks.len()))
+}
+
+/// Apply a reconcile strategy to agents.defaults.models
+/// - add_recommended: add configured models that the official preset marks as recommended
+/// - add_all: add every configured model
+/// - prune_orphans: remove entries whose provider/model is no longer configured
+pub(crate) fn reconcile_available_models_in(
+    config: &mut Value,
+    strategy: &str,
+    official: &[OfficialProvider],
+) -> Result<ReconcileReport, String> {
+    let configured = configured_model_ids(config);
 
-    // Ensure paths exist
     if config.get("agents").is_none() {
         config["agents"] = json!({});
     }
     if config["agents"].get("defaults").is_none() {
         config["agents"]["defaults"] = json!({});
     }
-    if config["agents"]["defaults"].get("model").is_none() {
-        config["agents"]["defaults"]["model"] = json!({});
+    if config["agents"]["defaults"].get("models").is_none() {
+        config["agents"]["defaults"]["models"] = json!({});
+    }
+    let available = config["agents"]["defaults"]["models"]
+        .as_object_mut()
+        .ok_or("agents.defaults.models is not an object")?;
+
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+
+    match strategy {
+        "add_recommended" | "add_all" => {
+            for full_id in &configured {
+                if available.contains_key(full_id) {
+                    continue;
+                }
+                if strategy == "add_recommended" {
+                    let (provider, model) = full_id.split_once('/').unwrap_or((full_id.as_str(), ""));
+                    let recommended = official.iter()
+                        .find(|p| p.id == provider)
+                        .map(|p| p.suggested_models.iter().any(|m| m.id == model && m.recommended))
+                        .unwrap_or(false);
+                    if !recommended {
+                        continue;
+                    }
+                }
+                available.insert(full_id.clone(), json!({}));
+                added.push(full_id.clone());
+            }
+        }
+        "prune_orphans" => {
+            removed = available.keys()
+                .filter(|id| !configured.contains(id))
+                .cloned()
+                .collect();
+            for id in &removed {
+                available.shift_remove(id);
+            }
+        }
+        other => {
+            return Err(format!(
+                "Unknown strategy '{}'. Expected add_recommended, add_all or prune_orphans",
+                other
+            ));
+        }
     }
 
-    // Set primary model
-    config["agents"]["defaults"]["model"]["primary"] = json!(model_id);
+    Ok(ReconcileReport { strategy: strategy.to_string(), added, removed })
+}
 
-    save_openclaw_config(&config)?;
-    info!("[Set Primary Model] Primary model set to: {}", model_id);
+/// Reconcile the available model list with the configured providers
+#[command]
+pub async fn reconcile_available_models(strategy: String) -> Result<ReconcileReport, String> {
+    let _config_lock = lock_config().await;
+    info!("[Reconcile Models] Applying strategy: {}", strategy);
 
-    Ok(format!("Primary model set to {}", model_id))
+    let mut config = load_openclaw_config()?;
+    let official = get_official_providers().await?;
+    let report = reconcile_available_models_in(&mut config, &strategy, &official)?;
+
+    if !report.added.is_empty() || !report.removed.is_empty() {
+        save_openclaw_config(&config)?;
+    }
+    info!("[Reconcile Models] Added {}, removed {}", report.added.len(), report.removed.len());
+    Ok(report)
 }
 
 /// Add model to available list
 #[command]
 pub async fn add_available_model(model_id: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Add Model] Adding model to available list: {}", model_id);
 
     let mut config = load_openclaw_config()?;
@@ -915,6 +2953,7 @@ pub async fn add_available_model(model_id: String) -> Result<String, String> {
 /// Remove model from available list
 #[command]
 pub async fn remove_available_model(model_id: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Remove Model] Removing model from available list: {}", model_id);
 
     let mut config = load_openclaw_config()?;
@@ -923,8 +2962,9 @@ pub async fn remove_available_model(model_id: String) -> Result<String, String>
         .pointer_mut("/agents/defaults/models")
         .and_then(|v| v.as_object_mut())
     {
-        models.remove(&model_id);
+        models.shift_remove(&model_id);
     }
+    prune_unavailable_fallbacks(&mut config);
 
     save_openclaw_config(&config)?;
     info!("[Remove Model] Model {} removed", model_id);
@@ -932,10 +2972,57 @@ pub async fn remove_available_model(model_id: String) -> Result<String, String>
     Ok(format!("Model {} removed", model_id))
 }
 
+/// Add or remove a model key in agents.defaults.models, keeping its provider definition.
+/// Returns whether the config changed.
+pub(crate) fn set_model_available_in(config: &mut Value, model_id: &str, available: bool) -> bool {
+    if available {
+        let present = config
+            .pointer("/agents/defaults/models")
+            .and_then(|v| v.as_object())
+            .is_some_and(|models| models.contains_key(model_id));
+        if present {
+            return false;
+        }
+        if !config.get("agents").is_some_and(|v| v.is_object()) {
+            config["agents"] = json!({});
+        }
+        if !config["agents"].get("defaults").is_some_and(|v| v.is_object()) {
+            config["agents"]["defaults"] = json!({});
+        }
+        if !config["agents"]["defaults"].get("models").is_some_and(|v| v.is_object()) {
+            config["agents"]["defaults"]["models"] = json!({});
+        }
+        config["agents"]["defaults"]["models"][model_id] = json!({});
+        true
+    } else {
+        let removed = config
+            .pointer_mut("/agents/defaults/models")
+            .and_then(|v| v.as_object_mut())
+            .is_some_and(|models| models.shift_remove(model_id).is_some());
+        removed | !prune_unavailable_fallbacks(config).is_empty()
+    }
+}
+
+/// Toggle whether a model is in the available list without touching its provider
+#[command]
+pub async fn set_model_available(model_id: String, available: bool) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Model Availability] Setting {} available={}", model_id, available);
+
+    let mut config = load_openclaw_config()?;
+    if set_model_available_in(&mut config, &model_id, available) {
+        save_openclaw_config(&config)?;
+    }
+
+    let state = if available { "enabled" } else { "disabled" };
+    info!("[Model Availability] Model {} {}", model_id, state);
+    Ok(format!("Model {} {}", model_id, state))
+}
+
 // ============ MCP Configuration Commands ============
 
 /// Load MCP config from separate mcps.json file
-fn load_mcp_config_file() -> Result<HashMap<String, MCPConfig>, String> {
+pub(crate) fn load_mcp_config_file() -> Result<HashMap<String, MCPConfig>, String> {
     let config_path = platform::get_mcp_config_file_path();
     let path = std::path::Path::new(&config_path);
     
@@ -952,25 +3039,93 @@ fn load_mcp_config_file() -> Result<HashMap<String, MCPConfig>, String> {
     Ok(configs)
 }
 
-/// Save MCP config to separate mcps.json file AND sync to ~/.mcporter/mcporter.json
+/// Save MCP config to separate mcps.json file AND sync to ~/.mcporter/mcporter.json.
+/// mcps.json is always written; a sync problem is returned as an error after that.
 fn save_mcp_config_file(configs: &HashMap<String, MCPConfig>) -> Result<(), String> {
-    // 1. Save to Manager's private config (mcps.json)
+    write_mcp_config_file(configs)?;
+    sync_mcp_config(configs)
+}
+
+/// Write the Manager's private MCP config (mcps.json)
+fn write_mcp_config_file(configs: &HashMap<String, MCPConfig>) -> Result<(), String> {
     let config_path = platform::get_mcp_config_file_path();
     let content = serde_json::to_string_pretty(configs)
         .map_err(|e| format!("Failed to serialize MCP config: {}", e))?;
-    
+
     std::fs::write(&config_path, content)
-        .map_err(|e| format!("Failed to write mcps.json: {}", e))?;
-    
-    // 2. Sync enabled servers to system mcporter config (~/.mcporter/mcporter.json)
-    if let Err(e) = sync_to_mcporter(configs) {
-        warn!("Failed to sync to mcporter: {}", e);
-        // Don't fail the whole save operation if sync fails
+        .map_err(|e| format!("Failed to write mcps.json: {}", e))
+}
+
+/// Sync enabled servers to the system mcporter config (~/.mcporter/mcporter.json)
+fn sync_mcp_config(configs: &HashMap<String, MCPConfig>) -> Result<(), String> {
+    sync_to_mcporter(configs).map_err(|e| {
+        warn!("[MCP] mcporter sync incomplete: {}", e);
+        format!("MCP configuration saved, but {}", e)
+    })
+}
+
+/// Expand `${VAR}` references (Manager .env, keyring secrets, process env) in an MCP config value.
+/// Literal text is left untouched; an undefined variable is an error rather than an empty string.
+pub(crate) fn expand_env_refs(value: &str, lookup: &impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let is_var_name = |v: &str| {
+        v.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && v.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        match after.find('}').map(|end| &after[..end]).filter(|v| is_var_name(v)) {
+            Some(var) => {
+                let resolved = lookup(var)
+                    .ok_or_else(|| format!("Environment variable {} is not defined", var))?;
+                out.push_str(&rest[..start]);
+                out.push_str(&resolved);
+                rest = &after[var.len() + 1..];
+            }
+            None => {
+                // Not a reference, keep it literally
+                out.push_str(&rest[..start + 2]);
+                rest = after;
+            }
+        }
     }
-    
-    Ok(())
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Expand `${VAR}` references in an MCP server's env values and args, or its URL and headers
+pub(crate) fn expand_mcp_config(
+    name: &str,
+    config: &MCPConfig,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<MCPConfig, String> {
+    let field_err = |field: String| move |e: String| format!("MCP server '{}': {} ({})", name, e, field);
+
+    let mut expanded = config.clone();
+    match &mut expanded.transport {
+        McpTransport::Stdio { args, env, .. } => {
+            for (key, value) in env.iter_mut() {
+                *value = expand_env_refs(value, lookup).map_err(field_err(format!("env {}", key)))?;
+            }
+            for (i, arg) in args.iter_mut().enumerate() {
+                *arg = expand_env_refs(arg, lookup).map_err(field_err(format!("argument {}", i + 1)))?;
+            }
+        }
+        McpTransport::Http { url, headers } => {
+            *url = expand_env_refs(url, lookup).map_err(field_err("url".to_string()))?;
+            for (key, value) in headers.iter_mut() {
+                *value = expand_env_refs(value, lookup).map_err(field_err(format!("header {}", key)))?;
+            }
+        }
+    }
+    Ok(expanded)
 }
 
+/// Write enabled servers to mcporter.json. A server whose config can't be expanded (e.g. an
+/// undefined ${VAR}) is skipped, keeping its previous mcporter entry, while the others are
+/// still synced; the skipped servers are reported as an error.
 fn sync_to_mcporter(configs: &HashMap<String, MCPConfig>) -> Result<(), String> {
     let mcporter_path = platform::get_mcporter_config_file_path();
     let path = std::path::Path::new(&mcporter_path);
@@ -993,43 +3148,75 @@ fn sync_to_mcporter(configs: &HashMap<String, MCPConfig>) -> Result<(), String>
         serde_json::json!({ "mcpServers": {} })
     };
 
+    let skipped = merge_mcporter_servers(&mut root_val, configs, &lookup_env_reference)?;
+
+    // Write back
+    let new_content = serde_json::to_string_pretty(&root_val)
+        .map_err(|e| format!("Failed to serialize mcporter config: {}", e))?;
+    
+    std::fs::write(path, new_content)
+        .map_err(|e| format!("Failed to write mcporter.json: {}", e))?;
+
+    if !skipped.is_empty() {
+        return Err(format!("mcporter sync skipped {} server(s): {}", skipped.len(), skipped.join("; ")));
+    }
+    Ok(())
+}
+
+/// Add/update enabled servers and remove disabled ones in a mcporter.json document.
+/// Returns the (sorted) errors of servers that were skipped because they couldn't be expanded.
+pub(crate) fn merge_mcporter_servers(
+    root_val: &mut Value,
+    configs: &HashMap<String, MCPConfig>,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<Vec<String>, String> {
     // Ensure mcpServers object exists
-    if root_val.get("mcpServers").is_none() {
+    if !root_val.get("mcpServers").is_some_and(|v| v.is_object()) {
         root_val["mcpServers"] = serde_json::json!({});
     }
 
     let mcp_servers_obj = root_val["mcpServers"].as_object_mut().unwrap();
+    let mut skipped = Vec::new();
 
     // Sync: Add/Update enabled servers from Manager
     for (name, config) in configs {
         if config.enabled {
+            // mcporter doesn't know about ${VAR} references, so hand it resolved values
+            let config = match expand_mcp_config(name, config, lookup) {
+                Ok(config) => config,
+                Err(e) => {
+                    skipped.push(e);
+                    continue;
+                }
+            };
+
             // Convert MCPConfig to serde_json::Value
-            // Note: We skip 'enabled' field as mcporter doesn't use it (presence = enabled)
-            let mut server_val = serde_json::to_value(config)
+            // Note: We skip 'enabled' (presence = enabled) and 'transport' (command vs url tells
+            // mcporter which it is) as mcporter doesn't use them
+            let mut server_val = serde_json::to_value(&config)
                 .map_err(|e| format!("Failed to serialize config for {}: {}", name, e))?;
             
             if let Some(obj) = server_val.as_object_mut() {
-                obj.remove("enabled");
+                obj.shift_remove("enabled");
+                obj.shift_remove("transport");
+                // Install provenance is Manager metadata
+                obj.shift_remove("sourceUrl");
+                obj.shift_remove("installedAt");
+                obj.shift_remove("gitRef");
             }
             
             mcp_servers_obj.insert(name.clone(), server_val);
         } else {
             // Remove disabled servers if they were previously synced
-            mcp_servers_obj.remove(name);
+            mcp_servers_obj.shift_remove(name);
         }
     }
     
     // Important: We do NOT remove servers that are in mcporter but NOT in Manager,
     // to respect user's manual edits or other tools. We only manage the ones we know about.
 
-    // Write back
-    let new_content = serde_json::to_string_pretty(&root_val)
-        .map_err(|e| format!("Failed to serialize mcporter config: {}", e))?;
-    
-    std::fs::write(path, new_content)
-        .map_err(|e| format!("Failed to write mcporter.json: {}", e))?;
-
-    Ok(())
+    skipped.sort();
+    Ok(skipped)
 }
 
 /// Get MCP configuration
@@ -1043,17 +3230,74 @@ pub async fn get_mcp_config() -> Result<HashMap<String, MCPConfig>, String> {
     Ok(configs)
 }
 
-/// Save MCP configuration
+/// Check an MCP server name and the command/url invariants of its transport
+pub(crate) fn validate_mcp_entry(name: &str, config: &MCPConfig) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("MCP server name cannot be empty".to_string());
+    }
+    if name.trim() != name {
+        return Err(format!("MCP server name '{}' has leading or trailing whitespace", name));
+    }
+    if let Some(c) = name.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))) {
+        return Err(format!(
+            "MCP server name '{}' contains invalid character '{}' (use letters, digits, '-', '_' or '.')",
+            name, c
+        ));
+    }
+
+    match &config.transport {
+        McpTransport::Stdio { command, env, .. } => {
+            if command.trim().is_empty() {
+                return Err(format!("MCP server '{}' is a stdio server and needs a command", name));
+            }
+            if let Some(key) = env.keys().find(|k| k.trim().is_empty()) {
+                return Err(format!("MCP server '{}' has an empty environment variable name ({:?})", name, key));
+            }
+        }
+        McpTransport::Http { url, headers } => {
+            let url = url.trim();
+            if url.is_empty() {
+                return Err(format!("MCP server '{}' is a remote server and needs a URL", name));
+            }
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(format!("MCP server '{}' URL must start with http:// or https://", name));
+            }
+            if let Some(key) = headers.keys().find(|k| k.trim().is_empty()) {
+                return Err(format!("MCP server '{}' has an empty header name ({:?})", name, key));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Save MCP configuration.
+/// `is_new` rejects a name that is already taken instead of overwriting that server.
 #[command]
 pub async fn save_mcp_config(
     name: String,
     config: Option<MCPConfig>,
+    is_new: Option<bool>,
 ) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Save MCP] Saving MCP configuration for: {}", name);
     
     let mut configs = load_mcp_config_file()?;
     
-    if let Some(mcp) = config {
+    if let Some(mut mcp) = config {
+        validate_mcp_entry(&name, &mcp)?;
+        // The edit form doesn't send provenance; keep what install_mcp_from_git recorded
+        if mcp.source == McpSource::default() {
+            if let Some(existing) = configs.get(&name) {
+                mcp.source = existing.source.clone();
+            }
+        }
+        if is_new.unwrap_or(false) && configs.contains_key(&name) {
+            return Err(format!("An MCP server named '{}' already exists", name));
+        }
+        // Catch undefined ${VAR} references now rather than at launch time
+        if mcp.enabled {
+            expand_mcp_config(&name, &mcp, &lookup_env_reference)?;
+        }
         configs.insert(name.clone(), mcp);
         info!("[Save MCP] Updated configuration for {}", name);
     } else {
@@ -1065,155 +3309,617 @@ pub async fn save_mcp_config(
     Ok(format!("MCP configuration saved for {}", name))
 }
 
-/// Install MCP server from a Git repository URL
+/// Set `enabled` on every MCP server (or only those in `only`, when given).
+/// Returns the names whose flag actually changed, sorted.
+pub(crate) fn set_mcp_enabled_in(
+    configs: &mut HashMap<String, MCPConfig>,
+    enabled: bool,
+    only: Option<&[String]>,
+) -> Vec<String> {
+    let mut changed: Vec<String> = configs
+        .iter_mut()
+        .filter(|(name, _)| only.is_none_or(|names| names.contains(name)))
+        .filter(|(_, mcp)| mcp.enabled != enabled)
+        .map(|(name, mcp)| {
+            mcp.enabled = enabled;
+            name.clone()
+        })
+        .collect();
+    changed.sort();
+    changed
+}
+
+/// Enable or disable every MCP server at once and re-sync to mcporter.
+/// Disabling remembers which servers were enabled (meta.gui.mcpEnabledSnapshot); enabling
+/// restores exactly that set unless `restore_previous` is false. Returns the number changed.
 #[command]
-pub async fn install_mcp_from_git(url: String) -> Result<String, String> {
-    info!("[MCP Install] Installing MCP from: {}", url);
+pub async fn set_all_mcp_enabled(enabled: bool, restore_previous: Option<bool>) -> Result<usize, String> {
+    let _config_lock = lock_config().await;
+    info!("[MCP Bulk] Setting all MCP servers enabled={}", enabled);
 
-    // Extract repo name from URL (e.g. "excalidraw-mcp" from "https://github.com/excalidraw/excalidraw-mcp")
-    let repo_name = url
-        .trim_end_matches('/')
-        .trim_end_matches(".git")
-        .rsplit('/')
-        .next()
-        .ok_or_else(|| "Invalid repository URL".to_string())?
-        .to_string();
+    let mut configs = load_mcp_config_file()?;
+    let mut config = load_openclaw_config()?;
+
+    let snapshot: Option<Vec<String>> = config
+        .pointer("/meta/gui/mcpEnabledSnapshot")
+        .and_then(|v| serde_json::from_value(v.clone()).ok());
+
+    let changed = if enabled {
+        let only = snapshot.filter(|_| restore_previous.unwrap_or(true));
+        if only.is_some() {
+            info!("[MCP Bulk] Restoring previously enabled servers");
+        }
+        set_mcp_enabled_in(&mut configs, true, only.as_deref())
+    } else {
+        set_mcp_enabled_in(&mut configs, false, None)
+    };
+
+    // Sync problems are reported after the snapshot is updated
+    let synced = if changed.is_empty() {
+        Ok(())
+    } else {
+        write_mcp_config_file(&configs)?;
+        sync_mcp_config(&configs)
+    };
+
+    // Only overwrite the snapshot when something was disabled, so a repeated
+    // "disable all" doesn't lose the set to restore
+    if enabled || !changed.is_empty() {
+        repair_meta_structure(&mut config);
+        if let Some(gui) = config.pointer_mut("/meta/gui").and_then(|v| v.as_object_mut()) {
+            if enabled {
+                gui.shift_remove("mcpEnabledSnapshot");
+            } else {
+                gui.insert("mcpEnabledSnapshot".to_string(), json!(changed));
+            }
+        }
+        save_openclaw_config(&config)?;
+    }
+
+    info!("[MCP Bulk] {} MCP servers changed", changed.len());
+    synced?;
+    Ok(changed.len())
+}
+
+/// Normalize a git remote URL for comparison
+/// ("https://github.com/a/b.git", "git@github.com:a/b" and "github.com/a/b/" are the same remote)
+pub(crate) fn normalize_git_remote(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    // scp-like syntax: git@host:owner/repo
+    let without_user = without_scheme.split_once('@')
+        .filter(|(user, _)| !user.contains('/'))
+        .map(|(_, rest)| rest)
+        .unwrap_or(without_scheme);
+    let path_form = match without_user.split_once(':') {
+        Some((host, path)) if !path.starts_with(|c: char| c.is_ascii_digit()) => format!("{}/{}", host, path),
+        _ => without_user.to_string(),
+    };
+    path_form.to_lowercase()
+}
+
+/// Whether two git remote URLs point at the same repository
+pub(crate) fn same_git_remote(a: &str, b: &str) -> bool {
+    normalize_git_remote(a) == normalize_git_remote(b)
+}
+
+/// Device names Windows refuses as file or directory names (with any extension)
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Make a name safe as a directory name on every platform: characters outside [A-Za-z0-9._-]
+/// become '-', leading/trailing dots and dashes are dropped, Windows device names get a '_' suffix
+pub(crate) fn sanitize_mcp_dir_name(name: &str) -> String {
+    let mapped: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '-' })
+        .collect();
+    let trimmed = mapped.trim_matches(|c| c == '.' || c == '-');
+    let stem = trimmed.split('.').next().unwrap_or("").to_ascii_uppercase();
+    if WINDOWS_RESERVED_NAMES.contains(&stem.as_str()) {
+        format!("{}_", trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Path segments of a git URL after the scheme, without a trailing ".git"
+fn git_url_segments(url: &str) -> Vec<&str> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme.split(['/', ':']).filter(|s| !s.is_empty()).collect()
+}
+
+/// Directory and mcps.json name for a git install, namespaced by owner so same-named repos
+/// of different owners don't collide: "excalidraw__excalidraw-mcp" from
+/// "https://github.com/excalidraw/excalidraw-mcp". URLs without an owner use the repo name alone.
+pub(crate) fn mcp_install_name(url: &str) -> Result<String, String> {
+    let segments = git_url_segments(url);
+    let repo = segments
+        .last()
+        .map(|s| sanitize_mcp_dir_name(s))
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Could not extract repository name from URL".to_string())?;
+    // With fewer than three segments the one before the repo is the host, not an owner
+    let owner = (segments.len() >= 3)
+        .then(|| sanitize_mcp_dir_name(segments[segments.len() - 2]))
+        .filter(|s| !s.is_empty());
+    Ok(match owner {
+        Some(owner) => format!("{}__{}", owner, repo),
+        None => repo,
+    })
+}
+
+/// Name earlier Manager versions installed a git URL under (the bare repository name)
+pub(crate) fn legacy_mcp_install_name(url: &str) -> Option<String> {
+    git_url_segments(url).last().map(|s| sanitize_mcp_dir_name(s)).filter(|s| !s.is_empty())
+}
+
+/// Default overall time limit for install_mcp_from_git
+const DEFAULT_MCP_INSTALL_TIMEOUT_SECS: u64 = 300;
+
+/// Cancel signals of in-flight MCP installs, keyed by repository name
+static MCP_INSTALLS: once_cell::sync::Lazy<std::sync::Mutex<HashMap<String, std::sync::Arc<tokio::sync::Notify>>>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+/// An in-flight MCP install with its cancel signal and overall deadline.
+/// Unregisters itself when dropped, however the install ends.
+pub(crate) struct McpInstall {
+    name: String,
+    cancel: std::sync::Arc<tokio::sync::Notify>,
+    deadline: tokio::time::Instant,
+    timeout_secs: u64,
+}
+
+impl McpInstall {
+    /// Register an install; fails if one with the same name is already running
+    pub(crate) fn start(name: &str, timeout_secs: u64) -> Result<Self, String> {
+        let mut installs = MCP_INSTALLS.lock().map_err(|_| "Install registry is unavailable".to_string())?;
+        if installs.contains_key(name) {
+            return Err(format!("{} is already being installed", name));
+        }
+        let cancel = std::sync::Arc::new(tokio::sync::Notify::new());
+        installs.insert(name.to_string(), cancel.clone());
+        Ok(Self {
+            name: name.to_string(),
+            cancel,
+            deadline: tokio::time::Instant::now() + std::time::Duration::from_secs(timeout_secs),
+            timeout_secs,
+        })
+    }
+
+    /// Run one install step. The process is killed when the overall deadline passes
+    /// or the install is cancelled.
+    pub(crate) async fn run(&self, cmd: &str, args: &[&str], cwd: Option<&str>) -> Result<std::process::Output, String> {
+        let step = format!("{} {}", cmd, args.first().unwrap_or(&""));
+        tokio::select! {
+            output = shell::run_command_async(cmd, args, cwd) => {
+                output.map_err(|e| format!("Failed to run {}: {}", step, e))
+            }
+            _ = tokio::time::sleep_until(self.deadline) => {
+                Err(format!("Installing {} timed out after {}s ({} was stopped)", self.name, self.timeout_secs, step))
+            }
+            _ = self.cancel.notified() => {
+                Err(format!("Installation of {} was cancelled ({} was stopped)", self.name, step))
+            }
+        }
+    }
+}
+
+impl Drop for McpInstall {
+    fn drop(&mut self) {
+        if let Ok(mut installs) = MCP_INSTALLS.lock() {
+            installs.remove(&self.name);
+        }
+    }
+}
+
+/// Signal the in-flight install `name` to stop. Returns false if nothing is installing under that name.
+pub(crate) fn cancel_mcp_install(name: &str) -> bool {
+    let cancel = MCP_INSTALLS.lock().ok().and_then(|installs| installs.get(name).cloned());
+    match cancel {
+        Some(cancel) => {
+            // notify_one keeps the signal if no step is waiting yet, so it can't be missed
+            cancel.notify_one();
+            true
+        }
+        None => false,
+    }
+}
 
-    if repo_name.is_empty() {
-        return Err("Could not extract repository name from URL".to_string());
+/// Cancel an in-flight install_mcp_from_git (by install name or URL); its running process is killed
+#[command]
+pub async fn cancel_install(name: String) -> Result<String, String> {
+    let (name, legacy) = if name.contains('/') {
+        (mcp_install_name(&name)?, legacy_mcp_install_name(&name))
+    } else {
+        (name.trim().to_string(), None)
+    };
+    info!("[MCP Install] Cancelling install of {}", name);
+    if cancel_mcp_install(&name) || legacy.is_some_and(|legacy| cancel_mcp_install(&legacy)) {
+        Ok(format!("Cancelling installation of {}", name))
+    } else {
+        Err(format!("No installation of {} is in progress", name))
     }
+}
+
+/// Install MCP server from a Git repository URL.
+/// An existing checkout of the same remote is updated with `git pull` instead of re-cloned;
+/// `force` wipes it and does a clean reinstall. Processes are killed after `timeout_secs`
+/// (default 300) in total, or when cancel_install is called.
+#[command]
+pub async fn install_mcp_from_git(url: String, force: Option<bool>, timeout_secs: Option<u64>) -> Result<String, String> {
+    info!("[MCP Install] Installing MCP from: {}", url);
 
-    info!("[MCP Install] Repository name: {}", repo_name);
+    let mut repo_name = mcp_install_name(&url)?;
+    // Keep using a checkout made before install names were namespaced by owner
+    if let Some(legacy) = legacy_mcp_install_name(&url).filter(|legacy| *legacy != repo_name) {
+        let legacy_path = mcp_install_path(&legacy)?;
+        if !std::path::Path::new(&mcp_install_path(&repo_name)?).exists()
+            && git_origin(&legacy_path).await.is_some_and(|remote| same_git_remote(&remote, &url))
+        {
+            repo_name = legacy;
+        }
+    }
+    info!("[MCP Install] Install name: {}", repo_name);
+    let install = McpInstall::start(&repo_name, timeout_secs.unwrap_or(DEFAULT_MCP_INSTALL_TIMEOUT_SECS))?;
 
     // Create mcps directory if it doesn't exist
     let mcps_dir = platform::get_mcp_install_dir();
     std::fs::create_dir_all(&mcps_dir)
         .map_err(|e| format!("Failed to create mcps directory: {}", e))?;
 
-    let install_path = if platform::is_windows() {
-        format!("{}\\{}", mcps_dir, repo_name)
-    } else {
-        format!("{}/{}", mcps_dir, repo_name)
-    };
+    let install_path = mcp_install_path(&repo_name)?;
 
-    // Remove existing directory if present (re-install)
+    // Reuse an existing checkout of the same remote unless a clean reinstall was requested
+    let mut reuse_checkout = false;
     if std::path::Path::new(&install_path).exists() {
-        info!("[MCP Install] Removing existing installation at {}", install_path);
-        std::fs::remove_dir_all(&install_path)
-            .map_err(|e| format!("Failed to remove existing directory: {}", e))?;
+        let existing_remote = git_origin(&install_path).await;
+
+        reuse_checkout = !force.unwrap_or(false)
+            && existing_remote.as_deref().is_some_and(|remote| same_git_remote(remote, &url));
+
+        if !reuse_checkout {
+            info!("[MCP Install] Removing existing installation at {}", install_path);
+            std::fs::remove_dir_all(&install_path)
+                .map_err(|e| format!("Failed to remove existing directory: {}", e))?;
+        }
     }
 
-    // Step 1: Clone the repository
-    info!("[MCP Install] Cloning repository...");
-    let clone_output = shell::run_command("git", &["clone", &url, &install_path])
-        .map_err(|e| format!("Failed to run git clone: {}", e))?;
+    if reuse_checkout {
+        // Step 1: Update the existing checkout
+        info!("[MCP Install] {} is already cloned, pulling latest changes...", repo_name);
+        let head = |path: String| async move {
+            shell::run_command_async("git", &["rev-parse", "HEAD"], Some(&path)).await
+                .ok()
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        };
+        let before = head(install_path.clone()).await;
+        let pull_output = install.run("git", &["pull", "--ff-only"], Some(&install_path)).await?;
+        if !pull_output.status.success() {
+            return Err(format_command_failure(
+                "Git pull failed (use force to reinstall from scratch)",
+                &pull_output.stdout,
+                &pull_output.stderr,
+            ));
+        }
+        let after = head(install_path.clone()).await;
+
+        let node_modules = std::path::Path::new(&install_path).join("node_modules");
+        if before == after && node_modules.is_dir() && load_mcp_config_file()?.contains_key(&repo_name) {
+            info!("[MCP Install] {} is already installed and up to date", repo_name);
+            return Ok(format!("MCP {} is already installed and up to date", repo_name));
+        }
+    } else {
+        // Step 1: Clone the repository
+        info!("[MCP Install] Cloning repository...");
+        let clone_output = install.run("git", &["clone", &url, &install_path], None).await?;
 
-    if !clone_output.status.success() {
-        let stderr = String::from_utf8_lossy(&clone_output.stderr);
-        return Err(format!("Git clone failed: {}", stderr));
+        if !clone_output.status.success() {
+            return Err(format_command_failure("Git clone failed", &clone_output.stdout, &clone_output.stderr));
+        }
+        info!("[MCP Install] Clone successful");
     }
-    info!("[MCP Install] Clone successful");
 
     // Step 2: npm install
     info!("[MCP Install] Running npm install...");
     let npm_cmd = if platform::is_windows() { "npm.cmd" } else { "npm" };
 
-    let mut npm_install = std::process::Command::new(npm_cmd);
-    npm_install.args(&["install"]).current_dir(&install_path);
-
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-        npm_install.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
-
-    let install_output = npm_install.output()
-        .map_err(|e| format!("Failed to run npm install: {}", e))?;
+    let install_output = install.run(npm_cmd, &["install"], Some(&install_path)).await?;
 
     if !install_output.status.success() {
-        let stderr = String::from_utf8_lossy(&install_output.stderr);
-        return Err(format!("npm install failed: {}", stderr));
+        return Err(format_command_failure("npm install failed", &install_output.stdout, &install_output.stderr));
     }
     info!("[MCP Install] npm install successful");
 
     // Step 3: npm run build
     info!("[MCP Install] Running npm run build...");
-    let mut npm_build = std::process::Command::new(npm_cmd);
-    npm_build.args(&["run", "build"]).current_dir(&install_path);
-
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-        npm_build.creation_flags(0x08000000);
-    }
-
-    let build_output = npm_build.output()
-        .map_err(|e| format!("Failed to run npm run build: {}", e))?;
+    let build_output = install.run(npm_cmd, &["run", "build"], Some(&install_path)).await?;
 
     if !build_output.status.success() {
-        let stderr = String::from_utf8_lossy(&build_output.stderr);
-        warn!("[MCP Install] npm run build failed (may not have a build step): {}", stderr);
+        warn!("[MCP Install] {}", format_command_failure(
+            "npm run build failed (may not have a build step)",
+            &build_output.stdout,
+            &build_output.stderr,
+        ));
         // Don't fail — some MCPs don't need a build step
     } else {
         info!("[MCP Install] npm run build successful");
     }
 
-    // Step 4: Auto-configure in mcps.json
+    // Step 4: Auto-configure in mcps.json (the lock is only taken here so clone/build don't block other saves)
     info!("[MCP Install] Configuring MCP in mcps.json...");
+    let _config_lock = lock_config().await;
     let mut configs = load_mcp_config_file()?;
 
-    // Determine the entry point (dist/index.js or index.js)
-    let dist_index = if platform::is_windows() {
-        format!("{}\\dist\\index.js", install_path)
-    } else {
-        format!("{}/dist/index.js", install_path)
+    let package_json = std::fs::read_to_string(std::path::Path::new(&install_path).join("package.json")).ok();
+    let entry_point = match find_mcp_entry_point(&install_path, package_json.as_deref(), |p| std::path::Path::new(p).is_file()) {
+        Ok(entry_point) => entry_point,
+        Err(e) if !build_output.status.success() => {
+            return Err(format_command_failure(
+                &format!("{}\nThe build step failed, which is the likely cause", e),
+                &build_output.stdout,
+                &build_output.stderr,
+            ));
+        }
+        Err(e) => return Err(e),
+    };
+
+    let git_ref = shell::run_command_async("git", &["rev-parse", "HEAD"], Some(&install_path))
+        .await
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+    let first_line = std::fs::read_to_string(&entry_point).ok().and_then(|s| s.lines().next().map(str::to_string));
+    let (command, args) = mcp_launch_command(&entry_point, first_line.as_deref());
+    info!("[MCP Install] Launch command: {} {:?}", command, args);
+    let mut mcp = MCPConfig::stdio(command, args);
+    mcp.source = McpSource {
+        source_url: Some(url.trim().to_string()),
+        installed_at: Some(chrono::Utc::now().to_rfc3339()),
+        git_ref,
+    };
+    configs.insert(repo_name.clone(), mcp);
+
+    save_mcp_config_file(&configs)?;
+    info!("[MCP Install] Installation complete for {}", repo_name);
+    Ok(format!("Successfully installed MCP: {}", repo_name))
+}
+
+/// Entry points tried, in order, when package.json names none that exists
+const MCP_ENTRY_CANDIDATES: &[&str] = &["dist/index.js", "index.js"];
+
+/// Entry scripts named in package.json, in launch order: "bin" (the command named after the
+/// package first when there are several), then "main"
+pub(crate) fn package_json_entries(package_json: &str) -> Vec<String> {
+    let Ok(package) = serde_json::from_str::<Value>(package_json) else {
+        return Vec::new();
     };
+    let mut entries: Vec<String> = Vec::new();
+    match package.get("bin") {
+        Some(Value::String(bin)) => entries.push(bin.clone()),
+        Some(Value::Object(bins)) => {
+            let own_name = package.get("name").and_then(|v| v.as_str())
+                .map(|name| name.rsplit('/').next().unwrap_or(name));
+            if let Some(own) = own_name.and_then(|name| bins.get(name)).and_then(|v| v.as_str()) {
+                entries.push(own.to_string());
+            }
+            entries.extend(bins.values().filter_map(|v| v.as_str()).map(str::to_string));
+        }
+        _ => {}
+    }
+    if let Some(main) = package.get("main").and_then(|v| v.as_str()) {
+        entries.push(main.to_string());
+    }
+
+    let mut unique: Vec<String> = Vec::new();
+    for entry in entries {
+        let entry = entry.trim().trim_start_matches("./").to_string();
+        if !entry.is_empty() && !unique.contains(&entry) {
+            unique.push(entry);
+        }
+    }
+    unique
+}
+
+/// Entry point of an MCP server checked out at `install_path`: the first existing file among
+/// package.json's "bin"/"main" and the dist/index.js, index.js fallbacks. Paths leaving the
+/// checkout are ignored. With no entry point the install must not be configured, since node
+/// would fail to start with a confusing error.
+pub(crate) fn find_mcp_entry_point(
+    install_path: &str,
+    package_json: Option<&str>,
+    is_file: impl Fn(&str) -> bool,
+) -> Result<String, String> {
+    let sep = if platform::is_windows() { "\\" } else { "/" };
+    let mut candidates = package_json.map(package_json_entries).unwrap_or_default();
+    candidates.extend(MCP_ENTRY_CANDIDATES.iter().map(|c| c.to_string()));
+
+    candidates
+        .iter()
+        .filter(|rel| !rel.starts_with(['/', '\\']) && !rel.split(['/', '\\']).any(|part| part == ".." || part.contains(':')))
+        .map(|rel| format!("{}{}{}", install_path, sep, rel.split(['/', '\\']).collect::<Vec<_>>().join(sep)))
+        .find(|path| is_file(path))
+        .ok_or_else(|| {
+            format!(
+                "No entry point found in {} (looked for package.json \"bin\"/\"main\", {}). The server was not configured; \
+                 check its README for the start command and add it with \"Add Manual\".",
+                install_path,
+                MCP_ENTRY_CANDIDATES.join(", ")
+            )
+        })
+}
 
-    let entry_point = if std::path::Path::new(&dist_index).exists() {
-        dist_index
+/// Command and arguments that start the server at `entry_point`. JavaScript files, and
+/// scripts whose shebang (`first_line`) runs node, start under node; other shebang'd
+/// executables (e.g. Python) run directly, except on Windows where shebangs are ignored.
+pub(crate) fn mcp_launch_command(entry_point: &str, first_line: Option<&str>) -> (String, Vec<String>) {
+    let is_javascript = [".js", ".mjs", ".cjs"].iter().any(|ext| entry_point.ends_with(ext));
+    let foreign_shebang = first_line
+        .and_then(|line| line.strip_prefix("#!"))
+        .is_some_and(|interpreter| !interpreter.contains("node"));
+    if !is_javascript && foreign_shebang && !platform::is_windows() {
+        (entry_point.to_string(), vec!["--stdio".to_string()])
     } else {
-        let root_index = if platform::is_windows() {
-            format!("{}\\index.js", install_path)
-        } else {
-            format!("{}/index.js", install_path)
-        };
-        if std::path::Path::new(&root_index).exists() {
-            root_index
+        ("node".to_string(), vec![entry_point.to_string(), "--stdio".to_string()])
+    }
+}
+
+/// Characters kept per output stream in command failure messages
+const FAILURE_OUTPUT_LIMIT: usize = 4000;
+
+/// Describe a failed command with both output streams, labeled. npm often prints the
+/// actionable error (e.g. peer-dependency conflicts) on stdout, so stderr alone isn't enough.
+/// Each stream keeps its last FAILURE_OUTPUT_LIMIT characters, where the error usually is.
+pub(crate) fn format_command_failure(what: &str, stdout: &[u8], stderr: &[u8]) -> String {
+    let tail = |bytes: &[u8]| {
+        let text = String::from_utf8_lossy(bytes);
+        let text = text.trim();
+        let count = text.chars().count();
+        if count > FAILURE_OUTPUT_LIMIT {
+            let kept: String = text.chars().skip(count - FAILURE_OUTPUT_LIMIT).collect();
+            format!("... ({} characters truncated)\n{}", count - FAILURE_OUTPUT_LIMIT, kept)
         } else {
-            dist_index
+            text.to_string()
         }
     };
+    let (stdout, stderr) = (tail(stdout), tail(stderr));
+
+    let mut message = what.to_string();
+    if stdout.is_empty() && stderr.is_empty() {
+        message.push_str(" (no output)");
+    }
+    if !stderr.is_empty() {
+        message.push_str(&format!("\n--- stderr ---\n{}", stderr));
+    }
+    if !stdout.is_empty() {
+        message.push_str(&format!("\n--- stdout ---\n{}", stdout));
+    }
+    message
+}
+
+/// Check that a stdio MCP command can be launched: a path must point at an existing file,
+/// a bare command name must be found by `on_path` (the extended PATH)
+pub(crate) fn check_mcp_command_resolves(
+    command: &str,
+    on_path: impl Fn(&str) -> bool,
+    is_file: impl Fn(&str) -> bool,
+) -> Result<(), String> {
+    let command = command.trim();
+    if command.contains('/') || command.contains('\\') {
+        if !is_file(command) {
+            return Err(format!("Command '{}' does not exist", command));
+        }
+    } else if !on_path(command) {
+        return Err(format!("Command '{}' was not found on the PATH", command));
+    }
+    Ok(())
+}
+
+/// Register an MCP server that is already installed on this system (no git clone or npm install)
+#[command]
+pub async fn register_existing_mcp(
+    name: String,
+    command: String,
+    args: Vec<String>,
+    env: HashMap<String, String>,
+) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[MCP Register] Registering existing MCP {}: {}", name, command);
 
-    configs.insert(repo_name.clone(), MCPConfig {
-        command: "node".to_string(),
-        args: vec![entry_point, "--stdio".to_string()],
-        env: HashMap::new(),
-        url: String::new(),
+    let mcp = MCPConfig {
+        transport: McpTransport::Stdio { command: command.trim().to_string(), args, env },
         enabled: true,
-    });
+        source: McpSource::default(),
+    };
+    validate_mcp_entry(&name, &mcp)?;
+    check_mcp_command_resolves(&command, shell::command_exists, |p| std::path::Path::new(p).is_file())?;
+
+    let mut configs = load_mcp_config_file()?;
+    if configs.contains_key(&name) {
+        return Err(format!("An MCP server named '{}' already exists", name));
+    }
+    expand_mcp_config(&name, &mcp, &lookup_env_reference)?;
+    configs.insert(name.clone(), mcp);
 
     save_mcp_config_file(&configs)?;
-    info!("[MCP Install] Installation complete for {}", repo_name);
-    Ok(format!("Successfully installed MCP: {}", repo_name))
+    info!("[MCP Register] Registered {}", name);
+    Ok(format!("Registered MCP server {}", name))
+}
+
+/// Where an MCP server named `name` is (or would be) installed. Names that are not plain
+/// directory names (e.g. containing path separators or "..") are rejected.
+fn mcp_install_path(name: &str) -> Result<String, String> {
+    if name.is_empty() || sanitize_mcp_dir_name(name) != name {
+        return Err(format!("'{}' is not a valid MCP install directory name", name));
+    }
+    let mcps_dir = platform::get_mcp_install_dir();
+    Ok(if platform::is_windows() {
+        format!("{}\\{}", mcps_dir, name)
+    } else {
+        format!("{}/{}", mcps_dir, name)
+    })
+}
+
+/// URL of the `origin` remote of a checkout, if it is a git repository
+async fn git_origin(path: &str) -> Option<String> {
+    if !std::path::Path::new(path).exists() {
+        return None;
+    }
+    shell::run_command_async("git", &["remote", "get-url", "origin"], Some(path))
+        .await
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+}
+
+/// Describe what uninstalling an MCP server would change
+pub(crate) fn uninstall_mcp_changes(
+    name: &str,
+    configs: &HashMap<String, MCPConfig>,
+    install_path: Option<&str>,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+    if let Some(path) = install_path {
+        changes.push(format!("Delete directory {}", path));
+    }
+    if let Some(server) = configs.get(name) {
+        changes.push(format!("Remove {} from mcps.json", name));
+        if server.enabled {
+            changes.push(format!("Remove {} from mcporter.json", name));
+        }
+    }
+    changes
+}
+
+/// List what uninstall_mcp would change, without touching anything
+#[command]
+pub async fn preview_uninstall_mcp(name: String) -> Result<Vec<String>, String> {
+    // Servers registered under names that aren't directory names have no install directory
+    let install_path = mcp_install_path(&name).ok().filter(|p| std::path::Path::new(p).exists());
+    let configs = load_mcp_config_file()?;
+    Ok(uninstall_mcp_changes(&name, &configs, install_path.as_deref()))
 }
 
 /// Uninstall an MCP server
 #[command]
 pub async fn uninstall_mcp(name: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[MCP Uninstall] Uninstalling MCP: {}", name);
 
-    // Remove directory
-    let mcps_dir = platform::get_mcp_install_dir();
-    let install_path = if platform::is_windows() {
-        format!("{}\\{}", mcps_dir, name)
-    } else {
-        format!("{}/{}", mcps_dir, name)
-    };
-
-    if std::path::Path::new(&install_path).exists() {
-        std::fs::remove_dir_all(&install_path)
-            .map_err(|e| format!("Failed to remove MCP directory: {}", e))?;
-        info!("[MCP Uninstall] Removed directory: {}", install_path);
+    // Remove directory (only for names that map to a directory inside mcps/)
+    match mcp_install_path(&name) {
+        Ok(install_path) if std::path::Path::new(&install_path).exists() => {
+            std::fs::remove_dir_all(&install_path)
+                .map_err(|e| format!("Failed to remove MCP directory: {}", e))?;
+            info!("[MCP Uninstall] Removed directory: {}", install_path);
+        }
+        Ok(_) => {}
+        Err(e) => info!("[MCP Uninstall] No install directory to remove: {}", e),
     }
 
     // Remove from mcps.json
@@ -1225,233 +3931,1003 @@ pub async fn uninstall_mcp(name: String) -> Result<String, String> {
     Ok(format!("Successfully uninstalled MCP: {}", name))
 }
 
-/// Check if mcporter is installed
+/// npm package that provides the `mcporter` CLI
+const MCPORTER_PACKAGE: &str = "mcporter";
+
+/// Package name older Manager versions tried to uninstall; removed too if present
+const LEGACY_MCPORTER_PACKAGE: &str = "@openclaw/mcporter";
+
+/// Which of `candidates` are listed in `npm ls -g --depth=0 --json` output
+pub(crate) fn installed_npm_packages(ls_json: &str, candidates: &[&str]) -> Vec<String> {
+    let json: Value = serde_json::from_str(ls_json).unwrap_or(Value::Null);
+    let Some(deps) = json.get("dependencies").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+    candidates.iter()
+        .filter(|name| deps.contains_key(**name))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Globally installed npm packages among `candidates`, or None if npm couldn't be queried
+async fn global_npm_packages(candidates: &[&str]) -> Option<Vec<String>> {
+    let npm_cmd = if platform::is_windows() { "npm.cmd" } else { "npm" };
+    // npm ls exits non-zero for unrelated problems in the global tree but still prints the JSON
+    let output = shell::run_command_async(npm_cmd, &["ls", "-g", "--depth=0", "--json"], None).await.ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        return None;
+    }
+    Some(installed_npm_packages(&stdout, candidates))
+}
+
+/// Installed mcporter version and the latest one on npm
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McporterStatus {
+    pub installed: bool,
+    /// Version reported by `mcporter --version`
+    pub version: Option<String>,
+    /// Latest version published on npm (None when npm couldn't be reached)
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+}
+
+/// Combine the installed and latest versions into a status
+pub(crate) fn mcporter_status_from(installed: bool, version: Option<String>, latest_version: Option<String>) -> McporterStatus {
+    let update_available = match (&version, &latest_version) {
+        (Some(current), Some(latest)) => super::installer::compare_versions(current, latest),
+        _ => false,
+    };
+    McporterStatus { installed, version, latest_version, update_available }
+}
+
+/// Get the installed mcporter version (`mcporter --version`), None when it can't be run
+#[command]
+pub async fn get_mcporter_version() -> Result<Option<String>, String> {
+    let cmd = if platform::is_windows() { "mcporter.cmd" } else { "mcporter" };
+    let version = shell::run_command_async(cmd, &["--version"], None).await
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| parse_tool_version(&String::from_utf8_lossy(&o.stdout)));
+    info!("[mcporter] Version: {:?}", version);
+    Ok(version)
+}
+
+/// Latest mcporter version published on npm
+async fn latest_mcporter_version() -> Option<String> {
+    let npm_cmd = if platform::is_windows() { "npm.cmd" } else { "npm" };
+    let output = shell::run_command_async(npm_cmd, &["view", MCPORTER_PACKAGE, "version"], None).await.ok()?;
+    if !output.status.success() {
+        warn!("[mcporter] Failed to get latest version from npm");
+        return None;
+    }
+    parse_tool_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Check if mcporter is installed and which version. Installed means its binary is on the PATH,
+/// or npm lists the package (the global npm bin directory isn't always on the PATH the app sees).
 #[command]
-pub async fn check_mcporter_installed() -> Result<bool, String> {
+pub async fn check_mcporter_installed() -> Result<McporterStatus, String> {
     info!("[mcporter] Checking if mcporter is installed...");
-    let installed = shell::command_exists("mcporter");
-    info!("[mcporter] Installed: {}", installed);
-    Ok(installed)
+    let installed = shell::command_exists("mcporter")
+        || global_npm_packages(&[MCPORTER_PACKAGE, LEGACY_MCPORTER_PACKAGE]).await
+            .is_some_and(|found| !found.is_empty());
+
+    let status = if installed {
+        let (version, latest) = tokio::join!(get_mcporter_version(), latest_mcporter_version());
+        mcporter_status_from(true, version?, latest)
+    } else {
+        mcporter_status_from(false, None, None)
+    };
+    info!("[mcporter] Installed: {} (version {:?}, latest {:?})", status.installed, status.version, status.latest_version);
+    Ok(status)
 }
 
 /// Install mcporter via npm
 #[command]
 pub async fn install_mcporter() -> Result<String, String> {
-    info!("[mcporter] Installing mcporter globally via npm...");
+    info!("[mcporter] Installing {} globally via npm...", MCPORTER_PACKAGE);
 
     let npm_cmd = if platform::is_windows() { "npm.cmd" } else { "npm" };
 
-    let mut cmd = std::process::Command::new(npm_cmd);
-    cmd.args(&["install", "-g", "mcporter"]);
-
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000);
-    }
-
-    let output = cmd.output()
+    let output = shell::run_command_async(npm_cmd, &["install", "-g", MCPORTER_PACKAGE], None)
+        .await
         .map_err(|e| format!("Failed to run npm install: {}", e))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("npm install -g mcporter failed: {}", stderr));
+        return Err(format_command_failure(
+            &format!("npm install -g {} failed", MCPORTER_PACKAGE),
+            &output.stdout,
+            &output.stderr,
+        ));
     }
 
     info!("[mcporter] Installation successful");
-    Ok("mcporter installed successfully".to_string())
+    Ok(format!("mcporter installed successfully (npm package {})", MCPORTER_PACKAGE))
 }
 
-/// Uninstall Mcporter
+/// Uninstall mcporter: the current package, plus the legacy package name if it is installed
 #[command]
 pub async fn uninstall_mcporter() -> Result<String, String> {
-    info!("Uninstalling mcporter globally via npm");
-
-    #[cfg(target_os = "windows")]
-    let program = "cmd";
-    #[cfg(target_os = "windows")]
-    let args = ["/C", "npm uninstall -g @openclaw/mcporter"];
+    info!("[mcporter] Uninstalling mcporter globally via npm...");
+
+    // Uninstall whatever is actually there; if npm can't tell us, try both names
+    let candidates = [MCPORTER_PACKAGE, LEGACY_MCPORTER_PACKAGE];
+    let packages = match global_npm_packages(&candidates).await {
+        Some(found) if found.is_empty() => {
+            info!("[mcporter] No mcporter package is installed");
+            return Ok(format!("mcporter is not installed (checked npm packages {})", candidates.join(", ")));
+        }
+        Some(found) => found,
+        None => candidates.iter().map(|s| s.to_string()).collect(),
+    };
 
-    #[cfg(not(target_os = "windows"))]
-    let program = "npm";
-    #[cfg(not(target_os = "windows"))]
-    let args = ["uninstall", "-g", "@openclaw/mcporter"];
+    let npm_cmd = if platform::is_windows() { "npm.cmd" } else { "npm" };
+    let mut args = vec!["uninstall", "-g"];
+    args.extend(packages.iter().map(|s| s.as_str()));
 
-    let output = std::process::Command::new(program)
-        .args(args)
-        .output()
+    let output = shell::run_command_async(npm_cmd, &args, None)
+        .await
         .map_err(|e| format!("Failed to execute npm uninstall: {}", e))?;
 
     if output.status.success() {
-        info!("mcporter uninstalled successfully");
-        Ok("MCPorter uninstalled successfully".to_string())
+        info!("[mcporter] Uninstalled {}", packages.join(", "));
+        Ok(format!("mcporter uninstalled successfully (npm package {})", packages.join(", ")))
     } else {
-        let error_msg = String::from_utf8_lossy(&output.stderr);
-        error!("Failed to uninstall mcporter: {}", error_msg);
-        Err(format!("Failed to uninstall mcporter: {}", error_msg))
+        let error_msg = format_command_failure(
+            &format!("Failed to uninstall mcporter (npm package {})", packages.join(", ")),
+            &output.stdout,
+            &output.stderr,
+        );
+        error!("[mcporter] {}", error_msg);
+        Err(error_msg)
+    }
+}
+
+/// Fail early with install guidance when the openclaw CLI is missing
+fn require_openclaw_cli(action: &str) -> Result<(), String> {
+    if shell::openclaw_available() {
+        return Ok(());
     }
+    warn!("[OpenClaw CLI] Not found, cannot {}", action);
+    Err(format!(
+        "{}. OpenClaw is required to {}: install it from the setup page, then try again.",
+        shell::OPENCLAW_NOT_FOUND, action
+    ))
+}
+
+/// Names a plugin installed from `source` is likely listed under (lowercase): the last
+/// segment of a package name, git URL or path without version or extension, and the full
+/// package name for scoped npm packages
+pub(crate) fn plugin_name_candidates(source: &str) -> Vec<String> {
+    let source = source.trim().trim_end_matches(['/', '\\']).to_lowercase();
+    let mut names = Vec::new();
+    if source.starts_with('@') {
+        names.push(split_package_version(&source).0.to_string());
+    }
+    let last = source.rsplit(['/', '\\', ':']).next().unwrap_or(&source);
+    let last = split_package_version(last).0;
+    let last = last.trim_end_matches(".git").trim_end_matches(".tgz");
+    if !last.is_empty() && !names.iter().any(|n| n == last) {
+        names.push(last.to_string());
+    }
+    names
+}
+
+/// Line of `plugins list` (after the install) showing the plugin from `source`. A matching line
+/// that was not listed before the install wins; failing a name match, a single new line is
+/// taken as the plugin (it may register under a different name).
+pub(crate) fn find_installed_plugin(source: &str, before: &str, after: &str) -> Option<String> {
+    let candidates = plugin_name_candidates(source);
+    let previous: HashSet<&str> = before.lines().map(str::trim).collect();
+    let lines: Vec<&str> = after.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let new_lines: Vec<&str> = lines.iter().copied().filter(|l| !previous.contains(l)).collect();
+    let matches = |line: &str| {
+        let line = line.to_lowercase();
+        candidates.iter().any(|c| line.contains(c.as_str()))
+    };
+
+    new_lines.iter().copied().find(|l| matches(l))
+        .or_else(|| lines.iter().copied().find(|l| matches(l)))
+        .or_else(|| (new_lines.len() == 1).then(|| new_lines[0]))
+        .map(str::to_string)
 }
 
-/// Install MCP server as an OpenClaw plugin (using openclaw plugins install)
+/// Turn the output of `plugins install` and the plugin list before/after into a result. A
+/// non-zero exit, or a plugin that does not show up in the list, is an error carrying the CLI output.
+pub(crate) fn interpret_plugin_install(
+    source: &str,
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+    list_before: &str,
+    list_after: &str,
+) -> Result<PluginInstallResult, String> {
+    let output = format!("{}\n{}", stdout.trim(), stderr.trim()).trim().to_string();
+    if exit_code != Some(0) {
+        let code = exit_code.map(|c| c.to_string()).unwrap_or_else(|| "none (terminated by signal)".to_string());
+        return Err(format!("openclaw plugins install {} failed (exit code {}):\n{}", source, code, output));
+    }
+    let plugin = find_installed_plugin(source, list_before, list_after).ok_or_else(|| {
+        format!(
+            "openclaw plugins install {} reported success, but the plugin is not listed by `openclaw plugins list`.\nCLI output:\n{}",
+            source, output
+        )
+    })?;
+    Ok(PluginInstallResult { source: source.to_string(), plugin, stdout, stderr, exit_code })
+}
+
+/// Install MCP plugin via openclaw CLI and verify it appears in `openclaw plugins list`
 #[command]
-pub async fn install_mcp_plugin(url: String) -> Result<String, String> {
+pub async fn install_mcp_plugin(url: String) -> Result<PluginInstallResult, String> {
     info!("[MCP Plugin] Installing MCP plugin from: {}", url);
+    install_plugin(url).await
+}
 
-    let result = shell::run_openclaw(&["plugins", "install", &url])
-        .map_err(|e| format!("Failed to install plugin: {}", e))?;
+/// Turn the CLI's output for `config set` into a result; a non-zero exit is an error
+pub(crate) fn interpret_config_set(
+    key: &str,
+    value: &str,
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+) -> Result<ConfigSetResult, String> {
+    if exit_code != Some(0) {
+        let code = exit_code.map(|c| c.to_string()).unwrap_or_else(|| "none (terminated by signal)".to_string());
+        let detail = format!("{}\n{}", stdout.trim(), stderr.trim()).trim().to_string();
+        return Err(format!("openclaw config set {} failed (exit code {}): {}", key, code, detail));
+    }
+    Ok(ConfigSetResult { key: key.to_string(), value: value.to_string(), stdout, stderr, exit_code })
+}
+
+/// Set openclaw config via CLI (openclaw config set <key> <value>) and return the CLI's output
+#[command]
+pub async fn openclaw_config_set(key: String, value: String) -> Result<ConfigSetResult, String> {
+    info!("[Config CLI] Setting config: {} = {}", key, log_sanitizer::sanitize(&value));
+    require_openclaw_cli("change settings via the CLI")?;
+
+    let out = shell::run_openclaw_raw(&["config", "set", &key, &value])?;
+    let result = interpret_config_set(
+        &key,
+        &value,
+        out.status.code(),
+        String::from_utf8_lossy(&out.stdout).to_string(),
+        String::from_utf8_lossy(&out.stderr).to_string(),
+    );
 
-    info!("[MCP Plugin] Installation result: {}", result);
-    Ok(format!("Successfully installed MCP plugin from: {}", url))
+    match &result {
+        Ok(r) => info!("[Config CLI] Set {} (stdout: {})", key, log_sanitizer::sanitize(r.stdout.trim())),
+        Err(e) => warn!("[Config CLI] {}", log_sanitizer::sanitize(e)),
+    }
+    result
+}
+
+/// Persisted defaults for MCP server tests (stored in meta.gui.mcpTest)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct McpTestDefaults {
+    /// Timeout in milliseconds; None keeps the built-in per-transport default
+    #[serde(rename = "timeoutMs", alias = "timeout_ms", default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Extra HTTP headers sent to remote MCP servers
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+}
+
+/// Read MCP test defaults from a loaded config
+pub(crate) fn mcp_test_defaults_from_config(config: &Value) -> McpTestDefaults {
+    config.pointer("/meta/gui/mcpTest")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Resolve the timeout for an MCP test: explicit value, then persisted default, then fallback
+pub(crate) fn resolve_mcp_test_timeout(explicit: Option<u64>, defaults: &McpTestDefaults, fallback_ms: u64) -> u64 {
+    explicit.or(defaults.timeout_ms).filter(|ms| *ms > 0).unwrap_or(fallback_ms)
+}
+
+/// Resolve the headers sent to an HTTP MCP server: the persisted defaults (replaced by an
+/// explicit map when given), then the server's own headers, then the explicit ones on top
+pub(crate) fn resolve_mcp_headers(
+    explicit: Option<&HashMap<String, String>>,
+    defaults: &McpTestDefaults,
+    server: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut headers = if explicit.is_some() { HashMap::new() } else { defaults.headers.clone() };
+    headers.extend(server.iter().map(|(k, v)| (k.clone(), v.clone())));
+    if let Some(explicit) = explicit {
+        headers.extend(explicit.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    headers
+}
+
+/// Whether an HTTP header carries credentials (Authorization, Cookie, X-Api-Key, ...)
+pub(crate) fn is_secret_header(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "authorization" | "proxy-authorization" | "cookie"
+    ) || secrets::is_secret_key(name)
+}
+
+/// Render headers for logging with credential values masked
+pub(crate) fn headers_for_log(headers: &HashMap<String, String>) -> String {
+    let mut entries: Vec<String> = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_secret_header(name) { mask_secret(value) } else { value.clone() };
+            format!("{}: {}", name, value)
+        })
+        .collect();
+    entries.sort();
+    entries.join(", ")
+}
+
+/// Get persisted MCP test defaults
+#[command]
+pub async fn get_mcp_test_defaults() -> Result<McpTestDefaults, String> {
+    let config = load_openclaw_config()?;
+    Ok(mcp_test_defaults_from_config(&config))
+}
+
+/// Save MCP test defaults
+#[command]
+pub async fn set_mcp_test_defaults(timeout_ms: Option<u64>, headers: Option<HashMap<String, String>>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[MCP Test] Saving test defaults: timeout_ms={:?}", timeout_ms);
+    let mut config = load_openclaw_config()?;
+
+    repair_meta_structure(&mut config);
+
+    let defaults = McpTestDefaults {
+        timeout_ms: timeout_ms.filter(|ms| *ms > 0),
+        headers: headers.unwrap_or_default(),
+    };
+    config["meta"]["gui"]["mcpTest"] = serde_json::to_value(&defaults)
+        .map_err(|e| format!("Failed to serialize MCP test defaults: {}", e))?;
+
+    save_openclaw_config(&config)?;
+    Ok("MCP test defaults saved".to_string())
 }
 
-/// Set openclaw config via CLI (openclaw config set <key> <value>)
+/// System tools needed by test_mcp_server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TestDeps {
+    /// node (stdio MCP servers)
+    pub node: bool,
+    /// npx (stdio MCP servers launched via npx)
+    pub npx: bool,
+    /// Human-readable warnings for missing tools
+    pub warnings: Vec<String>,
+}
+
+/// Build the dependency report using `exists` to probe for commands
+pub(crate) fn detect_test_dependencies(exists: impl Fn(&str) -> bool) -> TestDeps {
+    let node = exists("node");
+    let npx = exists("npx");
+
+    let mut warnings = Vec::new();
+    if !node {
+        warnings.push("node was not found: Node.js based stdio MCP servers cannot be tested".to_string());
+    }
+    if !npx {
+        warnings.push("npx was not found: MCP servers launched with npx cannot be tested".to_string());
+    }
+
+    TestDeps { node, npx, warnings }
+}
+
+/// Check which tools required for MCP tests are installed
 #[command]
-pub async fn openclaw_config_set(key: String, value: String) -> Result<String, String> {
-    info!("[Config CLI] Setting config: {} = {}", key, value);
+pub async fn check_test_dependencies() -> Result<TestDeps, String> {
+    info!("[MCP Test] Checking test dependencies...");
+    let deps = detect_test_dependencies(shell::command_exists);
+    for w in &deps.warnings {
+        warn!("[MCP Test] {}", w);
+    }
+    Ok(deps)
+}
+
+/// Tools install_mcp_from_git needs (git clone, npm install / build, npx-launched servers)
+const PREREQUISITE_TOOLS: [&str; 4] = ["git", "node", "npm", "npx"];
+
+/// Availability of one command-line tool
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolStatus {
+    pub name: String,
+    /// Whether the tool is on the extended PATH
+    pub found: bool,
+    /// Version reported by `<tool> --version`
+    pub version: Option<String>,
+}
+
+/// Extract the version number from `--version` output ("git version 2.43.0", "v22.1.0", "10.2.4")
+pub(crate) fn parse_tool_version(output: &str) -> Option<String> {
+    output.lines().next()?
+        .split_whitespace()
+        .map(|w| w.trim_start_matches('v'))
+        .find(|w| w.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|w| w.to_string())
+}
 
-    let result = shell::run_openclaw(&["config", "set", &key, &value])
-        .map_err(|e| format!("Failed to set config: {}", e))?;
+/// Build the prerequisite report; `exists` probes for a command, `version` returns its --version output
+pub(crate) fn prerequisite_statuses(
+    exists: impl Fn(&str) -> bool,
+    version: impl Fn(&str) -> Option<String>,
+) -> Vec<ToolStatus> {
+    PREREQUISITE_TOOLS.iter()
+        .map(|name| {
+            let found = exists(name);
+            ToolStatus {
+                name: name.to_string(),
+                found,
+                version: if found { version(name).as_deref().and_then(parse_tool_version) } else { None },
+            }
+        })
+        .collect()
+}
 
-    info!("[Config CLI] Set result: {}", result);
-    Ok(format!("Set {} = {}", key, value))
+/// Check that git, node, npm and npx are installed (needed to install MCP servers from source)
+#[command]
+pub async fn check_prerequisites() -> Result<Vec<ToolStatus>, String> {
+    info!("[MCP Install] Checking prerequisites...");
+    let statuses = prerequisite_statuses(shell::command_exists, |name| {
+        // npm and npx are .cmd shims on Windows
+        let cmd = if platform::is_windows() && (name == "npm" || name == "npx") {
+            format!("{}.cmd", name)
+        } else {
+            name.to_string()
+        };
+        shell::run_command_output(&cmd, &["--version"]).ok()
+    });
+    for s in statuses.iter().filter(|s| !s.found) {
+        warn!("[MCP Install] {} was not found", s.name);
+    }
+    Ok(statuses)
 }
 
 /// Test an MCP server connectivity
+/// `timeout_ms` and `headers` fall back to the persisted MCP test defaults when omitted.
+/// For a configured HTTP server (target is its name or URL) its own headers are sent as well.
+/// For stdio servers `timeout_ms` is the deadline for the initialize response and
+/// `retries` re-spawns the server that many extra times if it doesn't answer.
 #[command]
-pub async fn test_mcp_server(server_type: String, target: String, command: Option<String>, args: Option<Vec<String>>) -> Result<String, String> {
+pub async fn test_mcp_server(
+    server_type: String,
+    target: String,
+    command: Option<String>,
+    args: Option<Vec<String>>,
+    timeout_ms: Option<u64>,
+    headers: Option<HashMap<String, String>>,
+    retries: Option<u32>,
+) -> Result<McpTestResult, String> {
     info!("[MCP Test] Testing MCP server: type={}, target={}", server_type, target);
 
+    let defaults = load_openclaw_config()
+        .map(|c| mcp_test_defaults_from_config(&c))
+        .unwrap_or_default();
+
     if server_type == "url" {
         // Remote HTTP MCP: POST an MCP initialize request to the URL
-        let mut cmd = std::process::Command::new(if cfg!(windows) { "curl.exe" } else { "curl" });
-        cmd.args(&[
-            "-s", "-w", "\n%{http_code}",
-            "-X", "POST",
-            "-H", "Content-Type: application/json",
-            "-H", "Accept: text/event-stream, application/json",
-            "-d", r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"test","version":"1.0"}}}"#,
-            "--max-time", "10",
-            &target,
-        ]);
+        let timeout = resolve_mcp_test_timeout(timeout_ms, &defaults, 10_000);
 
-        #[cfg(windows)]
-        {
-            use std::os::windows::process::CommandExt;
-            cmd.creation_flags(0x08000000);
-        }
-
-        match cmd.output() {
-            Ok(out) => {
-                let output_str = String::from_utf8_lossy(&out.stdout).to_string();
-                let lines: Vec<&str> = output_str.trim().lines().collect();
-                let status_code = lines.last().unwrap_or(&"0");
-                let body = if lines.len() > 1 { lines[..lines.len()-1].join("\n") } else { String::new() };
-
-                if status_code.starts_with("2") {
-                    // Try to extract server name from JSON response
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&body) {
-                        if let Some(name) = json.pointer("/result/serverInfo/name") {
-                            return Ok(format!("✅ Server reachable: {} (HTTP {})", name.as_str().unwrap_or("unknown"), status_code));
-                        }
-                    }
-                    // Try to parse SSE response for server info
-                    for line in body.lines() {
-                        if line.starts_with("data:") {
-                            let data = line.trim_start_matches("data:").trim();
-                            if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
-                                if let Some(name) = json.pointer("/result/serverInfo/name") {
-                                    return Ok(format!("✅ Server reachable: {} (HTTP {})", name.as_str().unwrap_or("unknown"), status_code));
-                                }
-                            }
-                        }
+        let configs = load_mcp_config_file()?;
+        let server = configs.get(&target).or_else(|| {
+            configs.values().find(|c| matches!(&c.transport, McpTransport::Http { url, .. } if *url == target))
+        });
+        let (url, server_headers) = match server {
+            Some(server) => match expand_mcp_config(&target, server, &lookup_env_reference)?.transport {
+                McpTransport::Http { url, headers } => (url, headers),
+                McpTransport::Stdio { .. } => (expand_env_refs(&target, &lookup_env_reference)?, HashMap::new()),
+            },
+            None => (expand_env_refs(&target, &lookup_env_reference)?, HashMap::new()),
+        };
+        let headers = resolve_mcp_headers(headers.as_ref(), &defaults, &server_headers);
+        if !headers.is_empty() {
+            info!("[MCP Test] Request headers: {}", headers_for_log(&headers));
+        }
+
+        match mcp_http_post(&url, MCP_INITIALIZE_REQUEST, &headers, timeout, Some(1)).await {
+            Ok((status, _, body)) if (200..300).contains(&status) => {
+                let mut result = match parse_http_initialize_body(&body) {
+                    Some(init) => {
+                        let mut r = mcp_test_result_from_initialize(&init);
+                        r.message = format!("✅ Server reachable: {} (HTTP {})", r.server_name.as_deref().unwrap_or("unknown"), status);
+                        r
                     }
-                    Ok(format!("✅ Server reachable (HTTP {})", status_code))
-                } else {
-                    Err(format!("❌ Server returned HTTP {}", status_code))
-                }
+                    None => McpTestResult {
+                        message: format!("✅ Server reachable (HTTP {})", status),
+                        ..Default::default()
+                    },
+                };
+                result.http_status = Some(status);
+                Ok(result)
             }
-            Err(e) => Err(format!("Failed to test URL: {}", e))
+            Ok((status, _, _)) => Err(format!("❌ Server returned HTTP {}", status)),
+            Err(e) => Err(format!("Failed to test URL: {}", e)),
         }
     } else {
-        // Local stdio MCP: spawn the command directly with proper args
+        // Local stdio MCP: spawn the command and wait for its initialize response
         let cmd_name = command.unwrap_or(target.clone());
-        let cmd_args = args.unwrap_or_default();
-        
-        info!("[MCP Test] Spawning: {} {:?}", cmd_name, cmd_args);
+        let cmd_args = args.unwrap_or_default()
+            .iter()
+            .map(|a| expand_env_refs(a, &lookup_env_reference))
+            .collect::<Result<Vec<String>, String>>()?;
+
+        // For a configured server (target is its name), launch it with its env map
+        let env = match load_mcp_config_file()?.get(&target) {
+            Some(server) => match expand_mcp_config(&target, server, &lookup_env_reference)?.transport {
+                McpTransport::Stdio { env, .. } => env,
+                McpTransport::Http { .. } => HashMap::new(),
+            },
+            None => HashMap::new(),
+        };
+        let timeout = resolve_mcp_test_timeout(timeout_ms, &defaults, 10_000);
+        let attempts = retries.unwrap_or(0) + 1;
+
+        let mut outcome = StdioProbe::Running;
+        for attempt in 1..=attempts {
+            info!("[MCP Test] Spawning (attempt {}/{}): {} {:?}", attempt, attempts, cmd_name, cmd_args);
+            outcome = probe_stdio_server(&cmd_name, &cmd_args, &env, timeout).await?;
+            if matches!(outcome, StdioProbe::Initialized(_)) {
+                break;
+            }
+        }
+
+        let command_line = format!("{} {}", cmd_name, cmd_args.join(" "));
+        match outcome {
+            StdioProbe::Initialized(init) => {
+                let mut result = mcp_test_result_from_initialize(&init);
+                let name = result.server_name.as_deref().unwrap_or("unknown");
+                let version = result.server_version.as_deref().unwrap_or("");
+                result.message = format!("✅ Server responded to initialize: {} {}", name, version).trim_end().to_string();
+                Ok(result)
+            }
+            StdioProbe::Running => Ok(McpTestResult {
+                message: format!(
+                    "⚠️ Process is running but did not respond to initialize within {}ms\nCommand: {}",
+                    timeout, command_line
+                ),
+                ..Default::default()
+            }),
+            StdioProbe::Exited { status, stderr } => Err(format!(
+                "❌ Server exited ({}) without responding to initialize\n{}\nCommand: {}",
+                status, stderr.trim(), command_line
+            )),
+        }
+    }
+}
+
+/// JSON-RPC initialize request sent by MCP tests
+const MCP_INITIALIZE_REQUEST: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"test","version":"1.0"}}}"#;
+
+/// Outcome of probing a stdio MCP server
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StdioProbe {
+    /// The server answered initialize (the JSON-RPC result object)
+    Initialized(Value),
+    /// The process stayed up but never answered before the deadline
+    Running,
+    /// The process exited before answering
+    Exited { status: String, stderr: String },
+}
+
+/// Parse one line of server output as the JSON-RPC reply to request `id`:
+/// `Ok(result)` or `Err(error)`. Anything else (logs, notifications, other ids) is None.
+pub(crate) fn parse_jsonrpc_reply(line: &str, id: i64) -> Option<Result<Value, Value>> {
+    let msg: Value = serde_json::from_str(line.trim()).ok()?;
+    if msg.get("id").and_then(|v| v.as_i64()) != Some(id) {
+        return None;
+    }
+    match (msg.get("result"), msg.get("error")) {
+        (Some(result), _) => Some(Ok(result.clone())),
+        (None, Some(error)) => Some(Err(error.clone())),
+        (None, None) => None,
+    }
+}
+
+/// Extract the result of our initialize request (id 1) from one line of server output
+pub(crate) fn parse_initialize_response(line: &str) -> Option<Value> {
+    parse_jsonrpc_reply(line, 1)?.ok().filter(|r| r.is_object())
+}
+
+/// Extract the initialize result from an HTTP response body (plain JSON or SSE "data:" lines)
+pub(crate) fn parse_http_initialize_body(body: &str) -> Option<Value> {
+    parse_initialize_response(body).or_else(|| {
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .find_map(parse_initialize_response)
+    })
+}
+
+/// Build a test result from an initialize result object
+pub(crate) fn mcp_test_result_from_initialize(init: &Value) -> McpTestResult {
+    let text = |pointer: &str| init.pointer(pointer).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let mut capabilities: Vec<String> = init.get("capabilities")
+        .and_then(|v| v.as_object())
+        .map(|caps| caps.keys().cloned().collect())
+        .unwrap_or_default();
+    capabilities.sort();
+
+    McpTestResult {
+        handshake: true,
+        message: String::new(),
+        protocol_version: text("/protocolVersion"),
+        server_name: text("/serverInfo/name"),
+        server_version: text("/serverInfo/version"),
+        capabilities,
+        http_status: None,
+    }
+}
+
+/// How a stdio server answered (or didn't answer) a request
+enum StdioReply {
+    Result(Value),
+    Error(Value),
+    Closed,
+    Timeout,
+}
+
+/// A spawned stdio MCP server speaking newline-delimited JSON-RPC
+struct StdioSession {
+    child: tokio::process::Child,
+    stdin: Option<tokio::process::ChildStdin>,
+    lines: tokio::io::Lines<tokio::io::BufReader<tokio::process::ChildStdout>>,
+}
+
+impl StdioSession {
+    fn spawn(cmd_name: &str, cmd_args: &[String], env: &HashMap<String, String>) -> Result<Self, String> {
+        use tokio::io::AsyncBufReadExt;
 
-        let extended_path = shell::get_extended_path();
-        
         // On Windows, use cmd /c to resolve .cmd files (npx.cmd, node.cmd, etc.)
         #[cfg(windows)]
         let mut cmd = {
-            let mut c = std::process::Command::new("cmd");
-            let mut full_args = vec!["/c".to_string(), cmd_name.clone()];
-            full_args.extend(cmd_args.clone());
-            c.args(&full_args);
+            let mut c = tokio::process::Command::new("cmd");
+            c.arg("/c").arg(cmd_name).args(cmd_args);
+            c.creation_flags(0x08000000);
             c
         };
         #[cfg(not(windows))]
         let mut cmd = {
-            let mut c = std::process::Command::new(&cmd_name);
-            c.args(&cmd_args);
+            let mut c = tokio::process::Command::new(cmd_name);
+            c.args(cmd_args);
             c
         };
 
         cmd.stdin(std::process::Stdio::piped())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .env("PATH", &extended_path);
+            .env("PATH", shell::get_extended_path())
+            .envs(env)
+            .kill_on_drop(true);
+
+        let mut child = cmd.spawn()
+            .map_err(|e| format!("❌ Failed to start server: {}\nCommand: {} {}", e, cmd_name, cmd_args.join(" ")))?;
+
+        // Keep stdin open so the server doesn't see EOF
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take().ok_or("Failed to capture server stdout")?;
+        Ok(Self { child, stdin, lines: tokio::io::BufReader::new(stdout).lines() })
+    }
+
+    async fn send(&mut self, message: &str) {
+        use tokio::io::AsyncWriteExt;
+        if let Some(stdin) = self.stdin.as_mut() {
+            let _ = stdin.write_all(format!("{}\n", message).as_bytes()).await;
+            let _ = stdin.flush().await;
+        }
+    }
+
+    /// Read stdout until the reply to `id` arrives, stdout closes or the deadline passes
+    async fn wait_for(&mut self, id: i64, deadline: tokio::time::Instant) -> StdioReply {
+        loop {
+            match tokio::time::timeout_at(deadline, self.lines.next_line()).await {
+                Ok(Ok(Some(line))) => match parse_jsonrpc_reply(&line, id) {
+                    Some(Ok(result)) => return StdioReply::Result(result),
+                    Some(Err(error)) => return StdioReply::Error(error),
+                    None => {} // Logs or other notifications, keep reading
+                },
+                Ok(Ok(None)) | Ok(Err(_)) => return StdioReply::Closed,
+                Err(_) => return StdioReply::Timeout,
+            }
+        }
+    }
+
+    /// Exit status and stderr of a server whose stdout closed
+    async fn exit_details(&mut self) -> (String, String) {
+        use tokio::io::AsyncReadExt;
+        let status = tokio::time::timeout(std::time::Duration::from_secs(2), self.child.wait()).await
+            .ok()
+            .and_then(|r| r.ok())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "stdout closed".to_string());
+        let mut stderr = String::new();
+        if let Some(mut err) = self.child.stderr.take() {
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(1), err.read_to_string(&mut stderr)).await;
+        }
+        (status, stderr)
+    }
+
+    async fn shutdown(mut self) {
+        drop(self.stdin.take());
+        let _ = self.child.kill().await;
+    }
+}
+
+/// Spawn a stdio MCP server, send initialize and read stdout until it answers, exits or `timeout_ms` passes
+async fn probe_stdio_server(
+    cmd_name: &str,
+    cmd_args: &[String],
+    env: &HashMap<String, String>,
+    timeout_ms: u64,
+) -> Result<StdioProbe, String> {
+    let mut session = StdioSession::spawn(cmd_name, cmd_args, env)?;
+    session.send(MCP_INITIALIZE_REQUEST).await;
+
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout_ms);
+    let outcome = match session.wait_for(1, deadline).await {
+        StdioReply::Result(result) if result.is_object() => StdioProbe::Initialized(result),
+        StdioReply::Closed => {
+            let (status, stderr) = session.exit_details().await;
+            StdioProbe::Exited { status, stderr }
+        }
+        _ => StdioProbe::Running,
+    };
+
+    session.shutdown().await;
+    Ok(outcome)
+}
+
+/// Upper bound on tools/list pages fetched from one server
+const MCP_TOOLS_MAX_PAGES: usize = 20;
+
+/// Parse one tools/list result page into tools and the next cursor
+pub(crate) fn parse_tools_page(result: &Value) -> (Vec<McpTool>, Option<String>) {
+    let tools = result.get("tools")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter()
+            .filter_map(|t| {
+                let name = t.get("name")?.as_str()?.to_string();
+                let description = t.get("description").and_then(|v| v.as_str()).map(|s| s.trim().to_string());
+                Some(McpTool { name, description })
+            })
+            .collect())
+        .unwrap_or_default();
+    let cursor = result.get("nextCursor")
+        .and_then(|v| v.as_str())
+        .filter(|c| !c.is_empty())
+        .map(|c| c.to_string());
+    (tools, cursor)
+}
+
+/// Whether an initialize result advertises the tools capability
+pub(crate) fn supports_tools(init: &Value) -> bool {
+    init.pointer("/capabilities/tools").is_some()
+}
+
+fn tools_list_request(id: i64, cursor: Option<&str>) -> String {
+    let params = match cursor {
+        Some(c) => json!({ "cursor": c }),
+        None => json!({}),
+    };
+    json!({ "jsonrpc": "2.0", "id": id, "method": "tools/list", "params": params }).to_string()
+}
+
+const MCP_INITIALIZED_NOTIFICATION: &str = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+
+/// Tool list for a server that can't or won't list tools
+fn empty_tool_list(server: &str, note: String) -> McpToolList {
+    McpToolList { server: server.to_string(), tools: Vec::new(), note: Some(note) }
+}
+
+/// Run initialize + tools/list against a stdio server
+async fn list_stdio_tools(
+    name: &str,
+    command: &str,
+    args: &[String],
+    env: &HashMap<String, String>,
+    timeout_ms: u64,
+) -> Result<McpToolList, String> {
+    let mut session = StdioSession::spawn(command, args, env)?;
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout_ms);
+
+    session.send(MCP_INITIALIZE_REQUEST).await;
+    let init = match session.wait_for(1, deadline).await {
+        StdioReply::Result(init) => init,
+        StdioReply::Error(e) => {
+            session.shutdown().await;
+            return Err(format!("Server rejected initialize: {}", e));
+        }
+        StdioReply::Closed => {
+            let (status, stderr) = session.exit_details().await;
+            return Err(format!("Server exited ({}) before initializing\n{}", status, stderr.trim()));
+        }
+        StdioReply::Timeout => {
+            session.shutdown().await;
+            return Err(format!("Server did not respond to initialize within {}ms", timeout_ms));
+        }
+    };
+
+    if !supports_tools(&init) {
+        session.shutdown().await;
+        return Ok(empty_tool_list(name, "Server does not advertise the tools capability".to_string()));
+    }
+    session.send(MCP_INITIALIZED_NOTIFICATION).await;
+
+    let mut tools = Vec::new();
+    let mut cursor: Option<String> = None;
+    for page in 0..MCP_TOOLS_MAX_PAGES {
+        let id = 2 + page as i64;
+        session.send(&tools_list_request(id, cursor.as_deref())).await;
+        match session.wait_for(id, deadline).await {
+            StdioReply::Result(result) => {
+                let (page_tools, next) = parse_tools_page(&result);
+                tools.extend(page_tools);
+                cursor = next;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+            StdioReply::Error(e) => {
+                session.shutdown().await;
+                return Ok(empty_tool_list(name, format!("Server returned an error for tools/list: {}", e)));
+            }
+            StdioReply::Closed | StdioReply::Timeout => {
+                session.shutdown().await;
+                return Err(format!("Server did not answer tools/list within {}ms", timeout_ms));
+            }
+        }
+    }
+
+    session.shutdown().await;
+    Ok(McpToolList { server: name.to_string(), tools, note: None })
+}
+
+/// POST a JSON-RPC message to an HTTP MCP server. Returns (status, lowercase headers, body).
+/// SSE responses are read only until the reply to `reply_id` arrives (streamable HTTP servers
+/// may keep the stream open afterwards); with no `reply_id` an SSE body is not read at all.
+pub(crate) async fn mcp_http_post(
+    url: &str,
+    body: &str,
+    headers: &HashMap<String, String>,
+    timeout_ms: u64,
+    reply_id: Option<i64>,
+) -> Result<(u16, HashMap<String, String>, String), String> {
+    let request_err = |e: reqwest::Error| {
+        if e.is_timeout() {
+            format!("Request to {} timed out after {}ms", url, timeout_ms)
+        } else if e.is_connect() {
+            format!("Could not connect to {}: {}", url, e)
+        } else {
+            format!("Request to {} failed: {}", url, e)
+        }
+    };
+
+    let client = build_http_client(timeout_ms)?;
+
+    let mut request = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("Accept", "text/event-stream, application/json")
+        .body(body.to_string());
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+
+    let mut response = request.send().await.map_err(request_err)?;
+    let status = response.status().as_u16();
+    let response_headers: HashMap<String, String> = response.headers().iter()
+        .filter_map(|(k, v)| Some((k.as_str().to_lowercase(), v.to_str().ok()?.to_string())))
+        .collect();
+
+    let is_sse = response_headers.get("content-type")
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    let body = if !is_sse {
+        response.text().await.map_err(request_err)?
+    } else if let Some(id) = reply_id {
+        let mut raw = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(request_err)? {
+            raw.extend_from_slice(&chunk);
+            if parse_http_reply(&String::from_utf8_lossy(&raw), id).is_some() {
+                break;
+            }
+        }
+        String::from_utf8_lossy(&raw).into_owned()
+    } else {
+        String::new()
+    };
+
+    Ok((status, response_headers, body))
+}
+
+/// Extract the JSON-RPC reply to `id` from an HTTP response body (plain JSON or SSE "data:" lines)
+fn parse_http_reply(body: &str, id: i64) -> Option<Result<Value, Value>> {
+    parse_jsonrpc_reply(body, id).or_else(|| {
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .find_map(|data| parse_jsonrpc_reply(data, id))
+    })
+}
+
+/// Run initialize + tools/list against an HTTP server
+async fn list_http_tools(name: &str, url: &str, headers: &HashMap<String, String>, timeout_ms: u64) -> Result<McpToolList, String> {
+    let (status, response_headers, body) = mcp_http_post(url, MCP_INITIALIZE_REQUEST, headers, timeout_ms, Some(1)).await?;
+    if !(200..300).contains(&status) {
+        return Err(format!("Server returned HTTP {} for initialize", status));
+    }
+    let init = match parse_http_reply(&body, 1) {
+        Some(Ok(init)) => init,
+        Some(Err(e)) => return Err(format!("Server rejected initialize: {}", e)),
+        None => return Err("Server did not return an initialize result".to_string()),
+    };
+    if !supports_tools(&init) {
+        return Ok(empty_tool_list(name, "Server does not advertise the tools capability".to_string()));
+    }
+
+    // Streamable HTTP servers hand out a session id that must accompany later requests
+    let mut headers = headers.clone();
+    if let Some(session) = response_headers.get("mcp-session-id") {
+        headers.insert("Mcp-Session-Id".to_string(), session.clone());
+    }
+    let _ = mcp_http_post(url, MCP_INITIALIZED_NOTIFICATION, &headers, timeout_ms, None).await;
+
+    let mut tools = Vec::new();
+    let mut cursor: Option<String> = None;
+    for page in 0..MCP_TOOLS_MAX_PAGES {
+        let id = 2 + page as i64;
+        let (status, _, body) = mcp_http_post(url, &tools_list_request(id, cursor.as_deref()), &headers, timeout_ms, Some(id)).await?;
+        if !(200..300).contains(&status) {
+            return Err(format!("Server returned HTTP {} for tools/list", status));
+        }
+        match parse_http_reply(&body, id) {
+            Some(Ok(result)) => {
+                let (page_tools, next) = parse_tools_page(&result);
+                tools.extend(page_tools);
+                cursor = next;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+            Some(Err(e)) => return Ok(empty_tool_list(name, format!("Server returned an error for tools/list: {}", e))),
+            None => return Err("Server did not return a tools/list result".to_string()),
+        }
+    }
+
+    Ok(McpToolList { server: name.to_string(), tools, note: None })
+}
+
+/// List the tools exposed by a configured MCP server (initialize + tools/list).
+/// Timeout and default HTTP headers come from the persisted MCP test defaults;
+/// an HTTP server's own headers are added on top.
+#[command]
+pub async fn list_mcp_tools(name: String) -> Result<McpToolList, String> {
+    info!("[MCP Tools] Listing tools for {}", name);
+    let configs = load_mcp_config_file()?;
+    let server = configs.get(&name)
+        .ok_or_else(|| format!("MCP server '{}' is not configured", name))?;
+    let server = expand_mcp_config(&name, server, &lookup_env_reference)?;
 
-        #[cfg(windows)]
-        {
-            use std::os::windows::process::CommandExt;
-            cmd.creation_flags(0x08000000);
-        }
+    let defaults = load_openclaw_config()
+        .map(|c| mcp_test_defaults_from_config(&c))
+        .unwrap_or_default();
+    let timeout = resolve_mcp_test_timeout(None, &defaults, 15_000);
 
-        match cmd.spawn() {
-            Ok(mut child) => {
-                // Send MCP initialize request via stdin
-                if let Some(ref mut stdin) = child.stdin {
-                    use std::io::Write;
-                    let init_msg = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"test","version":"1.0"}}}"#;
-                    let _ = writeln!(stdin, "Content-Length: {}\r\n\r\n{}", init_msg.len(), init_msg);
-                }
-                
-                // Wait briefly then check
-                std::thread::sleep(std::time::Duration::from_millis(3000));
-                
-                match child.try_wait() {
-                    Ok(Some(status)) => {
-                        // Process exited — read stderr for error info
-                        let stderr = child.stderr.take().map(|mut s| {
-                            let mut buf = String::new();
-                            use std::io::Read;
-                            let _ = s.read_to_string(&mut buf);
-                            buf
-                        }).unwrap_or_default();
-                        
-                        if status.success() {
-                            Ok("✅ Server process started and exited cleanly".to_string())
-                        } else {
-                            Err(format!("❌ Server exited with {}\n{}", status, stderr.trim()))
-                        }
-                    }
-                    Ok(None) => {
-                        // Still running — good! Kill it and report success
-                        let _ = child.kill();
-                        Ok(format!("✅ Server is running (process started successfully)\nCommand: {} {}", cmd_name, cmd_args.join(" ")))
-                    }
-                    Err(e) => {
-                        let _ = child.kill();
-                        Err(format!("Failed to check process: {}", e))
-                    }
-                }
+    let list = match server.transport {
+        McpTransport::Http { url, headers } => {
+            let headers = resolve_mcp_headers(None, &defaults, &headers);
+            if !headers.is_empty() {
+                info!("[MCP Tools] Request headers: {}", headers_for_log(&headers));
             }
-            Err(e) => {
-                Err(format!("❌ Failed to start server: {}\nCommand: {} {}", e, cmd_name, cmd_args.join(" ")))
+            list_http_tools(&name, &url, &headers, timeout).await?
+        }
+        McpTransport::Stdio { command, args, env } => {
+            if command.is_empty() {
+                return Err(format!("MCP server '{}' has no command", name));
             }
+            list_stdio_tools(&name, &command, &args, &env, timeout).await?
         }
-    }
+    };
+
+    info!("[MCP Tools] {} exposes {} tools", list.server, list.tools.len());
+    Ok(list)
 }
 
 // ============ Legacy Compatibility ============
@@ -1488,7 +4964,67 @@ pub async fn get_ai_providers() -> Result<Vec<crate::models::AIProviderOption>,
 
 // ============ Channel Configuration ============
 
-/// Get channel configuration - read from openclaw.json and env file
+/// Supported channel types and their test-only fields, which live in the env file
+/// as OPENCLAW_<CHANNEL>_<FIELD> instead of openclaw.json
+const CHANNEL_TEST_FIELDS: &[(&str, &[&str])] = &[
+    ("telegram", &["userId"]),
+    ("discord", &["testChannelId"]),
+    ("slack", &["testChannelId"]),
+    ("feishu", &["testChatId"]),
+    ("whatsapp", &[]),
+    ("imessage", &[]),
+    ("wechat", &[]),
+    ("dingtalk", &[]),
+];
+
+/// Whether a channel config key is a test-only field of any channel
+fn is_channel_test_field(key: &str) -> bool {
+    CHANNEL_TEST_FIELDS.iter().any(|(_, fields)| fields.contains(&key))
+}
+
+/// Env file key of a channel's test field
+fn channel_env_key(channel_id: &str, field: &str) -> String {
+    format!("OPENCLAW_{}_{}", channel_id.to_uppercase(), field.to_uppercase())
+}
+
+/// Describe a channel field for display. Secret values are masked unless they are
+/// a ${VAR} reference (which holds no secret itself).
+pub(crate) fn channel_field(key: &str, value: &Value, source: FieldSource) -> ChannelField {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let value_masked = if secrets::is_secret_key(key) && parse_env_reference(&text).is_none() {
+        mask_secret(&text)
+    } else {
+        text
+    };
+    ChannelField { key: key.to_string(), value_masked, source }
+}
+
+/// Mask a channel config value for display: a secret string is masked unless it is a
+/// ${VAR} reference, and secrets nested in objects/arrays are masked too
+pub(crate) fn mask_channel_value(key: &str, value: &Value) -> Value {
+    match value {
+        Value::String(s) if secrets::is_secret_key(key) && parse_env_reference(s).is_none() => json!(mask_secret(s)),
+        Value::Object(_) | Value::Array(_) => mask_secrets_for_log(value),
+        other => other.clone(),
+    }
+}
+
+/// Whether an incoming channel value is just the masked form of the stored secret
+/// (the UI sent back what get_channels_config returned), so the stored value must be kept
+pub(crate) fn is_masked_echo(key: &str, incoming: &Value, existing: Option<&Value>) -> bool {
+    match (incoming.as_str(), existing.and_then(|v| v.as_str())) {
+        (Some(incoming), Some(existing)) => {
+            secrets::is_secret_key(key) && incoming != existing && mask_channel_value(key, &json!(existing)) == json!(incoming)
+        }
+        _ => false,
+    }
+}
+
+/// Get channel configuration - read from openclaw.json and env file.
+/// Secret values are masked; use get_channel_secret to read the one being edited.
 #[command]
 pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
     info!("[Channel Config] Getting channel configuration list...");
@@ -1500,19 +5036,8 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
 
     let mut channels = Vec::new();
 
-    // List of supported channel types and their test fields
-    let channel_types = vec![
-        ("telegram", "telegram", vec!["userId"]),
-        ("discord", "discord", vec!["testChannelId"]),
-        ("slack", "slack", vec!["testChannelId"]),
-        ("feishu", "feishu", vec!["testChatId"]),
-        ("whatsapp", "whatsapp", vec![]),
-        ("imessage", "imessage", vec![]),
-        ("wechat", "wechat", vec![]),
-        ("dingtalk", "dingtalk", vec![]),
-    ];
-
-    for (channel_id, channel_type, test_fields) in channel_types {
+    for &(channel_id, test_fields) in CHANNEL_TEST_FIELDS {
+        let channel_type = channel_id;
         let channel_config = channels_obj.get(channel_id);
 
         let enabled = channel_config
@@ -1525,7 +5050,7 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
             if let Some(obj) = cfg.as_object() {
                 obj.iter()
                     .filter(|(k, _)| *k != "enabled") // Exclude enabled field
-                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .map(|(k, v)| (k.clone(), mask_channel_value(k, v)))
                     .collect()
             } else {
                 HashMap::new()
@@ -1534,21 +5059,28 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
             HashMap::new()
         };
 
+        // Clean up any legacy 'pairing' or 'allowlist' keys that shouldn't be here
+        config_map.remove("pairing");
+        config_map.remove("allowlist");
+
+        let mut fields: Vec<ChannelField> = channel_config
+            .and_then(|c| c.as_object())
+            .into_iter()
+            .flatten()
+            .filter(|(key, _)| config_map.contains_key(*key))
+            .map(|(key, value)| channel_field(key, value, FieldSource::Config))
+            .collect();
+
         // Read test fields from env file
         for field in test_fields {
-            let env_key = format!(
-                "OPENCLAW_{}_{}",
-                channel_id.to_uppercase(),
-                field.to_uppercase()
-            );
+            let env_key = channel_env_key(channel_id, field);
             if let Some(value) = file::read_env_value(&env_path, &env_key) {
+                fields.retain(|f| f.key != *field);
+                fields.push(channel_field(field, &json!(value), FieldSource::Env));
                 config_map.insert(field.to_string(), json!(value));
             }
         }
-
-        // Clean up any legacy 'pairing' or 'allowlist' keys that shouldn't be here
-        config_map.remove("pairing");
-        config_map.remove("allowlist");
+        fields.sort_by(|a, b| a.key.cmp(&b.key));
 
         // Determine if configured (has any non-empty configuration items)
         let has_config = !config_map.is_empty() || enabled;
@@ -1558,6 +5090,7 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
             channel_type: channel_type.to_string(),
             enabled: has_config,
             config: config_map,
+            fields,
         });
     }
 
@@ -1568,9 +5101,26 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
     Ok(channels)
 }
 
+/// Unmasked value of one channel field (from openclaw.json, or the env file for test fields),
+/// for the field currently being edited
+#[command]
+pub async fn get_channel_secret(channel_id: String, key: String) -> Result<Option<String>, String> {
+    info!("[Channel Config] Reading {}.{} (unmasked)", channel_id, key);
+    let config = read_openclaw_config()?;
+    let pointer = format!("/channels/{}/{}", escape_pointer_token(&channel_id), escape_pointer_token(&key));
+    if let Some(value) = config.pointer(&pointer).and_then(|v| v.as_str()) {
+        return Ok(Some(value.to_string()));
+    }
+    if is_channel_test_field(&key) {
+        return Ok(file::read_env_value(&platform::get_env_file_path(), &channel_env_key(&channel_id, &key)));
+    }
+    Ok(None)
+}
+
 /// Save channel configuration - save to openclaw.json
 #[command]
 pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!(
         "[Save Channel Config] Saving channel configuration: {} ({})",
         channel.id, channel.channel_type
@@ -1601,23 +5151,23 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
         config["plugins"]["entries"] = json!({});
     }
 
-    // These fields are only for testing, not saved to openclaw.json, but saved to env file
-    let test_only_fields = vec!["userId", "testChatId", "testChannelId"];
-
     // Update channels configuration - MERGE with existing
     if let Some(existing_channel) = config["channels"].get_mut(&channel.id).and_then(|v| v.as_object_mut()) {
         existing_channel.insert("enabled".to_string(), json!(true));
         
         // Clean up legacy invalid keys
-        existing_channel.remove("pairing");
-        existing_channel.remove("allowlist");
+        existing_channel.shift_remove("pairing");
+        existing_channel.shift_remove("allowlist");
 
         for (key, value) in &channel.config {
-            if test_only_fields.contains(&key.as_str()) {
-                let env_key = format!("OPENCLAW_{}_{}", channel.id.to_uppercase(), key.to_uppercase());
+            // Test-only fields are saved to the env file, not openclaw.json
+            if is_channel_test_field(key) {
+                let env_key = channel_env_key(&channel.id, key);
                 if let Some(val_str) = value.as_str() {
                     let _ = file::set_env_value(&env_path, &env_key, val_str);
                 }
+            } else if is_masked_echo(key, value, existing_channel.get(key)) {
+                debug!("[Save Channel Config] Keeping stored {} (masked value unchanged)", key);
             } else {
                  existing_channel.insert(key.clone(), value.clone());
             }
@@ -1626,8 +5176,9 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
         let mut channel_obj = json!({ "enabled": true });
 
         for (key, value) in &channel.config {
-            if test_only_fields.contains(&key.as_str()) {
-                let env_key = format!("OPENCLAW_{}_{}", channel.id.to_uppercase(), key.to_uppercase());
+            // Test-only fields are saved to the env file, not openclaw.json
+            if is_channel_test_field(key) {
+                let env_key = channel_env_key(&channel.id, key);
                 if let Some(val_str) = value.as_str() {
                     let _ = file::set_env_value(&env_path, &env_key, val_str);
                 }
@@ -1640,12 +5191,12 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
 
     // Cleanup legacy attempts
     if let Some(plugin_entry) = config["plugins"]["entries"].get_mut(&channel.id).and_then(|v| v.as_object_mut()) {
-        plugin_entry.remove("allowlist");
-        plugin_entry.remove("pairing");
+        plugin_entry.shift_remove("allowlist");
+        plugin_entry.shift_remove("pairing");
     }
     // Remove global allowlist (invalid at root level)
     if let Some(obj) = config.as_object_mut() {
-        obj.remove("allowlist");
+        obj.shift_remove("allowlist");
     }
 
     // Save configuration
@@ -1665,39 +5216,74 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
     }
 }
 
-/// Clear channel configuration - delete specified channel configuration from openclaw.json
-#[command]
-pub async fn clear_channel_config(channel_id: String) -> Result<String, String> {
-    info!("[Clear Channel Config] Clearing channel configuration: {}", channel_id);
-
-    let mut config = load_openclaw_config()?;
-    let env_path = platform::get_env_file_path();
+/// Remove a channel from channels, plugins.allow and plugins.entries.
+/// Returns a description of every change.
+pub(crate) fn clear_channel_in(config: &mut Value, channel_id: &str) -> Vec<String> {
+    let mut changes = Vec::new();
 
     // Delete channel from channels object
     if let Some(channels) = config.get_mut("channels").and_then(|v| v.as_object_mut()) {
-        channels.remove(&channel_id);
-        info!("[Clear Channel Config] Deleted from channels: {}", channel_id);
+        if channels.shift_remove(channel_id).is_some() {
+            changes.push(format!("Remove channels.{}", channel_id));
+        }
     }
 
     // Delete from plugins.allow array
     if let Some(allow_arr) = config.pointer_mut("/plugins/allow").and_then(|v| v.as_array_mut()) {
-        allow_arr.retain(|v| v.as_str() != Some(&channel_id));
-        info!("[Clear Channel Config] Deleted from plugins.allow: {}", channel_id);
+        let before = allow_arr.len();
+        allow_arr.retain(|v| v.as_str() != Some(channel_id));
+        if allow_arr.len() != before {
+            changes.push(format!("Remove {} from plugins.allow", channel_id));
+        }
     }
 
     // Delete from plugins.entries
     if let Some(entries) = config.pointer_mut("/plugins/entries").and_then(|v| v.as_object_mut()) {
-        entries.remove(&channel_id);
-        info!("[Clear Channel Config] Deleted from plugins.entries: {}", channel_id);
+        if entries.shift_remove(channel_id).is_some() {
+            changes.push(format!("Remove plugins.entries.{}", channel_id));
+        }
+    }
+
+    changes
+}
+
+/// Env file keys that belong to a channel and are present in `env_content`: its known
+/// test fields plus anything else under the OPENCLAW_<CHANNEL>_ prefix
+pub(crate) fn channel_env_keys(channel_id: &str, env_content: &str) -> Vec<String> {
+    let prefix = format!("OPENCLAW_{}_", channel_id.to_uppercase());
+    let present: Vec<String> = file::parse_env_exports(env_content).into_iter().map(|(key, _)| key).collect();
+
+    let mut keys: Vec<String> = CHANNEL_TEST_FIELDS
+        .iter()
+        .filter(|(id, _)| *id == channel_id)
+        .flat_map(|(_, fields)| fields.iter().map(|field| channel_env_key(channel_id, field)))
+        .filter(|key| present.contains(key))
+        .collect();
+    for key in present {
+        if key.starts_with(&prefix) && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// Clear channel configuration - delete specified channel configuration from openclaw.json
+#[command]
+pub async fn clear_channel_config(channel_id: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Clear Channel Config] Clearing channel configuration: {}", channel_id);
+
+    let mut config = load_openclaw_config()?;
+    let env_path = platform::get_env_file_path();
+
+    for change in clear_channel_in(&mut config, &channel_id) {
+        info!("[Clear Channel Config] {}", change);
     }
 
     // Clear related environment variables
-    let env_prefixes = vec![
-        format!("OPENCLAW_{}_USERID", channel_id.to_uppercase()),
-        format!("OPENCLAW_{}_TESTCHATID", channel_id.to_uppercase()),
-        format!("OPENCLAW_{}_TESTCHANNELID", channel_id.to_uppercase()),
-    ];
-    for env_key in env_prefixes {
+    let env_content = file::read_file(&env_path).unwrap_or_default();
+    for env_key in channel_env_keys(&channel_id, &env_content) {
+        info!("[Clear Channel Config] Removing {} from the env file", env_key);
         let _ = file::remove_env_value(&env_path, &env_key);
     }
 
@@ -1714,6 +5300,111 @@ pub async fn clear_channel_config(channel_id: String) -> Result<String, String>
     }
 }
 
+/// List what clear_channel_config would change, without writing anything
+#[command]
+pub async fn preview_clear_channel_config(channel_id: String) -> Result<Vec<String>, String> {
+    let mut config = load_openclaw_config()?;
+    let mut changes = clear_channel_in(&mut config, &channel_id);
+
+    let env_content = file::read_file(&platform::get_env_file_path()).unwrap_or_default();
+    for env_key in channel_env_keys(&channel_id, &env_content) {
+        changes.push(format!("Remove {} from the env file", env_key));
+    }
+    Ok(changes)
+}
+
+/// Describe a JSON value's type for error messages
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// List channels whose value is not an object (these are hidden from the UI)
+pub(crate) fn find_malformed_channels(config: &Value) -> Vec<String> {
+    match config.get("channels") {
+        None | Some(Value::Object(_)) => {}
+        Some(other) => return vec![format!("channels is {} instead of an object", json_type_name(other))],
+    }
+
+    config.get("channels")
+        .and_then(|v| v.as_object())
+        .map(|channels| channels.iter()
+            .filter(|(_, v)| !v.is_object())
+            .map(|(id, v)| format!("Channel '{}' is {} instead of an object", id, json_type_name(v)))
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Coerce or remove malformed channel entries. A boolean becomes `{ "enabled": <bool> }`;
+/// anything else that is not an object is removed. Returns a description of each change.
+pub(crate) fn repair_channels_in(config: &mut Value) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    if let Some(other) = config.get("channels").filter(|v| !v.is_object()) {
+        changes.push(format!("Reset channels ({} instead of an object)", json_type_name(other)));
+        config["channels"] = json!({});
+        return changes;
+    }
+
+    if let Some(channels) = config.get_mut("channels").and_then(|v| v.as_object_mut()) {
+        let malformed: Vec<String> = channels.iter()
+            .filter(|(_, v)| !v.is_object())
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in malformed {
+            match channels.get(&id).and_then(|v| v.as_bool()) {
+                Some(enabled) => {
+                    channels.insert(id.clone(), json!({ "enabled": enabled }));
+                    changes.push(format!("Converted channel '{}' to {{ \"enabled\": {} }}", id, enabled));
+                }
+                None => {
+                    channels.shift_remove(&id);
+                    changes.push(format!("Removed malformed channel '{}'", id));
+                }
+            }
+        }
+    }
+    changes
+}
+
+/// Report channels with malformed (non-object) values
+#[command]
+pub async fn validate_channels() -> Result<Vec<String>, String> {
+    let config = load_openclaw_config()?;
+    let problems = find_malformed_channels(&config);
+    for p in &problems {
+        warn!("[Channels] {}", p);
+    }
+    Ok(problems)
+}
+
+/// Back up openclaw.json and repair malformed channel entries
+#[command]
+pub async fn repair_channels() -> Result<Vec<String>, String> {
+    let _config_lock = lock_config().await;
+    info!("[Channels] Repairing channels section...");
+    let mut config = load_openclaw_config()?;
+    let changes = repair_channels_in(&mut config);
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+
+    let backup_path = backup_openclaw_config()?;
+    info!("[Channels] Backed up configuration to {}", backup_path);
+
+    save_openclaw_config(&config)?;
+    for c in &changes {
+        info!("[Channels] {}", c);
+    }
+    Ok(changes)
+}
+
 // ============ Telegram Multi-Account Management ============
 
 /// Telegram account info for frontend
@@ -1835,6 +5526,7 @@ pub async fn get_telegram_accounts() -> Result<Vec<TelegramAccount>, String> {
 /// Save a Telegram bot account
 #[command]
 pub async fn save_telegram_account(account: TelegramAccount) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     // Normalize account ID to lowercase and replace spaces with dashes
     let account_id = account.id.to_lowercase().replace(' ', "-");
     info!("[Telegram Accounts] Saving account: {}", account_id);
@@ -1877,13 +5569,13 @@ pub async fn save_telegram_account(account: TelegramAccount) -> Result<String, S
             
             // Remove top-level single-bot fields
             if let Some(tg) = config["channels"]["telegram"].as_object_mut() {
-                tg.remove("botToken");
-                tg.remove("groupPolicy");
-                tg.remove("dmPolicy");
-                tg.remove("streamMode");
-                tg.remove("groups");
-                tg.remove("allowFrom");
-                tg.remove("groupAllowFrom");
+                tg.shift_remove("botToken");
+                tg.shift_remove("groupPolicy");
+                tg.shift_remove("dmPolicy");
+                tg.shift_remove("streamMode");
+                tg.shift_remove("groups");
+                tg.shift_remove("allowFrom");
+                tg.shift_remove("groupAllowFrom");
             }
         }
     }
@@ -2062,7 +5754,7 @@ pub async fn save_telegram_account(account: TelegramAccount) -> Result<String, S
         let current_primary = manager_config.pointer("/primaryBotAccount").and_then(|v| v.as_str());
         if current_primary == Some(account_id.as_str()) {
             if let Some(obj) = manager_config.as_object_mut() {
-                obj.remove("primaryBotAccount");
+                obj.shift_remove("primaryBotAccount");
             }
         }
     }
@@ -2074,7 +5766,7 @@ pub async fn save_telegram_account(account: TelegramAccount) -> Result<String, S
 
     // Clean up legacy location in openclaw.json
     if let Some(meta) = config.get_mut("meta").and_then(|v| v.as_object_mut()) {
-        meta.remove("primaryBotAccount");
+        meta.shift_remove("primaryBotAccount");
     }
 
     // Handle groups configuration
@@ -2154,7 +5846,7 @@ pub async fn save_telegram_account(account: TelegramAccount) -> Result<String, S
             .collect();
         for old_key in old_keys {
             info!("[Telegram Accounts] Removing old key '{}' (normalized to '{}')", old_key, account_id);
-            accts.remove(&old_key);
+            accts.shift_remove(&old_key);
         }
     }
 
@@ -2173,12 +5865,13 @@ pub async fn save_telegram_account(account: TelegramAccount) -> Result<String, S
 /// Delete a Telegram bot account
 #[command]
 pub async fn delete_telegram_account(account_id: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     let account_id = account_id.to_lowercase().replace(' ', "-");
     info!("[Telegram Accounts] Deleting account: {}", account_id);
     let mut config = load_openclaw_config()?;
 
     if let Some(accts) = config.pointer_mut("/channels/telegram/accounts").and_then(|v| v.as_object_mut()) {
-        accts.remove(&account_id);
+        accts.shift_remove(&account_id);
     }
 
     // Also clean up any bindings referencing this account
@@ -2190,7 +5883,162 @@ pub async fn delete_telegram_account(account_id: String) -> Result<String, Strin
     Ok(format!("Account '{}' deleted", account_id))
 }
 
-// ============ Feishu Plugin Management ============
+// ============ Plugin Management ============
+
+/// npm package of the Feishu channel plugin
+const FEISHU_PLUGIN_PACKAGE: &str = "@m1heng-clawd/feishu";
+
+/// Split "name@version" or "@scope/name@version" into name and version. The '@' that starts
+/// a scoped package name is not a separator.
+pub(crate) fn split_package_version(spec: &str) -> (&str, Option<&str>) {
+    let search_from = usize::from(spec.starts_with('@'));
+    match spec[search_from..].find('@') {
+        Some(i) => {
+            let at = search_from + i;
+            let version = &spec[at + 1..];
+            (&spec[..at], (!version.is_empty()).then_some(version))
+        }
+        None => (spec, None),
+    }
+}
+
+/// Whether a list column looks like a version ("0.1.2", "v1.0.0-beta.1"): dotted digits,
+/// optionally followed by a pre-release or build suffix
+fn looks_like_version(token: &str) -> bool {
+    let token = token.strip_prefix(['v', 'V']).unwrap_or(token);
+    let (core, suffix) = match token.find(['-', '+']) {
+        Some(i) => token.split_at(i),
+        None => (token, ""),
+    };
+    !core.is_empty()
+        && core.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        && suffix.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+}
+
+/// Name and version of a `plugins list` line in the "@scope/name@version", "name@version" or
+/// "name version" form. A separate version may sit in any later column, optionally in brackets;
+/// the version is None only when the line has none.
+pub(crate) fn parse_plugin_spec(line: &str) -> (String, Option<String>) {
+    let mut columns = line.split_whitespace();
+    let Some(first) = columns.next() else {
+        return (String::new(), None);
+    };
+    let (name, inline_version) = split_package_version(first);
+    let version = inline_version.map(str::to_string).or_else(|| {
+        columns
+            .map(|c| c.trim_matches(['(', ')', '[', ']', ',']))
+            .find(|c| looks_like_version(c))
+            .map(str::to_string)
+    });
+    (name.to_string(), version)
+}
+
+/// Plugins in the output of `openclaw plugins list`, one per non-empty line.
+/// Section headings such as "Plugins:" are skipped.
+pub(crate) fn parse_plugin_list(output: &str) -> Vec<PluginInfo> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter(|line| !line.split_whitespace().next().is_some_and(|first| first.ends_with(':')))
+        .map(|line| {
+            let (name, version) = parse_plugin_spec(line);
+            PluginInfo { name, version, line: line.to_string() }
+        })
+        .collect()
+}
+
+/// First plugin whose list line mentions `query` (case-insensitive)
+pub(crate) fn find_plugin<'a>(plugins: &'a [PluginInfo], query: &str) -> Option<&'a PluginInfo> {
+    let query = query.to_lowercase();
+    plugins.iter().find(|p| p.line.to_lowercase().contains(&query))
+}
+
+/// Turn the output of `plugins uninstall` and the plugin list afterwards into a result.
+/// A non-zero exit, or a plugin that is still listed, is an error carrying the CLI output.
+pub(crate) fn interpret_plugin_uninstall(
+    name: &str,
+    exit_code: Option<i32>,
+    stdout: &str,
+    stderr: &str,
+    list_after: &str,
+) -> Result<String, String> {
+    let output = format!("{}\n{}", stdout.trim(), stderr.trim()).trim().to_string();
+    if exit_code != Some(0) {
+        let code = exit_code.map(|c| c.to_string()).unwrap_or_else(|| "none (terminated by signal)".to_string());
+        return Err(format!("openclaw plugins uninstall {} failed (exit code {}):\n{}", name, code, output));
+    }
+    if parse_plugin_list(list_after).iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+        return Err(format!(
+            "openclaw plugins uninstall {} reported success, but the plugin is still listed.\nCLI output:\n{}",
+            name, output
+        ));
+    }
+    Ok(format!("Plugin {} uninstalled", name))
+}
+
+/// List installed openclaw plugins
+#[command]
+pub async fn list_plugins() -> Result<Vec<PluginInfo>, String> {
+    let output = shell::run_openclaw(&["plugins", "list"])
+        .map_err(|e| format!("Failed to list plugins: {}", e))?;
+    debug!("[Plugins] plugins list output: {}", output);
+    Ok(parse_plugin_list(&output))
+}
+
+/// Install an openclaw plugin (npm package, git URL or path) and verify it appears in
+/// `openclaw plugins list`
+#[command]
+pub async fn install_plugin(package: String) -> Result<PluginInstallResult, String> {
+    let package = package.trim().to_string();
+    info!("[Plugins] Installing plugin: {}", package);
+    require_openclaw_cli("install plugins")?;
+
+    // The list before the install tells a newly installed plugin apart from existing ones
+    let list_before = shell::run_openclaw(&["plugins", "list"]).unwrap_or_default();
+    let out = shell::run_openclaw_raw(&["plugins", "install", &package])
+        .map_err(|e| format!("Failed to install plugin: {}", e))?;
+    let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+    info!("[Plugins] Installation exit code: {:?}", out.status.code());
+
+    let list_after = if out.status.success() {
+        shell::run_openclaw(&["plugins", "list"])
+            .map_err(|e| format!("Plugin install finished but `openclaw plugins list` failed: {}\nCLI output:\n{}", e, stdout.trim()))?
+    } else {
+        String::new()
+    };
+    let result = interpret_plugin_install(&package, out.status.code(), stdout, stderr, &list_before, &list_after)
+        .inspect_err(|e| error!("[Plugins] {}", e))?;
+    info!("[Plugins] Installed: {}", result.plugin);
+    Ok(result)
+}
+
+/// Uninstall an openclaw plugin by name and verify it is no longer listed
+#[command]
+pub async fn uninstall_plugin(name: String) -> Result<String, String> {
+    let name = name.trim().to_string();
+    info!("[Plugins] Uninstalling plugin: {}", name);
+    require_openclaw_cli("uninstall plugins")?;
+    if name.is_empty() {
+        return Err("Plugin name is required".to_string());
+    }
+
+    let out = shell::run_openclaw_raw(&["plugins", "uninstall", &name])
+        .map_err(|e| format!("Failed to uninstall plugin: {}", e))?;
+    let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+    let list_after = if out.status.success() {
+        shell::run_openclaw(&["plugins", "list"])
+            .map_err(|e| format!("Plugin uninstall finished but `openclaw plugins list` failed: {}", e))?
+    } else {
+        String::new()
+    };
+    let message = interpret_plugin_uninstall(&name, out.status.code(), &stdout, &stderr, &list_after)
+        .inspect_err(|e| error!("[Plugins] {}", e))?;
+    info!("[Plugins] {}", message);
+    Ok(message)
+}
 
 /// Feishu plugin status
 #[derive(Debug, Serialize, Deserialize)]
@@ -2205,54 +6053,27 @@ pub struct FeishuPluginStatus {
 pub async fn check_feishu_plugin() -> Result<FeishuPluginStatus, String> {
     info!("[Feishu Plugin] Checking Feishu plugin installation status...");
 
-    // Execute openclaw plugins list command
-    match shell::run_openclaw(&["plugins", "list"]) {
-        Ok(output) => {
-            debug!("[Feishu Plugin] plugins list output: {}", output);
-
-            // Find line containing feishu (case-insensitive)
-            let lines: Vec<&str> = output.lines().collect();
-            let feishu_line = lines.iter().find(|line| {
-                line.to_lowercase().contains("feishu")
-            });
-
-            if let Some(line) = feishu_line {
-                info!("[Feishu Plugin] Feishu plugin installed: {}", line);
-
-                // Try to parse version number (usually format is "name@version" or "name version")
-                let version = if line.contains('@') {
-                    line.split('@').last().map(|s| s.trim().to_string())
-                } else {
-                    // Try to match version number pattern (e.g. 0.1.2)
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    parts.iter()
-                        .find(|p| p.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false))
-                        .map(|s| s.to_string())
-                };
-
-                Ok(FeishuPluginStatus {
-                    installed: true,
-                    version,
-                    plugin_name: Some(line.trim().to_string()),
-                })
-            } else {
-                info!("[Feishu Plugin] Feishu plugin not installed");
-                Ok(FeishuPluginStatus {
-                    installed: false,
-                    version: None,
-                    plugin_name: None,
-                })
-            }
-        }
+    let plugins = match list_plugins().await {
+        Ok(plugins) => plugins,
         Err(e) => {
-            warn!("[Feishu Plugin] Failed to check plugin list: {}", e);
+            warn!("[Feishu Plugin] {}", e);
             // If command fails, assume plugin is not installed
+            Vec::new()
+        }
+    };
+    match find_plugin(&plugins, "feishu") {
+        Some(plugin) => {
+            info!("[Feishu Plugin] Feishu plugin installed: {}", plugin.line);
             Ok(FeishuPluginStatus {
-                installed: false,
-                version: None,
-                plugin_name: None,
+                installed: true,
+                version: plugin.version.clone(),
+                plugin_name: Some(plugin.line.clone()),
             })
         }
+        None => {
+            info!("[Feishu Plugin] Feishu plugin not installed");
+            Ok(FeishuPluginStatus { installed: false, version: None, plugin_name: None })
+        }
     }
 }
 
@@ -2260,6 +6081,7 @@ pub async fn check_feishu_plugin() -> Result<FeishuPluginStatus, String> {
 #[command]
 pub async fn install_feishu_plugin() -> Result<String, String> {
     info!("[Feishu Plugin] Starting Feishu plugin installation...");
+    require_openclaw_cli("install the Feishu plugin")?;
 
     // First check if already installed
     let status = check_feishu_plugin().await?;
@@ -2268,27 +6090,12 @@ pub async fn install_feishu_plugin() -> Result<String, String> {
         return Ok(format!("Feishu plugin already installed: {}", status.plugin_name.unwrap_or_default()));
     }
 
-    // Install Feishu plugin
-    // Note: Using @m1heng-clawd/feishu package name
-    info!("[Feishu Plugin] Executing openclaw plugins install @m1heng-clawd/feishu ...");
-    match shell::run_openclaw(&["plugins", "install", "@m1heng-clawd/feishu"]) {
-        Ok(output) => {
-            info!("[Feishu Plugin] Installation output: {}", output);
-
-            // Verify installation result
-            let verify_status = check_feishu_plugin().await?;
-            if verify_status.installed {
-                info!("[Feishu Plugin] Feishu plugin installed successfully");
-                Ok(format!("Feishu plugin installed successfully: {}", verify_status.plugin_name.unwrap_or_default()))
-            } else {
-                warn!("[Feishu Plugin] Installation command succeeded but plugin not found");
-                Err("Installation command succeeded but plugin not found, please check openclaw version".to_string())
-            }
-        }
-        Err(e) => {
-            error!("[Feishu Plugin] Installation failed: {}", e);
-            Err(format!("Failed to install Feishu plugin: {}\n\nPlease run manually: openclaw plugins install @m1heng-clawd/feishu", e))
-        }
+    match install_plugin(FEISHU_PLUGIN_PACKAGE.to_string()).await {
+        Ok(result) => Ok(format!("Feishu plugin installed successfully: {}", result.plugin)),
+        Err(e) => Err(format!(
+            "Failed to install Feishu plugin: {}\n\nPlease run manually: openclaw plugins install {}",
+            e, FEISHU_PLUGIN_PACKAGE
+        )),
     }
 }
 
@@ -2300,6 +6107,25 @@ pub async fn get_openclaw_home_dir() -> Result<String, String> {
     Ok(platform::get_config_dir())
 }
 
+/// Resolve the Manager's file locations, checking each with `exists`
+pub(crate) fn resolve_config_paths(exists: impl Fn(&str) -> bool) -> ConfigPaths {
+    let resolve = |path: String| crate::models::ResolvedPath { exists: exists(&path), path };
+    ConfigPaths {
+        config_dir: resolve(platform::get_config_dir()),
+        config_file: resolve(platform::get_config_file_path()),
+        env_file: resolve(platform::get_env_file_path()),
+        mcp_config_file: resolve(platform::get_mcp_config_file_path()),
+        mcporter_config_file: resolve(platform::get_mcporter_config_file_path()),
+        mcp_install_dir: resolve(platform::get_mcp_install_dir()),
+    }
+}
+
+/// Get the resolved config file paths and whether each exists
+#[command]
+pub async fn get_config_paths() -> Result<ConfigPaths, String> {
+    Ok(resolve_config_paths(|p| std::path::Path::new(p).exists()))
+}
+
 // ============ Multi-Agent Routing ============
 
 /// Agent configuration for the frontend
@@ -2311,10 +6137,98 @@ pub struct AgentInfo {
     #[serde(alias = "agentDir", alias = "agent_dir")]
     pub agent_dir: Option<String>,
     pub model: Option<String>,
-    pub sandbox: Option<bool>,
+    /// Per-agent available models (agents.list[].models), overriding agents.defaults.models
+    pub models: Option<Vec<String>>,
+    pub sandbox: Option<SandboxConfig>,
     pub heartbeat: Option<String>,
     pub default: Option<bool>,
     pub subagents: Option<SubagentConfig>,
+    /// Model the agent used before its last switch (Manager metadata, meta.gui.agents.<id>)
+    #[serde(default)]
+    pub last_used_model: Option<String>,
+}
+
+/// Last-used model recorded for an agent under meta.gui.agents.<id>.lastUsedModel
+pub(crate) fn agent_last_used_model(config: &Value, agent_id: &str) -> Option<String> {
+    config
+        .pointer("/meta/gui/agents")
+        .and_then(|agents| agents.get(agent_id))
+        .and_then(|agent| agent.get("lastUsedModel"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Record (or clear, with None) an agent's last-used model; empty per-agent entries are removed
+pub(crate) fn set_agent_last_used_model_in(config: &mut Value, agent_id: &str, model: Option<&str>) {
+    repair_meta_structure(config);
+    let model = model.map(str::trim).filter(|m| !m.is_empty());
+    if !config["meta"]["gui"].get("agents").is_some_and(|v| v.is_object()) {
+        if model.is_none() {
+            return;
+        }
+        config["meta"]["gui"]["agents"] = json!({});
+    }
+    let Some(agents) = config.pointer_mut("/meta/gui/agents").and_then(|v| v.as_object_mut()) else {
+        return;
+    };
+
+    match model {
+        Some(m) => {
+            let entry = agents.entry(agent_id.to_string()).or_insert_with(|| json!({}));
+            if !entry.is_object() {
+                *entry = json!({});
+            }
+            entry["lastUsedModel"] = json!(m);
+        }
+        None => {
+            if let Some(entry) = agents.get_mut(agent_id).and_then(|v| v.as_object_mut()) {
+                entry.shift_remove("lastUsedModel");
+                if entry.is_empty() {
+                    agents.shift_remove(agent_id);
+                }
+            }
+        }
+    }
+    if agents.is_empty() {
+        if let Some(gui) = config.pointer_mut("/meta/gui").and_then(|v| v.as_object_mut()) {
+            gui.shift_remove("agents");
+        }
+    }
+}
+
+/// Remember the model an agent used before switching, so the UI can offer to restore it
+#[command]
+pub async fn record_agent_last_used_model(agent_id: String, model: Option<String>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Agents] Recording last-used model for {}: {:?}", agent_id, model);
+    let mut config = load_openclaw_config()?;
+    set_agent_last_used_model_in(&mut config, &agent_id, model.as_deref());
+    save_openclaw_config(&config)?;
+    Ok(format!("Last-used model for agent '{}' recorded", agent_id))
+}
+
+/// Read an agent's model list (object keyed by "provider/model", like agents.defaults.models)
+pub(crate) fn agent_models_from_value(agent_val: &Value) -> Option<Vec<String>> {
+    match agent_val.get("models")? {
+        Value::Object(map) => Some(map.keys().cloned().collect()),
+        // Tolerate a plain array of model ids written by hand
+        Value::Array(arr) => Some(arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect()),
+        _ => None,
+    }
+}
+
+/// Build the models object for an agent, keeping per-model settings already present
+pub(crate) fn build_agent_models(existing: Option<&Value>, models: &[String]) -> Value {
+    let mut obj = serde_json::Map::new();
+    for id in models.iter().map(|m| m.trim()).filter(|m| !m.is_empty()) {
+        let entry = existing
+            .and_then(|e| e.get(id))
+            .filter(|v| v.is_object())
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        obj.insert(id.to_string(), entry);
+    }
+    Value::Object(obj)
 }
 
 /// Per-agent subagent configuration
@@ -2324,6 +6238,55 @@ pub struct SubagentConfig {
     pub allow_agents: Option<Vec<String>>,
 }
 
+/// Per-agent sandbox settings stored under agents.list[].sandbox
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SandboxConfig {
+    pub enabled: bool,
+    /// Absolute paths the sandboxed agent may access
+    #[serde(default, rename = "allowedPaths", alias = "allowed_paths")]
+    pub allowed_paths: Vec<String>,
+}
+
+impl SandboxConfig {
+    /// Parse a stored sandbox value (object, or legacy bare boolean)
+    pub fn from_value(value: &Value) -> Option<SandboxConfig> {
+        match value {
+            Value::Bool(enabled) => Some(SandboxConfig { enabled: *enabled, allowed_paths: Vec::new() }),
+            Value::Object(obj) => Some(SandboxConfig {
+                enabled: obj.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false),
+                allowed_paths: obj.get("allowedPaths")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect())
+                    .unwrap_or_default(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Validate the allow-list before it is written to config
+    pub fn validate(&self) -> Result<(), String> {
+        for path in &self.allowed_paths {
+            if path.trim().is_empty() {
+                return Err("Sandbox allowed path cannot be empty".to_string());
+            }
+            if !std::path::Path::new(path.trim()).is_absolute() {
+                return Err(format!("Sandbox allowed path must be absolute: {}", path));
+            }
+        }
+        Ok(())
+    }
+
+    /// Value written to agents.list[].sandbox: the managed fields merged into the `existing`
+    /// sandbox object, so other sandbox settings are kept
+    pub fn to_value(&self, existing: Option<&Value>) -> Value {
+        let paths: Vec<&str> = self.allowed_paths.iter().map(|p| p.trim()).collect();
+        let mut sandbox = existing.filter(|v| v.is_object()).cloned().unwrap_or_else(|| json!({}));
+        sandbox["enabled"] = json!(self.enabled);
+        sandbox["allowedPaths"] = json!(paths);
+        sandbox
+    }
+}
+
 /// Global subagent defaults
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SubagentDefaults {
@@ -2344,12 +6307,126 @@ pub struct AgentBinding {
     pub match_rule: MatchRule,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MatchRule {
-    pub channel: Option<String>,
-    #[serde(alias = "accountId", alias = "account_id")]
-    pub account_id: Option<String>,
-    pub peer: Option<serde_json::Value>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchRule {
+    pub channel: Option<String>,
+    #[serde(alias = "accountId", alias = "account_id")]
+    pub account_id: Option<String>,
+    pub peer: Option<PeerMatch>,
+}
+
+/// How a binding's peer id is compared against an incoming peer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerMatchMode {
+    #[default]
+    Exact,
+    Prefix,
+    Regex,
+}
+
+/// Structured peer match stored under bindings[].match.peer
+/// Exact matches are written as plain { kind, id } so the gateway keeps reading them as before;
+/// prefix/regex matches add a "mode" field the gateway uses to interpret the id as a pattern.
+/// `bare` and `numeric_id` remember how a stored peer was written, so saving it back through
+/// the UI keeps legacy bare ids and numeric ids in their original type.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PeerMatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "PeerMatchMode::is_exact")]
+    pub mode: PeerMatchMode,
+    /// Stored as a bare id instead of an object
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bare: bool,
+    /// Id stored as a JSON number
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub numeric_id: bool,
+}
+
+impl PeerMatchMode {
+    fn is_exact(&self) -> bool {
+        *self == PeerMatchMode::Exact
+    }
+}
+
+impl PeerMatch {
+    /// Parse a stored peer value (object, or legacy bare id string / number)
+    pub fn from_value(value: &Value) -> Option<PeerMatch> {
+        let id_of = |v: &Value| match v {
+            Value::String(s) => Some((s.clone(), false)),
+            Value::Number(n) => Some((n.to_string(), true)),
+            _ => None,
+        };
+        match value {
+            Value::String(_) | Value::Number(_) => {
+                let (id, numeric_id) = id_of(value)?;
+                Some(PeerMatch { id, numeric_id, bare: true, ..Default::default() })
+            }
+            Value::Object(obj) => {
+                let (id, numeric_id) = obj.get("id").and_then(id_of)?;
+                let mode = obj.get("mode")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default();
+                Some(PeerMatch {
+                    kind: obj.get("kind").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                    id,
+                    mode,
+                    bare: false,
+                    numeric_id,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The value written to bindings[].match.peer, in the shape the peer was stored in.
+    /// A bare peer that gained a kind or a non-exact mode has to become an object.
+    pub fn to_value(&self) -> Value {
+        let id = match self.id.parse::<i64>() {
+            Ok(n) if self.numeric_id => json!(n),
+            _ => json!(self.id),
+        };
+        if self.bare && self.kind.is_none() && self.mode.is_exact() {
+            return id;
+        }
+        let mut peer = serde_json::Map::new();
+        if let Some(kind) = &self.kind {
+            peer.insert("kind".to_string(), json!(kind));
+        }
+        peer.insert("id".to_string(), id);
+        if !self.mode.is_exact() {
+            peer.insert("mode".to_string(), json!(self.mode));
+        }
+        Value::Object(peer)
+    }
+
+    /// Compile the regex for regex-mode peers (anchored, so the whole id must match)
+    fn compiled_regex(&self) -> Result<regex::Regex, String> {
+        regex::Regex::new(&format!("^(?:{})$", self.id))
+            .map_err(|e| format!("Invalid peer regex '{}': {}", self.id, e))
+    }
+
+    /// Validate the peer before it is written to config
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("Peer id cannot be empty".to_string());
+        }
+        if self.mode == PeerMatchMode::Regex {
+            self.compiled_regex()?;
+        }
+        Ok(())
+    }
+
+    /// Check whether an incoming peer id matches this rule
+    pub fn matches(&self, peer_id: &str) -> bool {
+        match self.mode {
+            PeerMatchMode::Exact => self.id == peer_id,
+            PeerMatchMode::Prefix => peer_id.starts_with(&self.id),
+            PeerMatchMode::Regex => self.compiled_regex().map(|re| re.is_match(peer_id)).unwrap_or(false),
+        }
+    }
 }
 
 /// Combined agents config for frontend
@@ -2375,11 +6452,13 @@ pub async fn get_agents_config() -> Result<AgentsConfigResponse, String> {
         for agent_val in list_arr {
             agents.push(AgentInfo {
                 id: agent_val.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                last_used_model: agent_val.get("id").and_then(|v| v.as_str()).and_then(|id| agent_last_used_model(&config, id)),
                 name: agent_val.get("name").and_then(|v| v.as_str()).map(|s| s.to_string()),
                 workspace: agent_val.get("workspace").and_then(|v| v.as_str()).map(|s| s.to_string()),
                 agent_dir: agent_val.get("agentDir").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                model: agent_val.pointer("/model/primary").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                sandbox: agent_val.get("sandbox").and_then(|v| v.as_bool()),
+                model: model_primary(agent_val.get("model")).map(|s| s.to_string()),
+                models: agent_models_from_value(agent_val),
+                sandbox: agent_val.get("sandbox").and_then(SandboxConfig::from_value),
                 heartbeat: agent_val.pointer("/heartbeat/every").and_then(|v| v.as_str()).map(|s| s.to_string()),
                 default: agent_val.get("default").and_then(|v| v.as_bool()),
                 subagents: agent_val.get("subagents").and_then(|v| {
@@ -2395,11 +6474,13 @@ pub async fn get_agents_config() -> Result<AgentsConfigResponse, String> {
         for (id, agent_val) in list_obj {
             agents.push(AgentInfo {
                 id: id.clone(),
+                last_used_model: agent_last_used_model(&config, id),
                 name: agent_val.get("name").and_then(|v| v.as_str()).map(|s| s.to_string()),
                 workspace: agent_val.get("workspace").and_then(|v| v.as_str()).map(|s| s.to_string()),
                 agent_dir: agent_val.get("agentDir").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                model: agent_val.pointer("/model/primary").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                sandbox: agent_val.get("sandbox").and_then(|v| v.as_bool()),
+                model: model_primary(agent_val.get("model")).map(|s| s.to_string()),
+                models: agent_models_from_value(agent_val),
+                sandbox: agent_val.get("sandbox").and_then(SandboxConfig::from_value),
                 heartbeat: agent_val.pointer("/heartbeat/every").and_then(|v| v.as_str()).map(|s| s.to_string()),
                 default: agent_val.get("default").and_then(|v| v.as_bool()),
                 subagents: agent_val.get("subagents").and_then(|v| {
@@ -2426,7 +6507,7 @@ pub async fn get_agents_config() -> Result<AgentsConfigResponse, String> {
                 match_rule: MatchRule {
                     channel: match_obj.get("channel").and_then(|v| v.as_str()).map(|s| s.to_string()),
                     account_id: match_obj.get("accountId").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                    peer: match_obj.get("peer").cloned(),
+                    peer: match_obj.get("peer").and_then(PeerMatch::from_value),
                 }
             });
         }
@@ -2449,10 +6530,26 @@ pub async fn get_agents_config() -> Result<AgentsConfigResponse, String> {
 
 /// Save (add/update) an agent
 #[command]
-pub async fn save_agent(agent: AgentInfo) -> Result<String, String> {
+pub async fn save_agent(agent: AgentInfo, force: Option<bool>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Agents] Saving agent: {}", agent.id);
+    if let Some(heartbeat) = &agent.heartbeat {
+        if !heartbeat.is_empty() {
+            parse_duration_expr(heartbeat)?;
+        }
+    }
+    if let Some(sandbox) = &agent.sandbox {
+        sandbox.validate()?;
+    }
     let mut config = load_openclaw_config()?;
 
+    // Make sure the per-agent model override points at a configured model
+    if let Some(model) = &agent.model {
+        if !model.is_empty() {
+            resolve_model_reference(&config, model, force.unwrap_or(false))?;
+        }
+    }
+
     // Ensure agents object exists
     if config.get("agents").is_none() {
         config["agents"] = json!({});
@@ -2480,8 +6577,13 @@ pub async fn save_agent(agent: AgentInfo) -> Result<String, String> {
             agent_obj["model"] = json!({ "primary": model });
         }
     }
-    if let Some(sandbox) = agent.sandbox {
-        agent_obj["sandbox"] = json!(sandbox);
+    if let Some(models) = &agent.models {
+        if !models.is_empty() {
+            agent_obj["models"] = build_agent_models(None, models);
+        }
+    }
+    if let Some(sandbox) = &agent.sandbox {
+        agent_obj["sandbox"] = sandbox.to_value(None);
     }
     if let Some(heartbeat) = &agent.heartbeat {
         if !heartbeat.is_empty() {
@@ -2662,6 +6764,16 @@ pub async fn save_agent(agent: AgentInfo) -> Result<String, String> {
                 existing["model"] = json!({ "primary": model });
             }
         }
+        // An empty list clears the override so the agent falls back to agents.defaults.models
+        if let Some(models) = &agent.models {
+            if models.is_empty() {
+                if let Some(obj) = existing.as_object_mut() {
+                    obj.shift_remove("models");
+                }
+            } else {
+                existing["models"] = build_agent_models(existing.get("models"), models);
+            }
+        }
         if let Some(is_default) = agent.default {
             if is_default {
                 existing["default"] = json!(true);
@@ -2682,8 +6794,8 @@ pub async fn save_agent(agent: AgentInfo) -> Result<String, String> {
                 }
             }
         }
-        if let Some(sandbox) = agent.sandbox {
-            existing["sandbox"] = json!(sandbox);
+        if let Some(sandbox) = &agent.sandbox {
+            existing["sandbox"] = sandbox.to_value(existing.get("sandbox"));
         }
         if let Some(heartbeat) = &agent.heartbeat {
             if !heartbeat.is_empty() {
@@ -2778,6 +6890,7 @@ pub async fn save_agent(agent: AgentInfo) -> Result<String, String> {
 /// Save global subagent defaults
 #[command]
 pub async fn save_subagent_defaults(defaults: SubagentDefaults) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Agents] Saving subagent defaults");
     let mut config = load_openclaw_config()?;
 
@@ -2809,6 +6922,7 @@ pub async fn save_subagent_defaults(defaults: SubagentDefaults) -> Result<String
 /// Delete an agent
 #[command]
 pub async fn delete_agent(agent_id: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Agents] Deleting agent: {}", agent_id);
     let mut config = load_openclaw_config()?;
 
@@ -2893,16 +7007,118 @@ pub async fn delete_agent(agent_id: String) -> Result<String, String> {
     if let Some(bindings) = config.pointer_mut("/agents/bindings").and_then(|v| v.as_array_mut()) {
         bindings.retain(|b| b.get("agentId").and_then(|v| v.as_str()) != Some(&agent_id));
     }
+    set_agent_last_used_model_in(&mut config, &agent_id, None);
 
     save_openclaw_config(&config)?;
     Ok(format!("Agent '{}' and its files were deleted", agent_id))
 }
 
+/// Copy an agent entry under a new id, optionally handing the source's bindings over to it.
+/// Bindings are never duplicated: routing takes the first match, so a copy placed after the
+/// original would never be reached. Moved bindings stay in place (top-level bindings or legacy
+/// agents.bindings, same position) with only their agentId changed.
+/// The workspace and agentDir are dropped unless `share_workspace` is set,
+/// so the clone gets its own default directories instead of sharing the source's.
+pub(crate) fn clone_agent_in_config(
+    config: &mut Value,
+    source_id: &str,
+    new_id: &str,
+    move_bindings: bool,
+    share_workspace: bool,
+) -> Result<(), String> {
+    let new_id = new_id.trim();
+    if new_id.is_empty() {
+        return Err("New agent id cannot be empty".to_string());
+    }
+
+    if config.get("agents").is_none() {
+        config["agents"] = json!({});
+    }
+
+    // Migrate legacy object format to array if needed
+    let mut list: Vec<Value> = if let Some(arr) = config["agents"].get("list").and_then(|v| v.as_array()) {
+        arr.clone()
+    } else if let Some(obj) = config["agents"].get("list").and_then(|v| v.as_object()) {
+        obj.iter().map(|(id, val)| {
+            let mut entry = val.clone();
+            entry["id"] = json!(id);
+            entry
+        }).collect()
+    } else {
+        Vec::new()
+    };
+
+    if list.iter().any(|a| a.get("id").and_then(|v| v.as_str()) == Some(new_id)) {
+        return Err(format!("Agent '{}' already exists", new_id));
+    }
+
+    let mut cloned = list.iter()
+        .find(|a| a.get("id").and_then(|v| v.as_str()) == Some(source_id))
+        .cloned()
+        .ok_or_else(|| format!("Agent '{}' not found", source_id))?;
+
+    if let Some(obj) = cloned.as_object_mut() {
+        obj.insert("id".to_string(), json!(new_id));
+        // Only one agent can be the default
+        obj.shift_remove("default");
+        if !share_workspace {
+            obj.shift_remove("workspace");
+            obj.shift_remove("agentDir");
+        }
+    }
+    list.push(cloned);
+    config["agents"]["list"] = json!(list);
+
+    if move_bindings {
+        for pointer in ["/bindings", "/agents/bindings"] {
+            if let Some(bindings) = config.pointer_mut(pointer).and_then(|v| v.as_array_mut()) {
+                for b in bindings.iter_mut() {
+                    if b.get("agentId").and_then(|v| v.as_str()) == Some(source_id) {
+                        b["agentId"] = json!(new_id);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Clone an existing agent under a new id
+#[command]
+pub async fn clone_agent(
+    source_id: String,
+    new_id: String,
+    move_bindings: Option<bool>,
+    share_workspace: Option<bool>,
+) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Agents] Cloning agent {} -> {}", source_id, new_id);
+    let mut config = load_openclaw_config()?;
+
+    clone_agent_in_config(
+        &mut config,
+        &source_id,
+        &new_id,
+        move_bindings.unwrap_or(false),
+        share_workspace.unwrap_or(false),
+    )?;
+
+    save_openclaw_config(&config)?;
+    Ok(format!("Agent '{}' cloned to '{}'", source_id, new_id.trim()))
+}
+
 /// Save an agent binding rule
 #[command]
 
 pub async fn save_agent_binding(binding: AgentBinding) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Agents] Saving binding for agent: {}", binding.agent_id);
+
+    if let Some(peer) = &binding.match_rule.peer {
+        peer.validate()?;
+    }
+
     let mut config = load_openclaw_config()?;
 
     // Ensure top-level bindings array exists
@@ -2919,7 +7135,7 @@ pub async fn save_agent_binding(binding: AgentBinding) -> Result<String, String>
         }
         // Remove legacy location
         if let Some(agents) = config.get_mut("agents").and_then(|v| v.as_object_mut()) {
-            agents.remove("bindings");
+            agents.shift_remove("bindings");
         }
     }
 
@@ -2931,7 +7147,7 @@ pub async fn save_agent_binding(binding: AgentBinding) -> Result<String, String>
         if !acc.is_empty() { match_obj["accountId"] = json!(acc); }
     }
     if let Some(peer) = &binding.match_rule.peer {
-        match_obj["peer"] = peer.clone();
+        match_obj["peer"] = peer.to_value();
     }
 
     let binding_obj = json!({
@@ -2950,6 +7166,7 @@ pub async fn save_agent_binding(binding: AgentBinding) -> Result<String, String>
 /// Delete an agent binding by index
 #[command]
 pub async fn delete_agent_binding(index: usize) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Agents] Deleting binding at index: {}", index);
     let mut config = load_openclaw_config()?;
 
@@ -3060,9 +7277,9 @@ pub async fn save_agent_system_prompt(agent_id: String, workspace: Option<String
     }
 }
 
-/// Test agent routing: given an account ID, find which agent handles it
+/// Test agent routing: given an account ID (and optionally a peer ID), find which agent handles it
 #[command]
-pub async fn test_agent_routing(account_id: String) -> Result<serde_json::Value, String> {
+pub async fn test_agent_routing(account_id: String, peer_id: Option<String>) -> Result<serde_json::Value, String> {
     let config = load_openclaw_config()?;
 
     // Walk through bindings to find a match
@@ -3078,8 +7295,13 @@ pub async fn test_agent_routing(account_id: String) -> Result<serde_json::Value,
             // Check if this binding matches
             let account_matches = binding_account.map(|a| a == account_id).unwrap_or(true); // None = catch-all
             let channel_matches = binding_channel.map(|c| c == "telegram").unwrap_or(true);
+            // Peer-scoped bindings only match when a peer id is supplied and satisfies the rule
+            let peer_matches = match match_obj.get("peer").and_then(PeerMatch::from_value) {
+                Some(peer) => peer_id.as_deref().map(|p| peer.matches(p)).unwrap_or(false),
+                None => true,
+            };
 
-            if account_matches && channel_matches {
+            if account_matches && channel_matches && peer_matches {
                 let agent_id = binding.get("agentId").and_then(|v| v.as_str()).unwrap_or("unknown");
 
                 // Find agent details
@@ -3118,7 +7340,7 @@ pub async fn test_agent_routing(account_id: String) -> Result<serde_json::Value,
                     "matched": true,
                     "agent_id": agent_id,
                     "agent_dir": agent_info.and_then(|a| a.get("agentDir").and_then(|v| v.as_str())),
-                    "model": agent_info.and_then(|a| a.pointer("/model/primary").and_then(|v| v.as_str())),
+                    "model": agent_info.and_then(|a| model_primary(a.get("model"))),
                     "system_prompt_preview": prompt_preview,
                     "binding": binding
                 }));
@@ -3151,6 +7373,48 @@ pub struct CompactionConfig {
     pub max_context_messages: Option<u32>,
 }
 
+/// Parse a gateway duration expression such as "30s", "5m", "1h" or "1h30m".
+/// Units: ms, s, m, h, d. Zero durations are rejected since the heartbeat would never fire.
+pub(crate) fn parse_duration_expr(expr: &str) -> Result<std::time::Duration, String> {
+    let input = expr.trim();
+    if input.is_empty() {
+        return Err("Duration cannot be empty".to_string());
+    }
+
+    let invalid = || format!("Invalid duration '{}': expected e.g. \"30s\", \"5m\", \"1h\" or \"1h30m\"", expr);
+    let mut total_ms: u64 = 0;
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            return Err(invalid());
+        }
+        let value: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+
+        let unit_len = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_alphabetic()).len();
+        let factor = match &rest[..unit_len] {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            "d" => 86_400_000,
+            _ => return Err(invalid()),
+        };
+        rest = &rest[unit_len..];
+
+        total_ms = value.checked_mul(factor)
+            .and_then(|ms| total_ms.checked_add(ms))
+            .ok_or_else(invalid)?;
+    }
+
+    if total_ms == 0 {
+        return Err(format!("Invalid duration '{}': must be greater than zero", expr));
+    }
+    Ok(std::time::Duration::from_millis(total_ms))
+}
+
 /// Get heartbeat configuration
 #[command]
 pub async fn get_heartbeat_config() -> Result<HeartbeatConfig, String> {
@@ -3168,9 +7432,16 @@ pub async fn get_heartbeat_config() -> Result<HeartbeatConfig, String> {
 /// Save heartbeat configuration
 #[command]
 pub async fn save_heartbeat_config(every: Option<String>, target: Option<String>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Heartbeat] Saving heartbeat config: every={:?}, target={:?}", every, target);
     let mut config = load_openclaw_config()?;
 
+    // Treat blank input as unset, and reject anything the gateway cannot parse
+    let every = every.map(|e| e.trim().to_string()).filter(|e| !e.is_empty());
+    if let Some(e) = &every {
+        parse_duration_expr(e)?;
+    }
+
     if config.get("agents").is_none() { config["agents"] = json!({}); }
     if config["agents"].get("defaults").is_none() { config["agents"]["defaults"] = json!({}); }
 
@@ -3182,7 +7453,7 @@ pub async fn save_heartbeat_config(every: Option<String>, target: Option<String>
     } else {
         // Remove heartbeat if both are None
         if let Some(defaults) = config["agents"]["defaults"].as_object_mut() {
-            defaults.remove("heartbeat");
+            defaults.shift_remove("heartbeat");
         }
     }
 
@@ -3199,17 +7470,14 @@ pub async fn get_compaction_config() -> Result<CompactionConfig, String> {
     let compaction_val = config.pointer("/agents/defaults/compaction");
     let pruning_val = config.pointer("/agents/defaults/contextPruning");
 
-    let enabled = compaction_val.map(|v| {
-        // compaction can be true/false or an object with settings
-        v.as_bool().unwrap_or(true)
-    }).unwrap_or(false);
+    let enabled = compaction_val.map(section_enabled).unwrap_or(false);
 
     let threshold = compaction_val
         .and_then(|v| v.get("threshold"))
         .and_then(|v| v.as_u64())
         .map(|v| v as u32);
 
-    let context_pruning = pruning_val.map(|v| v.as_bool().unwrap_or(false)).unwrap_or(false);
+    let context_pruning = pruning_val.map(section_enabled).unwrap_or(false);
 
     let max_context_messages = pruning_val
         .and_then(|v| v.get("maxMessages"))
@@ -3219,6 +7487,97 @@ pub async fn get_compaction_config() -> Result<CompactionConfig, String> {
     Ok(CompactionConfig { enabled, threshold, context_pruning, max_context_messages })
 }
 
+/// compaction/contextPruning can be true/false or an object with settings; an object is on
+/// unless it carries "enabled": false
+fn section_enabled(value: &Value) -> bool {
+    match value {
+        Value::Object(obj) => obj.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true),
+        v => v.as_bool().unwrap_or(false),
+    }
+}
+
+/// Turn off a compaction/contextPruning section by clearing the field the Manager manages.
+/// Settings the Manager doesn't know about are kept under "enabled": false; a section with
+/// nothing else in it is removed.
+fn disable_section(defaults: &mut Value, key: &str, managed_field: &str) {
+    let Some(obj) = defaults.as_object_mut() else { return };
+    match obj.get_mut(key) {
+        Some(Value::Object(section)) => {
+            section.shift_remove(managed_field);
+            section.shift_remove("enabled");
+            if section.is_empty() {
+                obj.shift_remove(key);
+            } else {
+                section.insert("enabled".to_string(), json!(false));
+            }
+        }
+        Some(_) => {
+            obj.shift_remove(key);
+        }
+        None => {}
+    }
+}
+
+/// Existing settings object of a section being turned on, without a previous "enabled": false
+fn enabled_section(defaults: &Value, key: &str) -> Option<Value> {
+    let mut section = defaults.get(key).filter(|v| v.is_object()).cloned()?;
+    if let Some(obj) = section.as_object_mut() {
+        if obj.get("enabled") == Some(&json!(false)) {
+            obj.shift_remove("enabled");
+        }
+    }
+    Some(section)
+}
+
+/// Update compaction/contextPruning under agents.defaults, keeping keys the Manager doesn't manage
+pub(crate) fn apply_compaction_settings(
+    defaults: &mut Value,
+    enabled: bool,
+    threshold: Option<u32>,
+    context_pruning: bool,
+    max_context_messages: Option<u32>,
+) {
+    if enabled {
+        // Start from the existing object so unknown core settings survive
+        let mut comp = enabled_section(defaults, "compaction").unwrap_or_else(|| json!({}));
+        match threshold {
+            Some(t) => comp["threshold"] = json!(t),
+            None => {
+                if let Some(o) = comp.as_object_mut() { o.shift_remove("threshold"); }
+            }
+        }
+        defaults["compaction"] = comp;
+    } else {
+        disable_section(defaults, "compaction", "threshold");
+    }
+
+    if context_pruning {
+        let pruning = match enabled_section(defaults, "contextPruning") {
+            Some(mut existing) => {
+                match max_context_messages {
+                    Some(max) => existing["maxMessages"] = json!(max),
+                    None => {
+                        if let Some(o) = existing.as_object_mut() { o.shift_remove("maxMessages"); }
+                    }
+                }
+                // An object emptied of all settings is equivalent to plain `true`
+                if existing.as_object().map(|o| o.is_empty()).unwrap_or(false) {
+                    json!(true)
+                } else {
+                    existing
+                }
+            }
+            None => match max_context_messages {
+                Some(max) => json!({ "maxMessages": max }),
+                None => json!(true),
+            },
+        };
+        defaults["contextPruning"] = pruning;
+    } else {
+        disable_section(defaults, "contextPruning", "maxMessages");
+    }
+}
+
 /// Save compaction configuration
 #[command]
 pub async fn save_compaction_config(
@@ -3227,33 +7586,20 @@ pub async fn save_compaction_config(
     context_pruning: bool,
     max_context_messages: Option<u32>,
 ) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Compaction] Saving compaction config: enabled={}, pruning={}", enabled, context_pruning);
     let mut config = load_openclaw_config()?;
 
     if config.get("agents").is_none() { config["agents"] = json!({}); }
     if config["agents"].get("defaults").is_none() { config["agents"]["defaults"] = json!({}); }
 
-    if enabled {
-        let mut comp = json!({});
-        if let Some(t) = threshold { comp["threshold"] = json!(t); }
-        config["agents"]["defaults"]["compaction"] = comp;
-    } else {
-        if let Some(defaults) = config["agents"]["defaults"].as_object_mut() {
-            defaults.remove("compaction");
-        }
-    }
-
-    if context_pruning {
-        let mut pruning = json!(true);
-        if let Some(max) = max_context_messages {
-            pruning = json!({ "maxMessages": max });
-        }
-        config["agents"]["defaults"]["contextPruning"] = pruning;
-    } else {
-        if let Some(defaults) = config["agents"]["defaults"].as_object_mut() {
-            defaults.remove("contextPruning");
-        }
-    }
+    apply_compaction_settings(
+        &mut config["agents"]["defaults"],
+        enabled,
+        threshold,
+        context_pruning,
+        max_context_messages,
+    );
 
     save_openclaw_config(&config)?;
     Ok("Compaction configuration saved".to_string())
@@ -3265,12 +7611,60 @@ pub async fn save_compaction_config(
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceConfig {
     pub workspace: Option<String>,
+    /// Whether the configured workspace directory currently exists
+    pub workspace_exists: bool,
     pub timezone: Option<String>,
     pub time_format: Option<String>,
     pub skip_bootstrap: bool,
     pub bootstrap_max_chars: Option<u32>,
 }
 
+/// Expand a leading "~" so paths saved for the gateway can be checked locally
+pub(crate) fn expand_home_path(path: &str) -> std::path::PathBuf {
+    if let Some(rest) = path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    } else if path == "~" {
+        if let Some(home) = dirs::home_dir() {
+            return home;
+        }
+    }
+    std::path::PathBuf::from(path)
+}
+
+/// Check that a workspace path is a writable directory, creating it when `create` is set
+pub(crate) fn ensure_workspace_dir(path: &str, create: bool) -> Result<(), String> {
+    let dir = expand_home_path(path.trim());
+
+    if dir.exists() {
+        if !dir.is_dir() {
+            return Err(format!("Workspace path {} exists but is not a directory", dir.display()));
+        }
+    } else if create {
+        info!("[Workspace] Creating workspace directory: {}", dir.display());
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create workspace directory {}: {}", dir.display(), e))?;
+    } else {
+        return Err(format!("Workspace directory {} does not exist", dir.display()));
+    }
+
+    // Permission bits don't say whether this user may write (owner, ACLs, Windows ignores
+    // read-only on directories), so probe with a uniquely named file and remove it again
+    let probe = dir.join(format!(
+        ".openclaw-write-test-{}-{}",
+        std::process::id(),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| format!("Workspace directory {} is not writable: {}", dir.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
 /// Get workspace configuration
 #[command]
 pub async fn get_workspace_config() -> Result<WorkspaceConfig, String> {
@@ -3279,6 +7673,10 @@ pub async fn get_workspace_config() -> Result<WorkspaceConfig, String> {
 
     let workspace = config.pointer("/agents/defaults/workspace")
         .and_then(|v| v.as_str()).map(|s| s.to_string());
+    let workspace_exists = workspace.as_deref()
+        .filter(|w| !w.is_empty())
+        .map(|w| expand_home_path(w).is_dir())
+        .unwrap_or(false);
     let timezone = config.pointer("/manager/timezone")
         .and_then(|v| v.as_str()).map(|s| s.to_string());
     let time_format = config.pointer("/manager/time_format")
@@ -3288,7 +7686,7 @@ pub async fn get_workspace_config() -> Result<WorkspaceConfig, String> {
     let bootstrap_max_chars = config.pointer("/agents/defaults/bootstrapMaxChars")
         .and_then(|v| v.as_u64()).map(|v| v as u32);
 
-    Ok(WorkspaceConfig { workspace, timezone, time_format, skip_bootstrap, bootstrap_max_chars })
+    Ok(WorkspaceConfig { workspace, workspace_exists, timezone, time_format, skip_bootstrap, bootstrap_max_chars })
 }
 
 /// Save workspace configuration
@@ -3299,10 +7697,21 @@ pub async fn save_workspace_config(
     time_format: Option<String>,
     skip_bootstrap: bool,
     bootstrap_max_chars: Option<u32>,
+    create: Option<bool>,
 ) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Workspace] Saving workspace config...");
     let mut config = load_openclaw_config()?;
 
+    // Validate new paths (an unchanged path is only re-checked when creation is requested)
+    if let Some(w) = workspace.as_deref().filter(|w| !w.is_empty()) {
+        let current = config.pointer("/agents/defaults/workspace").and_then(|v| v.as_str());
+        let create = create.unwrap_or(false);
+        if create || current != Some(w) {
+            ensure_workspace_dir(w, create)?;
+        }
+    }
+
     if config.get("agents").is_none() { config["agents"] = json!({}); }
     if config["agents"].get("defaults").is_none() { config["agents"]["defaults"] = json!({}); }
 
@@ -3310,20 +7719,20 @@ pub async fn save_workspace_config(
     if let Some(defaults) = config.pointer_mut("/agents/defaults").and_then(|v| v.as_object_mut()) {
         match &workspace {
             Some(w) if !w.is_empty() => { defaults.insert("workspace".into(), json!(w)); }
-            _ => { defaults.remove("workspace"); }
+            _ => { defaults.shift_remove("workspace"); }
         }
         if skip_bootstrap {
             defaults.insert("skipBootstrap".into(), json!(true));
         } else {
-            defaults.remove("skipBootstrap");
+            defaults.shift_remove("skipBootstrap");
         }
         match bootstrap_max_chars {
             Some(max) => { defaults.insert("bootstrapMaxChars".into(), json!(max)); }
-            None => { defaults.remove("bootstrapMaxChars"); }
+            None => { defaults.shift_remove("bootstrapMaxChars"); }
         }
         // Remove timezone/timeFormat from defaults if present (migrate to manager)
-        defaults.remove("timezone");
-        defaults.remove("timeFormat");
+        defaults.shift_remove("timezone");
+        defaults.shift_remove("timeFormat");
     }
 
     // Set manager fields
@@ -3331,11 +7740,11 @@ pub async fn save_workspace_config(
     if let Some(manager) = config.get_mut("manager").and_then(|v| v.as_object_mut()) {
         match &timezone {
             Some(tz) if !tz.is_empty() => { manager.insert("timezone".into(), json!(tz)); }
-            _ => { manager.remove("timezone"); }
+            _ => { manager.shift_remove("timezone"); }
         }
         match &time_format {
             Some(tf) if !tf.is_empty() => { manager.insert("time_format".into(), json!(tf)); }
-            _ => { manager.remove("time_format"); }
+            _ => { manager.shift_remove("time_format"); }
         }
     }
 
@@ -3343,36 +7752,102 @@ pub async fn save_workspace_config(
     Ok("Workspace configuration saved".to_string())
 }
 
-/// Get a personality file from the workspace directory
-#[command]
-pub async fn get_personality_file(filename: String) -> Result<String, String> {
-    info!("[Personality] Reading file: {}", filename);
-
-    // Validate filename
-    let allowed = ["AGENTS.md", "SOUL.md", "TOOLS.md"];
-    if !allowed.contains(&filename.as_str()) {
-        return Err(format!("Invalid file: {}. Allowed: {:?}", filename, allowed));
+/// Default personality files created by openclaw
+const DEFAULT_PERSONALITY_FILES: [&str; 3] = ["AGENTS.md", "SOUL.md", "TOOLS.md"];
+
+/// Validate a personality file name: a plain *.md file name inside the workspace
+pub(crate) fn validate_personality_filename(filename: &str) -> Result<(), String> {
+    if filename.is_empty()
+        || filename.contains('/')
+        || filename.contains('\\')
+        || filename.contains("..")
+        || filename.contains('\0')
+        || filename.starts_with('.')
+    {
+        return Err(format!("Invalid file name: {}", filename));
     }
+    if !filename.to_lowercase().ends_with(".md") {
+        return Err(format!("Invalid file: {}. Only markdown (*.md) files can be edited", filename));
+    }
+    Ok(())
+}
 
-    // Get workspace path from config, fallback to ~/.openclaw
-    let config = load_openclaw_config()?;
+/// Workspace directory holding personality files (falls back to ~/.openclaw), with "~" expanded
+pub(crate) fn personality_dir(config: &Value) -> std::path::PathBuf {
     let workspace = config.pointer("/agents/defaults/workspace")
         .and_then(|v| v.as_str())
         .unwrap_or("");
 
-    let dir = if workspace.is_empty() {
-        platform::get_config_dir()
+    if workspace.is_empty() {
+        std::path::PathBuf::from(platform::get_config_dir())
     } else {
-        workspace.to_string()
-    };
+        expand_home_path(workspace)
+    }
+}
+
+/// Resolve a personality file inside `dir`, rejecting anything that escapes it
+/// (including via symlinks). `dir` must already exist.
+pub(crate) fn resolve_workspace_file(dir: &std::path::Path, filename: &str) -> Result<std::path::PathBuf, String> {
+    validate_personality_filename(filename)?;
+
+    let base = dir.canonicalize()
+        .map_err(|e| format!("Failed to resolve workspace directory {}: {}", dir.display(), e))?;
+    let candidate = base.join(filename);
 
-    let filepath = if platform::is_windows() {
-        format!("{}\\{}", dir, filename)
+    let resolved = if candidate.exists() {
+        candidate.canonicalize()
+            .map_err(|e| format!("Failed to resolve {}: {}", candidate.display(), e))?
     } else {
-        format!("{}/{}", dir, filename)
+        candidate
+    };
+
+    if !resolved.starts_with(&base) {
+        return Err(format!("Refusing to access {}: path escapes the workspace directory", filename));
+    }
+    Ok(resolved)
+}
+
+/// List editable markdown files in the workspace directory
+#[command]
+pub async fn list_personality_files() -> Result<Vec<String>, String> {
+    let config = load_openclaw_config()?;
+    let dir = personality_dir(&config);
+    info!("[Personality] Listing markdown files in {}", dir.display());
+
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(Vec::new()), // Workspace not created yet
     };
 
-    match file::read_file(&filepath) {
+    let mut files: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+        .filter(|name| validate_personality_filename(name).is_ok())
+        .collect();
+
+    // Defaults first, then custom files alphabetically
+    files.sort_by_key(|name| (!DEFAULT_PERSONALITY_FILES.contains(&name.as_str()), name.to_lowercase()));
+    Ok(files)
+}
+
+/// Get a personality file from the workspace directory
+#[command]
+pub async fn get_personality_file(filename: String) -> Result<String, String> {
+    info!("[Personality] Reading file: {}", filename);
+
+    // Validate filename
+    validate_personality_filename(&filename)?;
+
+    // Get workspace path from config, fallback to ~/.openclaw
+    let config = load_openclaw_config()?;
+    let dir = personality_dir(&config);
+    if !dir.is_dir() {
+        return Ok(String::new()); // Workspace not created yet
+    }
+
+    let filepath = resolve_workspace_file(&dir, &filename)?;
+    match std::fs::read_to_string(&filepath) {
         Ok(content) => Ok(content),
         Err(_) => Ok(String::new()), // File doesn't exist yet, return empty
     }
@@ -3383,29 +7858,15 @@ pub async fn get_personality_file(filename: String) -> Result<String, String> {
 pub async fn save_personality_file(filename: String, content: String) -> Result<String, String> {
     info!("[Personality] Saving file: {}", filename);
 
-    let allowed = ["AGENTS.md", "SOUL.md", "TOOLS.md"];
-    if !allowed.contains(&filename.as_str()) {
-        return Err(format!("Invalid file: {}. Allowed: {:?}", filename, allowed));
-    }
+    validate_personality_filename(&filename)?;
 
     let config = load_openclaw_config()?;
-    let workspace = config.pointer("/agents/defaults/workspace")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    let dir = if workspace.is_empty() {
-        platform::get_config_dir()
-    } else {
-        workspace.to_string()
-    };
-
-    let filepath = if platform::is_windows() {
-        format!("{}\\{}", dir, filename)
-    } else {
-        format!("{}/{}", dir, filename)
-    };
+    let dir = personality_dir(&config);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create workspace directory: {}", e))?;
 
-    file::write_file(&filepath, &content)
+    let filepath = resolve_workspace_file(&dir, &filename)?;
+    std::fs::write(&filepath, &content)
         .map_err(|e| format!("Failed to save {}: {}", filename, e))?;
 
     Ok(format!("{} saved successfully", filename))
@@ -3420,6 +7881,19 @@ pub struct BrowserConfig {
     pub color: Option<String>,
 }
 
+/// Normalize a "#RGB" / "#RRGGBB" color to lowercase "#rrggbb"
+pub(crate) fn normalize_hex_color(color: &str) -> Result<String, String> {
+    let hex = color.trim().strip_prefix('#')
+        .filter(|h| h.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("Invalid color '{}': expected #RRGGBB or #RGB", color))?;
+
+    match hex.len() {
+        6 => Ok(format!("#{}", hex.to_lowercase())),
+        3 => Ok(format!("#{}", hex.chars().flat_map(|c| [c, c]).collect::<String>().to_lowercase())),
+        _ => Err(format!("Invalid color '{}': expected #RRGGBB or #RGB", color)),
+    }
+}
+
 /// Get browser configuration
 #[command]
 pub async fn get_browser_config() -> Result<BrowserConfig, String> {
@@ -3431,81 +7905,329 @@ pub async fn get_browser_config() -> Result<BrowserConfig, String> {
         .and_then(|v| v.as_bool())
         .unwrap_or(true); // Default to true if not set
 
-    let color = config.pointer("/meta/gui/browser/color")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
+    let color = config.pointer("/meta/gui/browser/color")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    Ok(BrowserConfig { enabled, color })
+}
+
+/// Save browser configuration
+#[command]
+pub async fn save_browser_config(enabled: bool, color: Option<String>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Browser] Saving browser config: enabled={}, color={:?}", enabled, color);
+    let mut config = load_openclaw_config()?;
+
+    // Store in meta.gui.browser to avoid polluting core config
+    repair_meta_structure(&mut config);
+    
+    let mut browser_config = json!({
+        "enabled": enabled
+    });
+
+    if let Some(c) = color {
+        if !c.trim().is_empty() {
+            browser_config["color"] = json!(normalize_hex_color(&c)?);
+        }
+    }
+
+    config["meta"]["gui"]["browser"] = browser_config;
+
+    save_openclaw_config(&config)?;
+    Ok("Browser configuration saved".to_string())
+}
+
+// ============ Network Proxy ============
+
+/// Proxy settings for the Manager's own outbound requests (model fetch, provider and MCP tests)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProxyConfig {
+    /// Proxy URL stored in meta.gui.network.proxy
+    pub url: Option<String>,
+    /// Comma-separated hosts that bypass the proxy (meta.gui.network.noProxy)
+    pub no_proxy: Option<String>,
+    /// Proxy from HTTPS_PROXY / HTTP_PROXY / ALL_PROXY, used when `url` is not set
+    pub env_proxy: Option<String>,
+}
+
+/// The proxy outbound requests should use
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ProxySettings {
+    pub url: String,
+    pub no_proxy: Option<String>,
+    /// "config" or "env"
+    pub source: &'static str,
+}
+
+/// First non-empty value among the given environment variables
+fn first_env(env: &impl Fn(&str) -> Option<String>, names: &[&str]) -> Option<String> {
+    names.iter().filter_map(|n| env(n)).map(|v| v.trim().to_string()).find(|v| !v.is_empty())
+}
+
+/// Proxy from the standard environment variables (upper- or lowercase)
+fn env_proxy(env: &impl Fn(&str) -> Option<String>) -> Option<String> {
+    first_env(env, &["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"])
+}
+
+/// Resolve the proxy: meta.gui.network.proxy wins over the environment variables
+pub(crate) fn resolve_proxy(config: &Value, env: impl Fn(&str) -> Option<String>) -> Option<ProxySettings> {
+    let network = config.pointer("/meta/gui/network");
+    let configured = network
+        .and_then(|n| n.get("proxy"))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    if let Some(url) = configured {
+        let no_proxy = network
+            .and_then(|n| n.get("noProxy"))
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| v.to_string());
+        return Some(ProxySettings { url: url.to_string(), no_proxy, source: "config" });
+    }
+    env_proxy(&env).map(|url| ProxySettings {
+        url,
+        no_proxy: first_env(&env, &["NO_PROXY", "no_proxy"]),
+        source: "env",
+    })
+}
+
+/// Proxy for the current config and process environment
+pub(crate) fn current_proxy() -> Option<ProxySettings> {
+    let config = read_openclaw_config().unwrap_or_else(|_| json!({}));
+    resolve_proxy(&config, |name| std::env::var(name).ok())
+}
+
+/// Check a proxy URL: http(s) with a host (reqwest is built without SOCKS support)
+pub(crate) fn validate_proxy_url(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid proxy URL '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Unsupported proxy scheme '{}': use http:// or https://", parsed.scheme()));
+    }
+    if parsed.host_str().is_none_or(|h| h.is_empty()) {
+        return Err(format!("Proxy URL '{}' has no host", url));
+    }
+    Ok(url.to_string())
+}
+
 
-    Ok(BrowserConfig { enabled, color })
+/// HTTP client for outbound requests, routed through the configured proxy
+pub(crate) fn build_http_client(timeout_ms: u64) -> Result<reqwest::Client, String> {
+    // reqwest is built without a bundled crypto provider; use ring like the updater does
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let mut builder = reqwest::Client::builder().timeout(std::time::Duration::from_millis(timeout_ms));
+
+    if let Some(proxy) = current_proxy() {
+        match reqwest::Proxy::all(&proxy.url) {
+            Ok(p) => {
+                debug!("[Network] Using proxy from {}", proxy.source);
+                let no_proxy = proxy.no_proxy.as_deref().and_then(reqwest::NoProxy::from_string);
+                builder = builder.proxy(p.no_proxy(no_proxy));
+            }
+            // A proxy reqwest cannot use (e.g. socks5:// from the environment) is skipped
+            Err(e) => warn!("[Network] Ignoring unusable proxy from {}: {}", proxy.source, e),
+        }
+    }
+    builder.build().map_err(|e| format!("Failed to create HTTP client: {}", e))
 }
 
-/// Save browser configuration
+/// Get the proxy configuration
 #[command]
-pub async fn save_browser_config(enabled: bool, color: Option<String>) -> Result<String, String> {
-    info!("[Browser] Saving browser config: enabled={}, color={:?}", enabled, color);
-    let mut config = load_openclaw_config()?;
+pub async fn get_proxy_config() -> Result<ProxyConfig, String> {
+    let config = load_openclaw_config()?;
+    let network = config.pointer("/meta/gui/network");
+    let field = |key: &str| {
+        network.and_then(|n| n.get(key)).and_then(|v| v.as_str()).map(|s| s.to_string())
+    };
+    Ok(ProxyConfig {
+        url: field("proxy"),
+        no_proxy: field("noProxy"),
+        env_proxy: env_proxy(&|name: &str| std::env::var(name).ok()),
+    })
+}
 
-    // Store in meta.gui.browser to avoid polluting core config
-    if config.get("meta").is_none() { config["meta"] = json!({}); }
-    if config["meta"].get("gui").is_none() { config["meta"]["gui"] = json!({}); }
-    
-    let mut browser_config = json!({
-        "enabled": enabled
-    });
+/// Save the proxy configuration (an empty url removes it and falls back to the environment)
+#[command]
+pub async fn save_proxy_config(url: Option<String>, no_proxy: Option<String>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    let url = url.filter(|u| !u.trim().is_empty()).map(|u| validate_proxy_url(&u)).transpose()?;
+    let no_proxy = no_proxy.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    info!("[Network] Saving proxy config: configured={}, no_proxy={:?}", url.is_some(), no_proxy);
 
-    if let Some(c) = color {
-        if !c.is_empty() {
-            browser_config["color"] = json!(c);
+    let mut config = load_openclaw_config()?;
+    repair_meta_structure(&mut config);
+    let mut network = serde_json::Map::new();
+    if let Some(url) = url {
+        network.insert("proxy".into(), json!(url));
+        if let Some(no_proxy) = no_proxy {
+            network.insert("noProxy".into(), json!(no_proxy));
+        }
+    }
+    if let Some(gui) = config.pointer_mut("/meta/gui").and_then(|v| v.as_object_mut()) {
+        if network.is_empty() {
+            gui.shift_remove("network");
+        } else {
+            gui.insert("network".into(), Value::Object(network));
         }
     }
-
-    config["meta"]["gui"]["browser"] = browser_config;
 
     save_openclaw_config(&config)?;
-    Ok("Browser configuration saved".to_string())
+    Ok("Proxy configuration saved".to_string())
 }
 
 // ============ Web Search ============
 
+/// Known web search providers
+const WEB_SEARCH_PROVIDERS: [&str; 3] = ["brave", "tavily", "google"];
+
+/// A configured web search provider (web.providers.<id>)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebSearchProvider {
+    pub id: String,
+    #[serde(alias = "apiKey")]
+    pub api_key: Option<String>,
+    /// Google Custom Search engine id
+    pub cx: Option<String>,
+}
+
 /// Web Search configuration for frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebConfig {
+    /// Kept for compatibility; mirrors web.providers.brave.apiKey
     pub brave_api_key: Option<String>,
+    pub active_provider: Option<String>,
+    pub providers: Vec<WebSearchProvider>,
+}
+
+/// Move a legacy web.braveApiKey into web.providers.brave. Returns true if anything changed.
+pub(crate) fn migrate_legacy_web_config(config: &mut Value) -> bool {
+    let Some(web) = config.get_mut("web").and_then(|v| v.as_object_mut()) else {
+        return false;
+    };
+    let Some(legacy) = web.shift_remove("braveApiKey") else {
+        return false;
+    };
+
+    if let Some(key) = legacy.as_str().filter(|k| !k.is_empty()) {
+        let providers = web.entry("providers").or_insert_with(|| json!({}));
+        // An explicit new-style key wins over the legacy one
+        if providers.pointer("/brave/apiKey").is_none() {
+            providers["brave"] = json!({ "apiKey": key });
+        }
+        if !web.contains_key("activeProvider") {
+            web.insert("activeProvider".to_string(), json!("brave"));
+        }
+    }
+    true
+}
+
+/// Read configured web search providers from web.providers
+pub(crate) fn web_providers_from_config(config: &Value) -> Vec<WebSearchProvider> {
+    let mut providers: Vec<WebSearchProvider> = config.pointer("/web/providers")
+        .and_then(|v| v.as_object())
+        .map(|map| map.iter().map(|(id, p)| WebSearchProvider {
+            id: id.clone(),
+            api_key: p.get("apiKey").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            cx: p.get("cx").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        }).collect())
+        .unwrap_or_default();
+    providers.sort_by(|a, b| a.id.cmp(&b.id));
+    providers
 }
 
 /// Get web search configuration
 #[command]
 pub async fn get_web_config() -> Result<WebConfig, String> {
     info!("[Web] Getting web search config...");
+    // Legacy web.braveApiKey is moved by the v2 config migration when loading
     let config = load_openclaw_config()?;
 
-    let brave_api_key = config.pointer("/web/braveApiKey")
+    let providers = web_providers_from_config(&config);
+    let brave_api_key = providers.iter()
+        .find(|p| p.id == "brave")
+        .and_then(|p| p.api_key.clone());
+    let active_provider = config.pointer("/web/activeProvider")
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
 
-    Ok(WebConfig { brave_api_key })
+    Ok(WebConfig { brave_api_key, active_provider, providers })
 }
 
 /// Save web search configuration
+/// `providers` replaces the full provider set; when omitted only `brave_api_key` is applied (legacy callers)
 #[command]
-pub async fn save_web_config(brave_api_key: Option<String>) -> Result<String, String> {
+pub async fn save_web_config(
+    brave_api_key: Option<String>,
+    active_provider: Option<String>,
+    providers: Option<Vec<WebSearchProvider>>,
+) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Web] Saving web search config...");
     let mut config = load_openclaw_config()?;
 
     if config.get("web").is_none() {
         config["web"] = json!({});
     }
+    migrate_legacy_web_config(&mut config);
+    if config["web"].get("providers").is_none() {
+        config["web"]["providers"] = json!({});
+    }
 
-    match brave_api_key {
-        Some(key) if !key.is_empty() => {
-            config["web"]["braveApiKey"] = json!(key);
-        }
-        _ => {
-            if let Some(web) = config.get_mut("web").and_then(|v| v.as_object_mut()) {
-                web.remove("braveApiKey");
+    match providers {
+        Some(list) => {
+            let mut map = serde_json::Map::new();
+            for p in list {
+                if !WEB_SEARCH_PROVIDERS.contains(&p.id.as_str()) {
+                    return Err(format!("Unknown web search provider '{}'. Supported: {:?}", p.id, WEB_SEARCH_PROVIDERS));
+                }
+                let Some(key) = p.api_key.filter(|k| !k.is_empty()) else {
+                    continue;
+                };
+                let mut entry = json!({ "apiKey": key });
+                if p.id == "google" {
+                    match p.cx.filter(|c| !c.is_empty()) {
+                        Some(cx) => entry["cx"] = json!(cx),
+                        None => return Err("Google search requires a search engine id (cx)".to_string()),
+                    }
+                }
+                map.insert(p.id, entry);
             }
+            config["web"]["providers"] = Value::Object(map);
         }
+        None => match brave_api_key {
+            Some(key) if !key.is_empty() => {
+                config["web"]["providers"]["brave"] = json!({ "apiKey": key });
+            }
+            _ => {
+                if let Some(p) = config.pointer_mut("/web/providers").and_then(|v| v.as_object_mut()) {
+                    p.shift_remove("brave");
+                }
+            }
+        },
     }
 
+    match active_provider.filter(|a| !a.is_empty()) {
+        Some(active) => {
+            if config["web"]["providers"].get(&active).is_none() {
+                return Err(format!("Active web search provider '{}' is not configured", active));
+            }
+            config["web"]["activeProvider"] = json!(active);
+        }
+        None => {
+            // Drop a selector pointing at a provider that was just removed
+            let active = config.pointer("/web/activeProvider").and_then(|v| v.as_str()).map(|s| s.to_string());
+            if let Some(active) = active {
+                if config["web"]["providers"].get(&active).is_none() {
+                    if let Some(web) = config.get_mut("web").and_then(|v| v.as_object_mut()) {
+                        web.shift_remove("activeProvider");
+                    }
+                }
+            }
+        }
+    }
 
     save_openclaw_config(&config)?;
     Ok("Web search configuration saved".to_string())
@@ -3540,9 +8262,50 @@ pub async fn get_gateway_config() -> Result<GatewayConfig, String> {
     Ok(GatewayConfig { port, log_level })
 }
 
+/// Gateway mode and auth state for the frontend (the token itself is never returned)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GatewayStatus {
+    /// gateway.mode (e.g. "local"), None when not set
+    pub mode: Option<String>,
+    /// gateway.auth.mode (e.g. "token"), None when not set
+    pub auth_mode: Option<String>,
+    /// Whether gateway.auth.token is set
+    pub has_token: bool,
+    /// gateway.port, or the gateway default
+    pub port: u16,
+}
+
+/// Read the gateway status from the config
+pub(crate) fn gateway_status_from(config: &Value) -> GatewayStatus {
+    let non_empty = |pointer: &str| config.pointer(pointer)
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+
+    GatewayStatus {
+        mode: non_empty("/gateway/mode"),
+        auth_mode: non_empty("/gateway/auth/mode"),
+        has_token: non_empty("/gateway/auth/token").is_some(),
+        port: config.pointer("/gateway/port")
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u16::try_from(v).ok())
+            .unwrap_or(18789),
+    }
+}
+
+/// Get gateway mode, auth mode, token presence and port
+#[command]
+pub async fn get_gateway_status() -> Result<GatewayStatus, String> {
+    info!("[Gateway] Getting gateway status...");
+    let config = load_openclaw_config()?;
+    Ok(gateway_status_from(&config))
+}
+
 /// Save gateway configuration
 #[command]
 pub async fn save_gateway_config(port: u16, log_level: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Gateway] Saving gateway config: port={}, level={}", port, log_level);
     let mut config = load_openclaw_config()?;
 
@@ -3553,8 +8316,8 @@ pub async fn save_gateway_config(port: u16, log_level: String) -> Result<String,
     if let Some(gateway) = config.get_mut("gateway").and_then(|v| v.as_object_mut()) {
         gateway.insert("port".to_string(), json!(port));
         // Remove legacy logLevel if exists
-        gateway.remove("logLevel");
-        gateway.remove("log_level");
+        gateway.shift_remove("logLevel");
+        gateway.shift_remove("log_level");
     }
 
     if config.get("manager").is_none() {
@@ -3571,6 +8334,14 @@ pub async fn save_gateway_config(port: u16, log_level: String) -> Result<String,
 
 // ============ Configuration Management ============
 
+/// Validate openclaw.json against the config schema (path + message per violation)
+#[command]
+pub async fn validate_config() -> Result<Vec<ValidationError>, String> {
+    info!("[Config] Validating configuration against schema...");
+    let config = load_openclaw_config()?;
+    Ok(validate_config_value(&config))
+}
+
 /// Export configuration
 #[command]
 pub async fn export_config(path: String) -> Result<String, String> {
@@ -3589,6 +8360,7 @@ pub async fn export_config(path: String) -> Result<String, String> {
 /// Import configuration
 #[command]
 pub async fn import_config(path: String) -> Result<String, String> {
+    let _config_lock = lock_config().await;
     info!("[Config] Importing config from: {}", path);
 
     let content = file::read_file(&path)
@@ -3605,3 +8377,602 @@ pub async fn import_config(path: String) -> Result<String, String> {
 
     Ok("Configuration imported successfully".to_string())
 }
+
+/// Placeholder written in place of secret values
+pub(crate) const REDACTED_PLACEHOLDER: &str = "***REDACTED***";
+
+/// Format marker stored in a bundle's manifest.json
+const BUNDLE_FORMAT: &str = "openclaw-bundle";
+const BUNDLE_VERSION: u64 = 1;
+/// Largest single entry accepted when reading a bundle
+const BUNDLE_MAX_ENTRY_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Contents of a portable configuration bundle
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConfigBundle {
+    /// openclaw.json
+    pub config: Value,
+    /// mcps.json (Manager MCP servers)
+    pub mcps: Option<Value>,
+    /// Personality markdown files (file name, content)
+    pub personality: Vec<(String, String)>,
+    /// Raw env file (only present when secrets were explicitly included)
+    pub env: Option<String>,
+}
+
+/// Write a bundle as a zip archive. With `strip_secrets`, secret values in openclaw.json
+/// and mcps.json are replaced with the redaction placeholder.
+pub(crate) fn write_config_bundle(bundle: &ConfigBundle, strip_secrets: bool) -> Result<Vec<u8>, String> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let mut config = bundle.config.clone();
+    let mut mcps = bundle.mcps.clone();
+    if strip_secrets {
+        redact_secrets(&mut config);
+        if let Some(m) = mcps.as_mut() {
+            redact_secrets(m);
+        }
+    }
+
+    let manifest = json!({
+        "format": BUNDLE_FORMAT,
+        "version": BUNDLE_VERSION,
+        "createdAt": chrono::Utc::now().to_rfc3339(),
+        "secretsStripped": strip_secrets,
+        "includesEnv": bundle.env.is_some(),
+    });
+
+    let mut entries: Vec<(String, String)> = vec![
+        ("manifest.json".to_string(), serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?),
+        ("openclaw.json".to_string(), serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?),
+    ];
+    if let Some(m) = &mcps {
+        entries.push(("mcps.json".to_string(), serde_json::to_string_pretty(m).map_err(|e| e.to_string())?));
+    }
+    for (name, content) in &bundle.personality {
+        validate_personality_filename(name)?;
+        entries.push((format!("personality/{}", name), content.clone()));
+    }
+    if let Some(env) = &bundle.env {
+        entries.push(("env".to_string(), env.clone()));
+    }
+
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in entries {
+        writer.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+        writer.write_all(content.as_bytes())
+            .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))?;
+    }
+    let cursor = writer.finish().map_err(|e| format!("Failed to finish bundle: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+/// Read and validate a bundle zip. Unknown entries, path tricks and oversized files are rejected.
+pub(crate) fn read_config_bundle(data: &[u8]) -> Result<ConfigBundle, String> {
+    use std::io::Read;
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data))
+        .map_err(|e| format!("Not a valid bundle archive: {}", e))?;
+
+    let mut manifest: Option<Value> = None;
+    let mut config: Option<Value> = None;
+    let mut bundle = ConfigBundle::default();
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)
+            .map_err(|e| format!("Failed to read bundle entry: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        if entry.size() > BUNDLE_MAX_ENTRY_SIZE {
+            return Err(format!("Bundle entry {} is too large", name));
+        }
+        let mut content = String::new();
+        entry.read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read {} from bundle: {}", name, e))?;
+
+        match name.as_str() {
+            "manifest.json" => manifest = Some(serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid manifest.json in bundle: {}", e))?),
+            "openclaw.json" => config = Some(serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid openclaw.json in bundle: {}", e))?),
+            "mcps.json" => bundle.mcps = Some(serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid mcps.json in bundle: {}", e))?),
+            "env" => bundle.env = Some(content),
+            other => match other.strip_prefix("personality/") {
+                Some(file_name) => {
+                    validate_personality_filename(file_name)?;
+                    bundle.personality.push((file_name.to_string(), content));
+                }
+                None => return Err(format!("Unexpected file in bundle: {}", other)),
+            },
+        }
+    }
+
+    let manifest = manifest.ok_or("Bundle is missing manifest.json")?;
+    if manifest.get("format").and_then(|v| v.as_str()) != Some(BUNDLE_FORMAT) {
+        return Err("manifest.json does not describe an OpenClaw bundle".to_string());
+    }
+    let version = manifest.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > BUNDLE_VERSION {
+        return Err(format!("Unsupported bundle version {} (this Manager supports {})", version, BUNDLE_VERSION));
+    }
+
+    bundle.config = config.ok_or("Bundle is missing openclaw.json")?;
+    if !bundle.config.is_object() {
+        return Err("openclaw.json in bundle is not a configuration object".to_string());
+    }
+    if bundle.mcps.as_ref().is_some_and(|m| !m.is_object()) {
+        return Err("mcps.json in bundle is not an object".to_string());
+    }
+    Ok(bundle)
+}
+
+/// Merge an imported document into `local`. Objects merge key by key, other values are replaced.
+/// Redacted placeholders keep the local value (or are dropped if there is none); the config
+/// paths of dropped secrets are returned so the user knows what to re-enter.
+pub(crate) fn merge_imported_config(local: &mut Value, imported: &Value) -> Vec<String> {
+    fn is_placeholder(v: &Value) -> bool {
+        v.as_str() == Some(REDACTED_PLACEHOLDER)
+    }
+
+    fn drop_placeholders(value: &mut Value, path: &str, missing: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                let redacted: Vec<String> = map.iter()
+                    .filter(|(_, v)| is_placeholder(v))
+                    .map(|(k, _)| k.clone())
+                    .collect();
+                for key in redacted {
+                    map.shift_remove(&key);
+                    missing.push(format!("{}/{}", path, escape_pointer_token(&key)));
+                }
+                for (key, v) in map.iter_mut() {
+                    drop_placeholders(v, &format!("{}/{}", path, escape_pointer_token(key)), missing);
+                }
+            }
+            Value::Array(arr) => {
+                for (i, v) in arr.iter_mut().enumerate() {
+                    drop_placeholders(v, &format!("{}/{}", path, i), missing);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn merge(local: &mut Value, imported: &Value, path: &str, missing: &mut Vec<String>) {
+        match (local.as_object_mut(), imported.as_object()) {
+            (Some(local_map), Some(imported_map)) => {
+                for (key, value) in imported_map {
+                    let child = format!("{}/{}", path, escape_pointer_token(key));
+                    if is_placeholder(value) {
+                        if !local_map.get(key).is_some_and(|v| v.is_string() && !is_placeholder(v)) {
+                            local_map.shift_remove(key);
+                            missing.push(child);
+                        }
+                        continue;
+                    }
+                    match local_map.get_mut(key) {
+                        Some(existing) => merge(existing, value, &child, missing),
+                        None => {
+                            let mut value = value.clone();
+                            drop_placeholders(&mut value, &child, missing);
+                            local_map.insert(key.clone(), value);
+                        }
+                    }
+                }
+            }
+            _ => {
+                *local = imported.clone();
+                drop_placeholders(local, path, missing);
+            }
+        }
+    }
+
+    let mut missing = Vec::new();
+    merge(local, imported, "", &mut missing);
+    missing
+}
+
+
+/// Export openclaw.json, mcps.json and personality files as a portable zip bundle.
+/// Secrets are stripped unless `strip_secrets` is false; the env file (API keys) is only
+/// included when `include_env_secrets` is explicitly true.
+#[command]
+pub async fn export_config_bundle(
+    dest_path: String,
+    strip_secrets: Option<bool>,
+    include_env_secrets: Option<bool>,
+) -> Result<String, String> {
+    let strip_secrets = strip_secrets.unwrap_or(true);
+    let include_env_secrets = include_env_secrets.unwrap_or(false);
+    info!("[Bundle] Exporting to {} (strip secrets: {}, env: {})", dest_path, strip_secrets, include_env_secrets);
+
+    let mut config = load_openclaw_config()?;
+    if !strip_secrets && keyring_mode_enabled(&config) {
+        // Keyring placeholders mean nothing on another machine
+        secrets::internalize_secrets(&mut config, &mut secrets::load_secret)?;
+    }
+
+    let mcp_path = platform::get_mcp_config_file_path();
+    let mcps = if file::file_exists(&mcp_path) {
+        let content = file::read_file(&mcp_path).map_err(|e| format!("Failed to read mcps.json: {}", e))?;
+        Some(serde_json::from_str::<Value>(&content).map_err(|e| format!("Failed to parse mcps.json: {}", e))?)
+    } else {
+        None
+    };
+
+    let dir = personality_dir(&config);
+    let mut personality = Vec::new();
+    if dir.is_dir() {
+        for name in list_personality_files().await? {
+            let path = resolve_workspace_file(&dir, &name)?;
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+            personality.push((name, content));
+        }
+    }
+
+    let env = match file::read_file(&platform::get_env_file_path()) {
+        // Keyring references mean nothing on another machine
+        Ok(content) if include_env_secrets => Some(secrets::internalize_env_secrets(&content, &mut secrets::load_secret)?.0),
+        _ => None,
+    };
+
+    let bundle = ConfigBundle { config, mcps, personality, env };
+    let data = write_config_bundle(&bundle, strip_secrets)?;
+    std::fs::write(&dest_path, data)
+        .map_err(|e| format!("Failed to write bundle {}: {}", dest_path, e))?;
+
+    Ok(format!(
+        "Bundle exported to {} ({} personality files{}{})",
+        dest_path,
+        bundle.personality.len(),
+        if strip_secrets { ", secrets stripped" } else { ", secrets included" },
+        if bundle.env.is_some() { ", env secrets included" } else { "" },
+    ))
+}
+
+/// Undo an imported agents.defaults.workspace, restoring `local` (or removing the key when there
+/// was none). Returns the imported path when it differed from the local one.
+pub(crate) fn keep_local_workspace(config: &mut Value, local: Option<Value>) -> Option<String> {
+    let imported = config.pointer("/agents/defaults/workspace").cloned();
+    if imported == local {
+        return None;
+    }
+    let defaults = config.pointer_mut("/agents/defaults").and_then(|v| v.as_object_mut())?;
+    match local {
+        Some(local) => { defaults.insert("workspace".to_string(), local); }
+        None => { defaults.shift_remove("workspace"); }
+    }
+    imported.map(|v| v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string()))
+}
+
+/// Validate a bundle and merge it into the current setup. openclaw.json is backed up first.
+/// Returns a summary of what was imported, including secrets that must be re-entered.
+#[command]
+pub async fn import_config_bundle(src_path: String) -> Result<Vec<String>, String> {
+    let _config_lock = lock_config().await;
+    info!("[Bundle] Importing from {}", src_path);
+    let data = std::fs::read(&src_path)
+        .map_err(|e| format!("Failed to read bundle {}: {}", src_path, e))?;
+    let bundle = read_config_bundle(&data)?;
+
+    let mut summary = Vec::new();
+
+    // 1. openclaw.json
+    let mut config = load_openclaw_config()?;
+    if file::file_exists(&platform::get_config_file_path()) {
+        let backup = backup_openclaw_config()?;
+        summary.push(format!("Backed up current configuration to {}", backup));
+    }
+    let local_workspace = config.pointer("/agents/defaults/workspace").cloned();
+    let missing = merge_imported_config(&mut config, &bundle.config);
+    // The exporter's workspace path belongs to their machine: keep this user's workspace
+    if let Some(imported) = keep_local_workspace(&mut config, local_workspace) {
+        summary.push(format!("Kept the local workspace directory (bundle used {})", imported));
+    }
+    save_openclaw_config(&config)?;
+    summary.push("Merged openclaw.json".to_string());
+    for path in missing {
+        summary.push(format!("Secret not included in bundle, please re-enter: {}", path));
+    }
+
+    // 2. mcps.json
+    if let Some(imported) = &bundle.mcps {
+        let mut mcps = serde_json::to_value(load_mcp_config_file()?).map_err(|e| e.to_string())?;
+        let missing = merge_imported_config(&mut mcps, imported);
+        let configs: HashMap<String, MCPConfig> = serde_json::from_value(mcps)
+            .map_err(|e| format!("Invalid MCP configuration in bundle: {}", e))?;
+        write_mcp_config_file(&configs)?;
+        summary.push(format!("Merged {} MCP servers", imported.as_object().map(|m| m.len()).unwrap_or(0)));
+        if let Err(e) = sync_mcp_config(&configs) {
+            summary.push(e);
+        }
+        for path in missing {
+            summary.push(format!("MCP secret not included in bundle, please re-enter: {}", path));
+        }
+    }
+
+    // 3. Personality files
+    if !bundle.personality.is_empty() {
+        let dir = personality_dir(&config);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create workspace directory {}: {}", dir.display(), e))?;
+        for (name, content) in &bundle.personality {
+            let path = resolve_workspace_file(&dir, name)?;
+            std::fs::write(&path, content)
+                .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        }
+        summary.push(format!("Restored {} personality files", bundle.personality.len()));
+    }
+
+    // 4. Env secrets (only present if the exporter opted in)
+    if let Some(env) = &bundle.env {
+        let env_path = platform::get_env_file_path();
+        let vars = file::parse_env_exports(env);
+        for (key, value) in &vars {
+            file::set_env_value(&env_path, key, value)
+                .map_err(|e| format!("Failed to write {} to env file: {}", key, e))?;
+        }
+        summary.push(format!("Imported {} env variables", vars.len()));
+    }
+
+    info!("[Bundle] Import complete: {:?}", summary);
+    Ok(summary)
+}
+
+/// Recursively replace secret string values with a placeholder
+pub(crate) fn redact_secrets(value: &mut Value) {
+    secrets::replace_secrets(value, &mut |_| REDACTED_PLACEHOLDER.to_string());
+}
+
+/// Copy the requested dot-separated sections (e.g. "models.providers.anthropic") into a new document
+pub(crate) fn extract_config_sections(config: &Value, sections: &[String]) -> Result<Value, String> {
+    let mut out = json!({});
+
+    for section in sections {
+        let parts: Vec<&str> = section.split('.').map(|p| p.trim()).filter(|p| !p.is_empty()).collect();
+        if parts.is_empty() {
+            continue;
+        }
+
+        let mut src = config;
+        for part in &parts {
+            src = src.get(*part)
+                .ok_or_else(|| format!("Section '{}' not found in config", section))?;
+        }
+
+        let mut dst = &mut out;
+        for part in &parts[..parts.len() - 1] {
+            if !dst.get(*part).map(|v| v.is_object()).unwrap_or(false) {
+                dst[*part] = json!({});
+            }
+            dst = &mut dst[*part];
+        }
+        dst[parts[parts.len() - 1]] = src.clone();
+    }
+
+    redact_secrets(&mut out);
+    Ok(out)
+}
+
+/// Generate a minimal, redacted config containing only the requested sections (for bug reports)
+#[command]
+pub async fn generate_minimal_config(sections: Vec<String>) -> Result<Value, String> {
+    info!("[Config] Generating minimal config for sections: {:?}", sections);
+    if sections.is_empty() {
+        return Err("At least one section is required".to_string());
+    }
+
+    let config = load_openclaw_config()?;
+    extract_config_sections(&config, &sections)
+}
+
+/// Patch operation for apply_config_patch
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PatchOp {
+    #[default]
+    Set,
+    Delete,
+}
+
+/// A single config change: JSON pointer (RFC 6901) plus value, or a delete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigPatch {
+    pub pointer: String,
+    #[serde(default)]
+    pub op: PatchOp,
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+/// Split a JSON pointer into unescaped tokens
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if !pointer.starts_with('/') || pointer.len() < 2 {
+        return Err(format!("Invalid pointer '{}': must start with '/' and name a key", pointer));
+    }
+    Ok(pointer[1..].split('/').map(|t| t.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+/// Apply a single patch in place
+fn apply_patch(config: &mut Value, patch: &ConfigPatch) -> Result<(), String> {
+    let tokens = parse_pointer(&patch.pointer)?;
+    let (last, parents) = tokens.split_last().ok_or("Empty pointer")?;
+    let invalid = |reason: &str| format!("Invalid pointer '{}': {}", patch.pointer, reason);
+
+    // Walk to the parent container; `set` creates missing objects on the way
+    let mut target = config;
+    for token in parents {
+        target = match target {
+            Value::Object(map) => {
+                if !map.contains_key(token) {
+                    if patch.op == PatchOp::Delete {
+                        return Err(invalid("path does not exist"));
+                    }
+                    map.insert(token.clone(), json!({}));
+                }
+                map.get_mut(token).unwrap()
+            }
+            Value::Array(arr) => {
+                let idx: usize = token.parse().map_err(|_| invalid("expected an array index"))?;
+                arr.get_mut(idx).ok_or_else(|| invalid("array index out of range"))?
+            }
+            _ => return Err(invalid("traverses a non-container value")),
+        };
+    }
+
+    match (patch.op, target) {
+        (PatchOp::Set, Value::Object(map)) => {
+            let value = patch.value.clone().ok_or_else(|| invalid("set requires a value"))?;
+            map.insert(last.clone(), value);
+        }
+        (PatchOp::Set, Value::Array(arr)) => {
+            let value = patch.value.clone().ok_or_else(|| invalid("set requires a value"))?;
+            if last == "-" {
+                arr.push(value);
+            } else {
+                let idx: usize = last.parse().map_err(|_| invalid("expected an array index"))?;
+                match idx.cmp(&arr.len()) {
+                    std::cmp::Ordering::Less => arr[idx] = value,
+                    std::cmp::Ordering::Equal => arr.push(value),
+                    std::cmp::Ordering::Greater => return Err(invalid("array index out of range")),
+                }
+            }
+        }
+        (PatchOp::Delete, Value::Object(map)) => {
+            map.shift_remove(last).ok_or_else(|| invalid("path does not exist"))?;
+        }
+        (PatchOp::Delete, Value::Array(arr)) => {
+            let idx: usize = last.parse().map_err(|_| invalid("expected an array index"))?;
+            if idx >= arr.len() {
+                return Err(invalid("array index out of range"));
+            }
+            arr.remove(idx);
+        }
+        _ => return Err(invalid("parent is not an object or array")),
+    }
+    Ok(())
+}
+
+/// Apply all patches to a copy of the config; any failure rejects the whole batch
+pub(crate) fn apply_config_patches(config: &Value, patches: &[ConfigPatch]) -> Result<Value, String> {
+    let mut updated = config.clone();
+    for (i, patch) in patches.iter().enumerate() {
+        apply_patch(&mut updated, patch).map_err(|e| format!("Patch {} rejected: {}", i + 1, e))?;
+    }
+    Ok(updated)
+}
+
+/// Apply several config changes atomically (one load, one save, all-or-nothing)
+#[command]
+pub async fn apply_config_patch(patches: Vec<ConfigPatch>) -> Result<String, String> {
+    let _config_lock = lock_config().await;
+    info!("[Config] Applying {} config patches", patches.len());
+    let config = load_openclaw_config()?;
+
+    let updated = apply_config_patches(&config, &patches)?;
+    save_openclaw_config(&updated)?;
+
+    Ok(format!("Applied {} config changes", patches.len()))
+}
+
+/// Kind of difference reported by diff_config
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// One difference between the current and a proposed config
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfigChange {
+    /// JSON pointer (RFC 6901) of the changed value
+    pub pointer: String,
+    pub kind: ChangeKind,
+    /// Current value (secrets masked), absent for additions
+    pub old_value: Option<Value>,
+    /// Proposed value (secrets masked), absent for removals
+    pub new_value: Option<Value>,
+}
+
+/// Mask a value for display in a diff; `key` is the object key it sits under
+fn mask_diff_value(key: Option<&str>, value: &Value) -> Value {
+    match value {
+        Value::String(s) if key.is_some_and(secrets::is_secret_key) => json!(mask_secret(s)),
+        _ => mask_secrets_for_log(value),
+    }
+}
+
+/// Recursively diff two configs. Objects are compared key by key and arrays index by index;
+/// any other difference (including a type change) is reported as a modification of the whole value.
+pub(crate) fn diff_config_values(current: &Value, proposed: &Value) -> Vec<ConfigChange> {
+    fn walk(path: &str, key: Option<&str>, old: &Value, new: &Value, changes: &mut Vec<ConfigChange>) {
+        let child = |token: &str| format!("{}/{}", path, token.replace('~', "~0").replace('/', "~1"));
+        let added = |pointer: String, k: Option<&str>, v: &Value| ConfigChange {
+            pointer, kind: ChangeKind::Added, old_value: None, new_value: Some(mask_diff_value(k, v)),
+        };
+        let removed = |pointer: String, k: Option<&str>, v: &Value| ConfigChange {
+            pointer, kind: ChangeKind::Removed, old_value: Some(mask_diff_value(k, v)), new_value: None,
+        };
+
+        match (old, new) {
+            (Value::Object(a), Value::Object(b)) => {
+                for (k, v) in a {
+                    match b.get(k) {
+                        Some(nv) => walk(&child(k), Some(k), v, nv, changes),
+                        None => changes.push(removed(child(k), Some(k), v)),
+                    }
+                }
+                for (k, v) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                    changes.push(added(child(k), Some(k), v));
+                }
+            }
+            (Value::Array(a), Value::Array(b)) => {
+                for i in 0..a.len().max(b.len()) {
+                    match (a.get(i), b.get(i)) {
+                        (Some(ov), Some(nv)) => walk(&child(&i.to_string()), key, ov, nv, changes),
+                        (Some(ov), None) => changes.push(removed(child(&i.to_string()), key, ov)),
+                        (None, Some(nv)) => changes.push(added(child(&i.to_string()), key, nv)),
+                        (None, None) => {}
+                    }
+                }
+            }
+            _ if old != new => changes.push(ConfigChange {
+                pointer: if path.is_empty() { "/".to_string() } else { path.to_string() },
+                kind: ChangeKind::Modified,
+                old_value: Some(mask_diff_value(key, old)),
+                new_value: Some(mask_diff_value(key, new)),
+            }),
+            _ => {}
+        }
+    }
+
+    let mut changes = Vec::new();
+    walk("", None, current, proposed, &mut changes);
+    changes
+}
+
+/// List what saving `proposed` would change in openclaw.json, without writing anything
+#[command]
+pub async fn diff_config(proposed: Value) -> Result<Vec<ConfigChange>, String> {
+    let current = load_openclaw_config()?;
+    let changes = diff_config_values(&current, &proposed);
+    info!("[Config] Proposed configuration has {} changes", changes.len());
+    Ok(changes)
+}
+
+/// List what apply_config_patch would change, without writing anything
+#[command]
+pub async fn preview_config_patch(patches: Vec<ConfigPatch>) -> Result<Vec<ConfigChange>, String> {
+    let current = load_openclaw_config()?;
+    let updated = apply_config_patches(&current, &patches)?;
+    Ok(diff_config_values(&current, &updated))
+}
@@ -0,0 +1,2566 @@
+#[cfg(test)]
+mod tests {
+    use super::super::config::*;
+    use crate::models::McpTransport;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    /// Serializes tests that depend on the process-wide config scope
+    static CONFIG_SCOPE_TEST_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    #[test]
+    fn test_peer_match_exact() {
+        let peer = PeerMatch { kind: Some("dm".to_string()), id: "U123".to_string(), mode: PeerMatchMode::Exact, ..Default::default() };
+        assert!(peer.validate().is_ok());
+        assert!(peer.matches("U123"));
+        assert!(!peer.matches("U1234"));
+        // Exact peers serialize without a mode field so existing gateway configs are unchanged
+        assert_eq!(serde_json::to_value(&peer).unwrap(), json!({ "kind": "dm", "id": "U123" }));
+    }
+
+    #[test]
+    fn test_peer_match_prefix() {
+        let peer = PeerMatch { kind: None, id: "T0ABC-".to_string(), mode: PeerMatchMode::Prefix, ..Default::default() };
+        assert!(peer.validate().is_ok());
+        assert!(peer.matches("T0ABC-U1"));
+        assert!(!peer.matches("T0XYZ-U1"));
+        assert_eq!(serde_json::to_value(&peer).unwrap(), json!({ "id": "T0ABC-", "mode": "prefix" }));
+    }
+
+    #[test]
+    fn test_peer_match_regex() {
+        let peer = PeerMatch { kind: None, id: r"U\d+".to_string(), mode: PeerMatchMode::Regex, ..Default::default() };
+        assert!(peer.validate().is_ok());
+        assert!(peer.matches("U42"));
+        // Anchored: partial matches are rejected
+        assert!(!peer.matches("xU42"));
+        assert!(!peer.matches("U42x"));
+    }
+
+    #[test]
+    fn test_peer_match_invalid_regex_rejected() {
+        let peer = PeerMatch { kind: None, id: "U(".to_string(), mode: PeerMatchMode::Regex, ..Default::default() };
+        let err = peer.validate().unwrap_err();
+        assert!(err.contains("Invalid peer regex"));
+    }
+
+    #[test]
+    fn test_peer_match_from_legacy_value() {
+        let peer = PeerMatch::from_value(&json!("12345")).unwrap();
+        assert_eq!(peer.mode, PeerMatchMode::Exact);
+        assert!(peer.matches("12345"));
+
+        let peer = PeerMatch::from_value(&json!({ "kind": "group", "id": -100, "mode": "prefix" })).unwrap();
+        assert_eq!(peer.kind.as_deref(), Some("group"));
+        assert_eq!(peer.mode, PeerMatchMode::Prefix);
+        assert!(peer.matches("-1001"));
+    }
+
+    #[test]
+    fn test_peer_match_keeps_stored_type() {
+        for stored in [json!("12345"), json!(12345), json!({ "kind": "group", "id": -100 }), json!({ "id": "U1", "mode": "regex" })] {
+            let peer = PeerMatch::from_value(&stored).unwrap();
+            assert_eq!(peer.to_value(), stored);
+            // Round trip through the UI (serde) as well
+            let from_ui: PeerMatch = serde_json::from_value(serde_json::to_value(&peer).unwrap()).unwrap();
+            assert_eq!(from_ui.to_value(), stored);
+        }
+
+        // A bare peer given a mode is written as an object, keeping the numeric id
+        let mut peer = PeerMatch::from_value(&json!(42)).unwrap();
+        peer.mode = PeerMatchMode::Prefix;
+        assert_eq!(peer.to_value(), json!({ "id": 42, "mode": "prefix" }));
+    }
+
+    fn sample_official() -> crate::models::OfficialProvider {
+        crate::models::OfficialProvider {
+            id: "anthropic".to_string(),
+            name: "Anthropic Claude".to_string(),
+            icon: "🟣".to_string(),
+            default_base_url: Some("https://api.anthropic.com".to_string()),
+            api_type: "anthropic-messages".to_string(),
+            suggested_models: vec![crate::models::SuggestedModel {
+                id: "claude-opus-4-5-20251101".to_string(),
+                name: "Claude Opus 4.5".to_string(),
+                description: None,
+                context_window: Some(200000),
+                max_tokens: Some(8192),
+                recommended: true,
+            }],
+            requires_api_key: true,
+            docs_url: None,
+        }
+    }
+
+    #[test]
+    fn test_provider_diff_matches_defaults() {
+        let provider = json!({
+            "baseUrl": "https://api.anthropic.com/",
+            "models": [{ "id": "claude-opus-4-5-20251101", "api": "anthropic-messages" }]
+        });
+        assert!(compute_provider_deltas(&provider, &sample_official()).is_empty());
+    }
+
+    #[test]
+    fn test_provider_diff_changed_base_url() {
+        let provider = json!({
+            "baseUrl": "https://proxy.example.com/v1",
+            "models": [{ "id": "claude-opus-4-5-20251101", "api": "anthropic-messages" }]
+        });
+        let deltas = compute_provider_deltas(&provider, &sample_official());
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].field, "base_url");
+        assert_eq!(deltas[0].configured.as_deref(), Some("https://proxy.example.com/v1"));
+        assert_eq!(deltas[0].official.as_deref(), Some("https://api.anthropic.com"));
+    }
+
+    #[test]
+    fn test_clone_agent_drops_workspace_and_moves_bindings() {
+        let mut config = json!({
+            "agents": {
+                "list": [{ "id": "main", "default": true, "workspace": "/ws/main", "model": { "primary": "a/b" } }],
+                "bindings": [{ "agentId": "main", "match": { "channel": "discord" } }]
+            },
+            "bindings": [
+                { "agentId": "main", "match": { "channel": "telegram" } },
+                { "agentId": "other", "match": { "channel": "slack" } }
+            ]
+        });
+        clone_agent_in_config(&mut config, "main", "helper", true, false).unwrap();
+
+        let list = config["agents"]["list"].as_array().unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1]["id"], "helper");
+        assert_eq!(list[1]["model"]["primary"], "a/b");
+        assert!(list[1].get("workspace").is_none());
+        assert!(list[1].get("default").is_none());
+        // Bindings are handed over in place, not duplicated
+        assert_eq!(config["bindings"], json!([
+            { "agentId": "helper", "match": { "channel": "telegram" } },
+            { "agentId": "other", "match": { "channel": "slack" } }
+        ]));
+        assert_eq!(config["agents"]["bindings"], json!([{ "agentId": "helper", "match": { "channel": "discord" } }]));
+
+        // Existing ids are rejected
+        assert!(clone_agent_in_config(&mut config, "main", "helper", false, false).is_err());
+    }
+
+    #[test]
+    fn test_mcp_test_timeout_uses_persisted_default() {
+        let config = json!({ "meta": { "gui": { "mcpTest": { "timeoutMs": 7500, "headers": { "X-Api-Key": "k" } } } } });
+        let defaults = mcp_test_defaults_from_config(&config);
+        assert_eq!(defaults.headers.get("X-Api-Key").map(String::as_str), Some("k"));
+
+        // No explicit timeout: the persisted one wins over the built-in fallback
+        assert_eq!(resolve_mcp_test_timeout(None, &defaults, 3000), 7500);
+        // Explicit timeout still overrides
+        assert_eq!(resolve_mcp_test_timeout(Some(1200), &defaults, 3000), 1200);
+        // Nothing persisted: built-in fallback
+        assert_eq!(resolve_mcp_test_timeout(None, &mcp_test_defaults_from_config(&json!({})), 3000), 3000);
+    }
+
+    #[test]
+    fn test_agent_models_round_trip() {
+        let existing = json!({ "anthropic/claude-opus-4-5": { "alias": "deep" } });
+        let models = vec!["anthropic/claude-opus-4-5".to_string(), "openai/gpt-4o-mini".to_string()];
+        let built = build_agent_models(Some(&existing), &models);
+        // Existing per-model settings are kept, new entries mirror the defaults shape
+        assert_eq!(built, json!({ "anthropic/claude-opus-4-5": { "alias": "deep" }, "openai/gpt-4o-mini": {} }));
+
+        let mut read = agent_models_from_value(&json!({ "id": "deep", "models": built })).unwrap();
+        read.sort();
+        assert_eq!(read, vec!["anthropic/claude-opus-4-5", "openai/gpt-4o-mini"]);
+        assert!(agent_models_from_value(&json!({ "id": "plain" })).is_none());
+    }
+
+    #[test]
+    fn test_minimal_config_extracts_and_redacts() {
+        let config = json!({
+            "models": { "providers": {
+                "anthropic": { "baseUrl": "https://api.anthropic.com", "apiKey": "sk-ant-secret",
+                               "models": [{ "id": "claude", "maxTokens": 8192 }] },
+                "openai": { "baseUrl": "https://api.openai.com/v1", "apiKey": "sk-other" }
+            }},
+            "agents": { "defaults": { "model": { "primary": "anthropic/claude" }, "heartbeat": { "every": "5m" } } },
+            "channels": { "telegram": { "botToken": "123:abc" } }
+        });
+        let sections = vec!["models.providers.anthropic".to_string(), "agents.defaults.model".to_string()];
+        let minimal = extract_config_sections(&config, &sections).unwrap();
+
+        assert_eq!(minimal["models"]["providers"]["anthropic"]["apiKey"], REDACTED_PLACEHOLDER);
+        assert_eq!(minimal["models"]["providers"]["anthropic"]["models"][0]["maxTokens"], 8192);
+        assert!(minimal["models"]["providers"].get("openai").is_none());
+        assert_eq!(minimal["agents"]["defaults"]["model"]["primary"], "anthropic/claude");
+        assert!(minimal["agents"]["defaults"].get("heartbeat").is_none());
+        assert!(minimal.get("channels").is_none());
+
+        let text = serde_json::to_string_pretty(&minimal).unwrap();
+        assert!(!text.contains("sk-ant-secret"));
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&text).unwrap(), minimal);
+
+        assert!(extract_config_sections(&config, &["gateway.auth".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_expr_valid() {
+        use std::time::Duration;
+        assert_eq!(parse_duration_expr("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration_expr("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration_expr(" 1h ").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration_expr("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration_expr("1d").unwrap(), Duration::from_secs(86400));
+        assert_eq!(parse_duration_expr("500ms").unwrap(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_parse_duration_expr_invalid() {
+        for bad in ["", "10min", "5 minuets", "m5", "5", "1.5h", "0m", "-5m", "5x"] {
+            assert!(parse_duration_expr(bad).is_err(), "expected '{}' to be rejected", bad);
+        }
+    }
+
+    fn config_with_models() -> serde_json::Value {
+        json!({ "models": { "providers": {
+            "anthropic": { "models": [{ "id": "claude-opus-4-5" }] },
+            "openai": { "models": [{ "id": "gpt-4o-mini" }] }
+        }}})
+    }
+
+    #[test]
+    fn test_agent_model_resolves() {
+        assert!(resolve_model_reference(&config_with_models(), "openai/gpt-4o-mini", false).is_ok());
+    }
+
+    #[test]
+    fn test_agent_model_typo_rejected() {
+        let err = resolve_model_reference(&config_with_models(), "openai/gpt-4o-mni", false).unwrap_err();
+        assert!(err.contains("anthropic/claude-opus-4-5"));
+        assert!(err.contains("openai/gpt-4o-mini"));
+    }
+
+    #[test]
+    fn test_agent_model_forced() {
+        assert!(resolve_model_reference(&config_with_models(), "local/unlisted", true).is_ok());
+    }
+
+    #[test]
+    fn test_primary_model_must_exist() {
+        let mut config = config_with_models();
+        config["agents"] = json!({ "defaults": { "models": { "gateway/auto": {} } } });
+
+        assert!(validate_primary_model(&config, "openai/gpt-4o-mini", false).is_ok());
+        assert!(validate_primary_model(&config, "gateway/auto", false).is_ok());
+        let err = validate_primary_model(&config, "openai/gpt-4o-mni", false).unwrap_err();
+        assert!(err.contains("openai/gpt-4o-mni"));
+        assert!(validate_primary_model(&config, "openai/gpt-4o-mni", true).is_ok());
+    }
+
+    #[test]
+    fn test_compaction_preserves_unknown_keys() {
+        let mut defaults = json!({
+            "compaction": { "threshold": 80, "reserveTokens": 4000 },
+            "contextPruning": { "maxMessages": 50, "mode": "adaptive" }
+        });
+        apply_compaction_settings(&mut defaults, true, Some(90), true, Some(100));
+
+        assert_eq!(defaults["compaction"], json!({ "threshold": 90, "reserveTokens": 4000 }));
+        assert_eq!(defaults["contextPruning"], json!({ "maxMessages": 100, "mode": "adaptive" }));
+
+        // Disabling clears only the managed fields and keeps the rest switched off
+        apply_compaction_settings(&mut defaults, false, None, false, None);
+        assert_eq!(defaults["compaction"], json!({ "reserveTokens": 4000, "enabled": false }));
+        assert_eq!(defaults["contextPruning"], json!({ "mode": "adaptive", "enabled": false }));
+
+        // Re-enabling picks the kept settings back up
+        apply_compaction_settings(&mut defaults, true, Some(70), true, None);
+        assert_eq!(defaults["compaction"], json!({ "reserveTokens": 4000, "threshold": 70 }));
+        assert_eq!(defaults["contextPruning"], json!({ "mode": "adaptive" }));
+
+        // Sections holding nothing but managed fields are removed
+        let mut defaults = json!({ "compaction": { "threshold": 80 }, "contextPruning": true });
+        apply_compaction_settings(&mut defaults, false, None, false, None);
+        assert!(defaults.get("compaction").is_none());
+        assert!(defaults.get("contextPruning").is_none());
+    }
+
+    #[test]
+    fn test_compaction_settings_pass_schema() {
+        let mut config = json!({ "agents": { "defaults": {} } });
+        apply_compaction_settings(&mut config["agents"]["defaults"], true, None, true, None);
+        assert_eq!(config["agents"]["defaults"]["contextPruning"], json!(true));
+        assert!(validate_config_value(&config).is_empty());
+
+        apply_compaction_settings(&mut config["agents"]["defaults"], true, Some(80), true, Some(40));
+        assert!(validate_config_value(&config).is_empty());
+    }
+
+    #[test]
+    fn test_provider_usage_from_fixture() {
+        let main_session = concat!(
+            r#"{"type":"session","id":"s1","timestamp":"2026-01-01T09:59:00Z"}"#, "\n",
+            r#"{"type":"message","timestamp":"2026-01-01T10:00:00Z","message":{"role":"user","content":"hi"}}"#, "\n",
+            r#"{"type":"message","timestamp":"2026-01-01T10:00:05Z","message":{"role":"assistant","provider":"anthropic","model":"claude","usage":{"input":1000,"output":200,"cost":{"total":0.5}}}}"#, "\n",
+            "not json\n",
+            r#"{"type":"message","timestamp":"2025-06-01T10:00:00Z","message":{"role":"assistant","provider":"openai","model":"gpt-4o","usage":{"input":10,"output":10}}}"#, "\n",
+        );
+        let coder_session = concat!(
+            r#"{"type":"message","timestamp":"2026-01-02T10:00:00Z","message":{"role":"assistant","provider":"anthropic","model":"claude","usage":{"input":500,"output":100}}}"#, "\n",
+        );
+        let agents = std::env::temp_dir().join(format!("openclaw-usage-test-{}", std::process::id()));
+        for (agent, content) in [("main", main_session), ("coder", coder_session)] {
+            let sessions = agents.join(agent).join("sessions");
+            std::fs::create_dir_all(&sessions).unwrap();
+            std::fs::write(sessions.join("session.jsonl"), content).unwrap();
+        }
+
+        let config = json!({ "models": { "providers": { "anthropic": {
+            "models": [{ "id": "claude", "cost": { "input": 3.0, "output": 15.0 } }]
+        }}}});
+        let since = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        let report = load_provider_usage(agents.to_str().unwrap(), Some(since), &config).unwrap();
+        std::fs::remove_dir_all(&agents).ok();
+
+        assert!(report.data_available);
+        assert_eq!(report.usage.len(), 1);
+        let claude = &report.usage[0];
+        assert_eq!((claude.provider.as_str(), claude.model.as_str()), ("anthropic", "claude"));
+        assert_eq!((claude.requests, claude.input_tokens, claude.output_tokens), (2, 1500, 300));
+        // 0.5 recorded + (500 * 3 + 100 * 15) / 1M estimated
+        assert!((claude.estimated_cost - 0.503).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_provider_usage_without_sessions() {
+        let path = std::env::temp_dir().join("openclaw-agents-does-not-exist");
+        let report = load_provider_usage(path.to_str().unwrap(), None, &json!({})).unwrap();
+        assert!(!report.data_available);
+        assert!(report.usage.is_empty());
+    }
+
+    #[test]
+    fn test_ensure_workspace_dir() {
+        let base = std::env::temp_dir().join(format!("openclaw-ws-test-{}", std::process::id()));
+        let ws = base.join("nested").join("workspace");
+        let ws_str = ws.to_str().unwrap();
+
+        // Missing directory is rejected unless create is requested
+        assert!(ensure_workspace_dir(ws_str, false).is_err());
+        assert!(ensure_workspace_dir(ws_str, true).is_ok());
+        assert!(ws.is_dir());
+        assert!(ensure_workspace_dir(ws_str, false).is_ok());
+        // The write probe doesn't leave files behind
+        assert_eq!(std::fs::read_dir(&ws).unwrap().count(), 0);
+
+        // A 0555 directory is rejected unless this user can still write there (root)
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&ws, std::fs::Permissions::from_mode(0o555)).unwrap();
+            let writable = std::fs::write(ws.join("probe"), "x").is_ok();
+            let _ = std::fs::remove_file(ws.join("probe"));
+            let result = ensure_workspace_dir(ws_str, false);
+            std::fs::set_permissions(&ws, std::fs::Permissions::from_mode(0o755)).unwrap();
+            if writable {
+                assert!(result.is_ok());
+            } else {
+                assert!(result.unwrap_err().contains("not writable"));
+            }
+            assert_eq!(std::fs::read_dir(&ws).unwrap().count(), 0);
+        }
+
+        // A regular file is never accepted
+        let file_path = base.join("not-a-dir");
+        std::fs::write(&file_path, "x").unwrap();
+        let err = ensure_workspace_dir(file_path.to_str().unwrap(), true).unwrap_err();
+        assert!(err.contains("not a directory"));
+
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    fn config_with_key(api_key: &str) -> serde_json::Value {
+        json!({
+            "agents": { "defaults": { "model": { "primary": "anthropic/claude" } } },
+            "models": { "providers": { "anthropic": { "apiKey": api_key } } }
+        })
+    }
+
+    #[test]
+    fn test_primary_key_env_ref_populated() {
+        let status = check_primary_model_key(&config_with_key("${ANTHROPIC_API_KEY}"), |var| {
+            (var == "ANTHROPIC_API_KEY").then(|| "sk-ant-123".to_string())
+        });
+        assert_eq!(status.source, "env");
+        assert_eq!(status.env_var.as_deref(), Some("ANTHROPIC_API_KEY"));
+        assert!(status.populated);
+    }
+
+    #[test]
+    fn test_primary_key_env_ref_unresolved() {
+        let status = check_primary_model_key(&config_with_key("${ANTHROPIC_API_KEY}"), |_| None);
+        assert_eq!(status.source, "env");
+        assert!(!status.populated);
+        assert!(status.message.contains("not set"));
+    }
+
+    #[test]
+    fn test_primary_key_inline() {
+        let status = check_primary_model_key(&config_with_key("sk-ant-inline"), |_| None);
+        assert_eq!(status.source, "inline");
+        assert!(status.populated);
+    }
+
+    #[test]
+    fn test_personality_filename_validation() {
+        for ok in ["SOUL.md", "STYLE.md", "notes.MD"] {
+            assert!(validate_personality_filename(ok).is_ok(), "{} should be allowed", ok);
+        }
+        for bad in ["", "../SOUL.md", "sub/STYLE.md", "sub\\STYLE.md", "..md", ".hidden.md", "config.json", "SOUL"] {
+            assert!(validate_personality_filename(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_official_api_types_are_supported() {
+        let supported: Vec<String> = supported_api_types().into_iter().map(|t| t.id).collect();
+        for provider in get_official_providers().await.unwrap() {
+            assert!(supported.contains(&provider.api_type),
+                "{} uses unsupported api_type {}", provider.id, provider.api_type);
+        }
+    }
+
+    #[test]
+    fn test_workspace_file_traversal_rejected() {
+        let base = std::env::temp_dir().join(format!("openclaw-traversal-test-{}", std::process::id()));
+        let ws = base.join("workspace");
+        std::fs::create_dir_all(&ws).unwrap();
+
+        assert!(resolve_workspace_file(&ws, "../../.ssh/authorized_keys").is_err());
+        assert!(resolve_workspace_file(&ws, "..\\outside.md").is_err());
+        assert!(resolve_workspace_file(&ws, "SOUL.md").unwrap().starts_with(ws.canonicalize().unwrap()));
+
+        // A symlink pointing outside the workspace is rejected after canonicalization
+        #[cfg(unix)]
+        {
+            std::fs::write(base.join("secret.md"), "x").unwrap();
+            std::os::unix::fs::symlink(base.join("secret.md"), ws.join("LINK.md")).unwrap();
+            let err = resolve_workspace_file(&ws, "LINK.md").unwrap_err();
+            assert!(err.contains("escapes"));
+        }
+
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn test_provider_name_reserved_rejected() {
+        for bad in ["providers", "apiKey", "APIKEY", "", "  ", "my provider", "1provider", "a/b"] {
+            assert!(validate_provider_name(bad).is_err(), "'{}' should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_provider_name_accepted() {
+        for ok in ["anthropic", "openai-proxy", "my_provider2", "DeepSeek"] {
+            assert!(validate_provider_name(ok).is_ok(), "'{}' should be accepted", ok);
+        }
+        assert_eq!(escape_pointer_token("a/b~c"), "a~1b~0c");
+    }
+
+    #[test]
+    fn test_normalize_hex_color() {
+        assert_eq!(normalize_hex_color("#FF8800").unwrap(), "#ff8800");
+        assert_eq!(normalize_hex_color(" #f80 ").unwrap(), "#ff8800");
+        for bad in ["blurple", "FF8800", "#ff88", "#gg0000", "#"] {
+            assert!(normalize_hex_color(bad).is_err(), "'{}' should be rejected", bad);
+        }
+    }
+
+    fn reconcile_fixture() -> (serde_json::Value, Vec<crate::models::OfficialProvider>) {
+        let config = json!({
+            "models": { "providers": {
+                "anthropic": { "models": [{ "id": "claude-opus-4-5-20251101" }, { "id": "claude-custom" }] },
+                "local": { "models": [{ "id": "llama3" }] }
+            }},
+            "agents": { "defaults": { "models": { "openai/gpt-4o": {} } } }
+        });
+        (config, vec![sample_official()])
+    }
+
+    #[test]
+    fn test_reconcile_add_recommended() {
+        let (mut config, official) = reconcile_fixture();
+        let report = reconcile_available_models_in(&mut config, "add_recommended", &official).unwrap();
+        assert_eq!(report.added, vec!["anthropic/claude-opus-4-5-20251101"]);
+        assert!(report.removed.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_add_all() {
+        let (mut config, official) = reconcile_fixture();
+        let mut report = reconcile_available_models_in(&mut config, "add_all", &official).unwrap();
+        report.added.sort();
+        assert_eq!(report.added, vec!["anthropic/claude-custom", "anthropic/claude-opus-4-5-20251101", "local/llama3"]);
+        assert_eq!(config["agents"]["defaults"]["models"].as_object().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_reconcile_prune_orphans() {
+        let (mut config, official) = reconcile_fixture();
+        let report = reconcile_available_models_in(&mut config, "prune_orphans", &official).unwrap();
+        assert_eq!(report.removed, vec!["openai/gpt-4o"]);
+        assert!(config["agents"]["defaults"]["models"].as_object().unwrap().is_empty());
+
+        assert!(reconcile_available_models_in(&mut config, "bogus", &official).is_err());
+    }
+
+    #[test]
+    fn test_web_config_legacy_brave_migrated() {
+        let mut config = json!({ "web": { "braveApiKey": "BSA-123" } });
+        assert!(migrate_legacy_web_config(&mut config));
+        assert!(config["web"].get("braveApiKey").is_none());
+        assert_eq!(config["web"]["activeProvider"], "brave");
+
+        let providers = web_providers_from_config(&config);
+        assert_eq!(providers, vec![WebSearchProvider { id: "brave".to_string(), api_key: Some("BSA-123".to_string()), cx: None }]);
+
+        // Second read is a no-op
+        assert!(!migrate_legacy_web_config(&mut config));
+    }
+
+    #[test]
+    fn test_meta_as_string_repaired() {
+        let mut config = json!({ "meta": "broken" });
+        assert!(repair_meta_structure(&mut config));
+        assert_eq!(config["meta"], json!({ "invalidMeta": "broken", "gui": {} }));
+
+        let mut config = json!({ "meta": { "gui": 5 } });
+        assert!(repair_meta_structure(&mut config));
+        assert_eq!(config["meta"], json!({ "invalidGui": 5, "gui": {} }));
+    }
+
+    #[test]
+    fn test_healthy_meta_untouched() {
+        let mut config = json!({ "meta": { "lastTouchedAt": "now", "gui": { "browser": { "enabled": true } } } });
+        let before = config.clone();
+        assert!(!repair_meta_structure(&mut config));
+        assert_eq!(config, before);
+    }
+
+    fn patch(pointer: &str, op: PatchOp, value: Option<serde_json::Value>) -> ConfigPatch {
+        ConfigPatch { pointer: pointer.to_string(), op, value }
+    }
+
+    #[test]
+    fn test_config_patch_batch_applied() {
+        let config = json!({ "agents": { "defaults": { "models": { "a/old": {} } } } });
+        let patches = vec![
+            patch("/agents/defaults/model/primary", PatchOp::Set, Some(json!("a/new"))),
+            patch("/agents/defaults/models/a~1new", PatchOp::Set, Some(json!({}))),
+            patch("/agents/defaults/models/a~1old", PatchOp::Delete, None),
+        ];
+        let updated = apply_config_patches(&config, &patches).unwrap();
+        assert_eq!(updated, json!({ "agents": { "defaults": {
+            "model": { "primary": "a/new" },
+            "models": { "a/new": {} }
+        }}}));
+    }
+
+    #[test]
+    fn test_config_patch_invalid_rejects_all() {
+        let config = json!({ "gateway": { "port": 18789 } });
+        let patches = vec![
+            patch("/gateway/mode", PatchOp::Set, Some(json!("local"))),
+            patch("/gateway/port/nested", PatchOp::Set, Some(json!(1))),
+        ];
+        let err = apply_config_patches(&config, &patches).unwrap_err();
+        assert!(err.contains("Patch 2"));
+        assert!(apply_config_patches(&config, &[patch("gateway", PatchOp::Set, Some(json!(1)))]).is_err());
+        assert!(apply_config_patches(&config, &[patch("/missing/key", PatchOp::Delete, None)]).is_err());
+    }
+
+    #[test]
+    fn test_dependencies_all_present() {
+        let deps = detect_test_dependencies(|_| true);
+        assert!(deps.node && deps.npx);
+        assert!(deps.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_dependencies_npx_missing() {
+        let deps = detect_test_dependencies(|cmd| cmd != "npx");
+        assert!(!deps.npx);
+        assert!(deps.node);
+        assert_eq!(deps.warnings.len(), 1);
+        assert!(deps.warnings[0].contains("npx"));
+    }
+
+    #[test]
+    fn test_model_catalog_populated_on_success() {
+        let mut config = json!({});
+        let models = parse_model_list(r#"{"data":[{"id":"gpt-4o"},{"id":"gpt-4o-mini"}]}"#).unwrap();
+        let catalog = apply_catalog_fetch(&mut config, "openai", Ok(models), "2026-01-01T00:00:00Z").unwrap();
+        assert!(!catalog.stale);
+        assert_eq!(catalog.models, vec!["gpt-4o", "gpt-4o-mini"]);
+        assert_eq!(config["meta"]["gui"]["modelCatalogs"]["openai"]["fetchedAt"], "2026-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_model_catalog_stale_on_failure() {
+        let mut config = json!({ "meta": { "gui": { "modelCatalogs": {
+            "openai": { "models": ["gpt-4o"], "fetchedAt": "2026-01-01T00:00:00Z" }
+        }}}});
+        let catalog = apply_catalog_fetch(&mut config, "openai", Err("offline".to_string()), "later").unwrap();
+        assert!(catalog.stale);
+        assert_eq!(catalog.models, vec!["gpt-4o"]);
+        assert_eq!(catalog.fetched_at.as_deref(), Some("2026-01-01T00:00:00Z"));
+        assert_eq!(catalog.error.as_deref(), Some("offline"));
+
+        // Without a cache the error is surfaced
+        assert!(apply_catalog_fetch(&mut config, "anthropic", Err("offline".to_string()), "later").is_err());
+    }
+
+    #[test]
+    fn test_model_catalog_cache_cleared() {
+        let mut config = json!({ "meta": { "gui": { "modelCatalogs": {
+            "openai": { "models": [] }, "anthropic": { "models": [] }
+        }}}});
+        assert!(clear_model_catalogs(&mut config, Some("openai")));
+        assert!(config["meta"]["gui"]["modelCatalogs"].get("openai").is_none());
+        assert!(clear_model_catalogs(&mut config, None));
+        assert!(config["meta"]["gui"].get("modelCatalogs").is_none());
+        assert!(!clear_model_catalogs(&mut config, None));
+    }
+
+    #[test]
+    fn test_save_config_log_masks_secrets() {
+        let config = json!({
+            "models": { "providers": { "custom": { "apiKey": "plainsecretvalue1234", "baseUrl": "https://x" } } },
+            "channels": { "slack": { "botToken": "tok-abcdefghijkl", "signingSecret": "s3cr3t-signing" } },
+            "agents": { "defaults": { "maxConcurrent": 4 } }
+        });
+        let formatted = serde_json::to_string_pretty(&mask_secrets_for_log(&config)).unwrap();
+        for secret in ["plainsecretvalue1234", "tok-abcdefghijkl", "s3cr3t-signing"] {
+            assert!(!formatted.contains(secret), "{} leaked into log output", secret);
+        }
+        assert!(formatted.contains("plai...1234"));
+        assert!(formatted.contains("https://x"));
+    }
+
+    #[test]
+    fn test_redact_secrets_includes_gateway_token() {
+        // Unlike keyring externalization, redaction also covers the inline-only gateway token
+        let mut config = json!({
+            "gateway": { "auth": { "token": "gw-token-value" } },
+            "mcp": [{ "env": { "GITHUB_TOKEN": "ghp_x" } }]
+        });
+        redact_secrets(&mut config);
+        assert_eq!(config["gateway"]["auth"]["token"], REDACTED_PLACEHOLDER);
+        assert_eq!(config["mcp"][0]["env"]["GITHUB_TOKEN"], REDACTED_PLACEHOLDER);
+    }
+
+    #[test]
+    fn test_channel_stored_as_string_reported_and_removed() {
+        let mut config = json!({ "channels": {
+            "telegram": { "enabled": true, "botToken": "x" },
+            "discord": "oops",
+            "slack": false
+        }});
+        let problems = find_malformed_channels(&config);
+        assert_eq!(problems.len(), 2);
+        assert!(problems.iter().any(|p| p.contains("'discord' is a string")));
+
+        let changes = repair_channels_in(&mut config);
+        assert_eq!(changes.len(), 2);
+        assert!(config["channels"].get("discord").is_none());
+        assert_eq!(config["channels"]["slack"], json!({ "enabled": false }));
+        assert_eq!(config["channels"]["telegram"], json!({ "enabled": true, "botToken": "x" }));
+    }
+
+    #[test]
+    fn test_well_formed_channels_untouched() {
+        let mut config = json!({ "channels": { "telegram": { "enabled": true } } });
+        let before = config.clone();
+        assert!(find_malformed_channels(&config).is_empty());
+        assert!(repair_channels_in(&mut config).is_empty());
+        assert_eq!(config, before);
+    }
+
+    fn sample_bundle() -> ConfigBundle {
+        ConfigBundle {
+            config: json!({
+                "models": { "providers": { "anthropic": { "baseUrl": "https://api.anthropic.com", "apiKey": "sk-live" } } },
+                "agents": { "defaults": { "workspace": "~/work" } }
+            }),
+            mcps: Some(json!({ "github": { "command": "npx", "env": { "GITHUB_TOKEN": "ghp_x" } } })),
+            personality: vec![("SOUL.md".to_string(), "# Soul".to_string())],
+            env: None,
+        }
+    }
+
+    #[test]
+    fn test_config_bundle_round_trip_strips_secrets() {
+        let data = write_config_bundle(&sample_bundle(), true).unwrap();
+        let bundle = read_config_bundle(&data).unwrap();
+
+        assert_eq!(bundle.config["models"]["providers"]["anthropic"]["apiKey"], json!(REDACTED_PLACEHOLDER));
+        assert_eq!(bundle.config["agents"]["defaults"]["workspace"], json!("~/work"));
+        assert_eq!(bundle.mcps.unwrap()["github"]["env"]["GITHUB_TOKEN"], json!(REDACTED_PLACEHOLDER));
+        assert_eq!(bundle.personality, vec![("SOUL.md".to_string(), "# Soul".to_string())]);
+        assert!(bundle.env.is_none());
+
+        let kept = read_config_bundle(&write_config_bundle(&sample_bundle(), false).unwrap()).unwrap();
+        assert_eq!(kept.config["models"]["providers"]["anthropic"]["apiKey"], json!("sk-live"));
+    }
+
+    #[test]
+    fn test_read_config_bundle_rejects_invalid_archives() {
+        assert!(read_config_bundle(b"not a zip").is_err());
+
+        use std::io::Write;
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer.start_file("personality/../evil.md", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"x").unwrap();
+        let data = writer.finish().unwrap().into_inner();
+        assert!(read_config_bundle(&data).is_err());
+    }
+
+    #[test]
+    fn test_merge_imported_config_keeps_local_secret_for_placeholder() {
+        let mut local = json!({ "models": { "providers": {
+            "anthropic": { "baseUrl": "https://old", "apiKey": "sk-local" }
+        }}});
+        let imported = json!({ "models": { "providers": {
+            "anthropic": { "baseUrl": "https://new", "apiKey": REDACTED_PLACEHOLDER },
+            "openai": { "baseUrl": "https://api.openai.com/v1", "apiKey": REDACTED_PLACEHOLDER }
+        }}});
+
+        let missing = merge_imported_config(&mut local, &imported);
+        assert_eq!(local["models"]["providers"]["anthropic"]["baseUrl"], json!("https://new"));
+        assert_eq!(local["models"]["providers"]["anthropic"]["apiKey"], json!("sk-local"));
+        assert!(local["models"]["providers"]["openai"].get("apiKey").is_none());
+        assert_eq!(missing, vec!["/models/providers/openai/apiKey".to_string()]);
+    }
+
+    #[test]
+    fn test_import_keeps_local_workspace() {
+        let mut local = json!({ "agents": { "defaults": { "workspace": "~/ws" } } });
+        let original = local.pointer("/agents/defaults/workspace").cloned();
+        merge_imported_config(&mut local, &json!({ "agents": { "defaults": { "workspace": "/home/other/ws" } } }));
+        assert_eq!(keep_local_workspace(&mut local, original).as_deref(), Some("/home/other/ws"));
+        assert_eq!(local["agents"]["defaults"]["workspace"], "~/ws");
+
+        // No local workspace: the imported one is dropped rather than adopted
+        let mut local = json!({ "agents": { "defaults": {} } });
+        merge_imported_config(&mut local, &json!({ "agents": { "defaults": { "workspace": "/srv/ws" } } }));
+        assert!(keep_local_workspace(&mut local, None).is_some());
+        assert!(local.pointer("/agents/defaults/workspace").is_none());
+
+        // The same workspace on both sides is left alone
+        let mut local = json!({ "agents": { "defaults": { "workspace": "~/ws" } } });
+        assert_eq!(keep_local_workspace(&mut local, Some(json!("~/ws"))), None);
+
+        // Export and import resolve "~" the same way
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(personality_dir(&local), home.join("ws"));
+        }
+    }
+
+    #[test]
+    fn test_parse_env_exports() {
+        let vars = crate::utils::file::parse_env_exports("# comment\nexport OPENAI_API_KEY=\"sk-1\"\nexport EMPTY=''\nFOO=bar\n");
+        assert_eq!(vars, vec![
+            ("OPENAI_API_KEY".to_string(), "sk-1".to_string()),
+            ("EMPTY".to_string(), String::new()),
+        ]);
+    }
+
+    fn mock_interfaces() -> Vec<(String, std::net::IpAddr)> {
+        vec![
+            ("lo".to_string(), "127.0.0.1".parse().unwrap()),
+            ("docker0".to_string(), "172.17.0.1".parse().unwrap()),
+            ("en0".to_string(), "fe80::1".parse().unwrap()),
+            ("en0".to_string(), "192.168.1.42".parse().unwrap()),
+        ]
+    }
+
+    #[test]
+    fn test_lan_dashboard_url_uses_lan_ip() {
+        let config = json!({ "gateway": { "bind": "lan", "port": 18790 } });
+        let url = build_lan_dashboard_url(&config, &mock_interfaces(), "tok").unwrap();
+        assert_eq!(url, "http://192.168.1.42:18790?token=tok");
+
+        let pinned = json!({ "gateway": { "bind": "10.0.0.5" } });
+        assert_eq!(build_lan_dashboard_url(&pinned, &mock_interfaces(), "tok").unwrap(), "http://10.0.0.5:18789?token=tok");
+    }
+
+    #[test]
+    fn test_lan_dashboard_url_refuses_loopback_bind() {
+        assert!(build_lan_dashboard_url(&json!({}), &mock_interfaces(), "tok").is_err());
+        let config = json!({ "gateway": { "bind": "loopback" } });
+        assert!(build_lan_dashboard_url(&config, &mock_interfaces(), "tok").is_err());
+
+        let lan = json!({ "gateway": { "bind": "lan" } });
+        let only_loopback = vec![("lo".to_string(), "127.0.0.1".parse().unwrap())];
+        assert!(build_lan_dashboard_url(&lan, &only_loopback, "tok").is_err());
+    }
+
+    #[test]
+    fn test_validate_config_reports_providers_array() {
+        let config = json!({ "models": { "providers": [ { "baseUrl": "https://x" } ] } });
+        let errors = validate_config_value(&config);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/models/providers");
+        assert!(errors[0].message.contains("object"));
+    }
+
+    #[test]
+    fn test_validate_config_accepts_well_formed_config() {
+        let config = json!({
+            "models": { "providers": { "anthropic": {
+                "baseUrl": "https://api.anthropic.com",
+                "models": [ { "id": "claude-sonnet-4", "name": "Sonnet", "contextWindow": 200000 } ]
+            }}},
+            "agents": { "defaults": { "model": { "primary": "anthropic/claude-sonnet-4" } }, "list": [ { "id": "main" } ] },
+            "channels": { "telegram": { "enabled": true } },
+            "gateway": { "port": 18789, "customField": 1 }
+        });
+        assert!(validate_config_value(&config).is_empty());
+
+        let bad = json!({ "gateway": { "port": 70000 }, "channels": { "discord": "oops" } });
+        let paths: Vec<String> = validate_config_value(&bad).into_iter().map(|e| e.path).collect();
+        assert!(paths.contains(&"/gateway/port".to_string()));
+        assert!(paths.contains(&"/channels/discord".to_string()));
+    }
+
+    fn model_ids(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("model-{}", i)).collect()
+    }
+
+    #[test]
+    fn test_model_cap_exceeded_warns() {
+        let config = json!({
+            "meta": { "gui": { "maxModelsPerProvider": 3 } },
+            "models": { "providers": { "openrouter": { "models": [ { "id": "model-0" } ] } } }
+        });
+        assert!(check_model_cap(&config, "openrouter", &model_ids(3), false).is_ok());
+
+        let err = check_model_cap(&config, "openrouter", &model_ids(5), false).unwrap_err();
+        assert!(err.contains("5 models (4 new)"));
+        assert!(err.contains("limit of 3"));
+
+        let counts = provider_model_counts(&config);
+        assert_eq!(counts[0].count, 1);
+        assert_eq!(counts[0].limit, 3);
+        assert!(!counts[0].over_limit);
+    }
+
+    #[test]
+    fn test_model_cap_force_through() {
+        let config = json!({});
+        assert_eq!(max_models_per_provider(&config), 100);
+        assert!(check_model_cap(&config, "openrouter", &model_ids(150), false).is_err());
+        assert!(check_model_cap(&config, "openrouter", &model_ids(150), true).is_ok());
+    }
+
+    #[test]
+    fn test_parse_initialize_response() {
+        let line = r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","serverInfo":{"name":"github","version":"1.2.0"},"capabilities":{"tools":{}}}}"#;
+        let result = parse_initialize_response(line).unwrap();
+        assert_eq!(result["serverInfo"]["name"], json!("github"));
+
+        assert!(parse_initialize_response("Starting server on stdio...").is_none());
+        assert!(parse_initialize_response(r#"{"jsonrpc":"2.0","method":"notifications/message","params":{}}"#).is_none());
+        assert!(parse_initialize_response(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32600,"message":"bad"}}"#).is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_probe_distinguishes_handshake_from_running() {
+        let reply = r#"{"jsonrpc":"2.0","id":1,"result":{"serverInfo":{"name":"echo"},"capabilities":{}}}"#;
+        let script = format!("read line; echo '{}'; sleep 5", reply);
+        let probe = test_mcp_server(
+            "stdio".to_string(), "sh".to_string(), Some("sh".to_string()),
+            Some(vec!["-c".to_string(), script]), Some(3000), None, None,
+        ).await.unwrap();
+        assert!(probe.handshake);
+        assert!(probe.message.contains("responded to initialize: echo"));
+
+        let silent = test_mcp_server(
+            "stdio".to_string(), "sh".to_string(), Some("sh".to_string()),
+            Some(vec!["-c".to_string(), "sleep 5".to_string()]), Some(300), None, None,
+        ).await.unwrap();
+        assert!(!silent.handshake);
+        assert!(silent.message.contains("did not respond to initialize within 300ms"));
+
+        let crashed = test_mcp_server(
+            "stdio".to_string(), "sh".to_string(), Some("sh".to_string()),
+            Some(vec!["-c".to_string(), "exit 3".to_string()]), Some(3000), None, Some(1),
+        ).await.unwrap_err();
+        assert!(crashed.contains("without responding"));
+    }
+
+    #[test]
+    fn test_mcp_test_result_from_sse_initialize() {
+        let body = "event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"protocolVersion\":\"2025-03-26\",\"serverInfo\":{\"name\":\"excalidraw\",\"version\":\"0.3.1\"},\"capabilities\":{\"tools\":{\"listChanged\":true},\"prompts\":{}}}}";
+        let init = parse_http_initialize_body(body).unwrap();
+        let result = mcp_test_result_from_initialize(&init);
+        assert!(result.handshake);
+        assert_eq!(result.protocol_version.as_deref(), Some("2025-03-26"));
+        assert_eq!(result.server_name.as_deref(), Some("excalidraw"));
+        assert_eq!(result.server_version.as_deref(), Some("0.3.1"));
+        assert_eq!(result.capabilities, vec!["prompts".to_string(), "tools".to_string()]);
+
+        assert!(parse_http_initialize_body("<html>not mcp</html>").is_none());
+    }
+
+    #[test]
+    fn test_parse_tools_page_and_capability() {
+        let result = json!({
+            "tools": [
+                { "name": "create_issue", "description": " Create a GitHub issue ", "inputSchema": {} },
+                { "name": "search_repos" },
+                { "description": "missing name" }
+            ],
+            "nextCursor": "page-2"
+        });
+        let (tools, cursor) = parse_tools_page(&result);
+        assert_eq!(tools, vec![
+            crate::models::McpTool { name: "create_issue".to_string(), description: Some("Create a GitHub issue".to_string()) },
+            crate::models::McpTool { name: "search_repos".to_string(), description: None },
+        ]);
+        assert_eq!(cursor.as_deref(), Some("page-2"));
+
+        assert!(supports_tools(&json!({ "capabilities": { "tools": {} } })));
+        assert!(!supports_tools(&json!({ "capabilities": { "resources": {} } })));
+        assert_eq!(parse_jsonrpc_reply(r#"{"jsonrpc":"2.0","id":2,"error":{"code":-32601}}"#, 2), Some(Err(json!({ "code": -32601 }))));
+    }
+
+    fn env_lookup(var: &str) -> Option<String> {
+        match var {
+            "OPENCLAW_GITHUB_PAT" => Some("ghp_123".to_string()),
+            "HOME_DIR" => Some("/home/me".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand_env_refs() {
+        assert_eq!(expand_env_refs("${OPENCLAW_GITHUB_PAT}", &env_lookup).unwrap(), "ghp_123");
+        assert_eq!(expand_env_refs("--root=${HOME_DIR}/mcp", &env_lookup).unwrap(), "--root=/home/me/mcp");
+        // Literal values and non-references are left untouched
+        assert_eq!(expand_env_refs("plain-value", &env_lookup).unwrap(), "plain-value");
+        assert_eq!(expand_env_refs("cost $5 ${not a var} ${", &env_lookup).unwrap(), "cost $5 ${not a var} ${");
+
+        let err = expand_env_refs("${MISSING_TOKEN}", &env_lookup).unwrap_err();
+        assert!(err.contains("MISSING_TOKEN"));
+    }
+
+    #[test]
+    fn test_expand_mcp_config_reports_undefined_variable() {
+        let stdio = |env: &[(&str, &str)]| crate::models::MCPConfig {
+            transport: McpTransport::Stdio {
+                command: "npx".to_string(),
+                args: vec!["-y".to_string(), "server-github".to_string()],
+                env: env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            },
+            enabled: true,
+            source: Default::default(),
+        };
+        let config = stdio(&[("GITHUB_TOKEN", "${OPENCLAW_GITHUB_PAT}")]);
+        let expanded = expand_mcp_config("github", &config, &env_lookup).unwrap();
+        let McpTransport::Stdio { args, env, .. } = expanded.transport else { panic!("expected stdio") };
+        assert_eq!(env["GITHUB_TOKEN"], "ghp_123");
+        assert_eq!(args, vec!["-y", "server-github"]);
+
+        let broken = stdio(&[("GITHUB_TOKEN", "${OPENCLAW_GITHUB_PAT}"), ("OTHER", "${UNDEFINED_VAR}")]);
+        let err = expand_mcp_config("github", &broken, &env_lookup).unwrap_err();
+        assert!(err.contains("'github'") && err.contains("UNDEFINED_VAR") && err.contains("env OTHER"));
+
+        // Syncing skips only the broken server and keeps its previous mcporter entry
+        let configs = HashMap::from([
+            ("github".to_string(), broken),
+            ("fs".to_string(), stdio(&[])),
+        ]);
+        let mut mcporter = json!({ "mcpServers": { "github": { "command": "old" } } });
+        let skipped = merge_mcporter_servers(&mut mcporter, &configs, &env_lookup).unwrap();
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].contains("UNDEFINED_VAR"));
+        assert_eq!(mcporter["mcpServers"]["github"], json!({ "command": "old" }));
+        assert_eq!(mcporter["mcpServers"]["fs"]["command"], "npx");
+    }
+
+    #[test]
+    fn test_same_git_remote() {
+        let url = "https://github.com/excalidraw/excalidraw-mcp";
+        assert!(same_git_remote(url, "https://github.com/excalidraw/excalidraw-mcp.git"));
+        assert!(same_git_remote(url, "git@github.com:excalidraw/excalidraw-mcp.git"));
+        assert!(same_git_remote(url, "https://GitHub.com/excalidraw/excalidraw-mcp/"));
+        assert!(same_git_remote("ssh://git@example.com:2222/team/repo.git", "ssh://git@example.com:2222/team/repo"));
+        assert!(!same_git_remote(url, "https://github.com/someone-else/excalidraw-mcp"));
+    }
+
+    #[test]
+    fn test_config_set_nonzero_exit_is_error() {
+        let err = interpret_config_set(
+            "agents.defaults.bogus", "1", Some(2),
+            String::new(), "Error: unknown config key agents.defaults.bogus\n".to_string(),
+        ).unwrap_err();
+        assert!(err.contains("exit code 2"));
+        assert!(err.contains("unknown config key"));
+
+        let ok = interpret_config_set(
+            "gateway.port", "18790", Some(0),
+            "Warning: restart the gateway to apply\n".to_string(), String::new(),
+        ).unwrap();
+        assert_eq!(ok.exit_code, Some(0));
+        assert!(ok.stdout.contains("Warning"));
+    }
+
+    #[test]
+    fn test_move_provider_model() {
+        let mut config = json!({ "models": { "providers": { "openai": { "models": [
+            { "id": "a" }, { "id": "b" }, { "id": "c" }
+        ] } } } });
+        move_provider_model(&mut config, "openai", 2, 0).unwrap();
+        let ids: Vec<&str> = config["models"]["providers"]["openai"]["models"].as_array().unwrap()
+            .iter().map(|m| m["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec!["c", "a", "b"]);
+
+        let err = move_provider_model(&mut config, "openai", 0, 3).unwrap_err();
+        assert!(err.contains("to = 3 is out of range"));
+        assert!(move_provider_model(&mut config, "missing", 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_set_model_available_in() {
+        let mut config = json!({ "models": { "providers": { "openai": { "models": [{ "id": "gpt-4o" }] } } } });
+        assert!(set_model_available_in(&mut config, "openai/gpt-4o", true));
+        assert!(config["agents"]["defaults"]["models"]["openai/gpt-4o"].is_object());
+        assert!(!set_model_available_in(&mut config, "openai/gpt-4o", true));
+
+        config["agents"]["defaults"]["model"] = json!({ "primary": "openai/gpt-4o", "fallbacks": ["openai/gpt-4o"] });
+        assert!(set_model_available_in(&mut config, "openai/gpt-4o", false));
+        assert!(config["agents"]["defaults"]["models"].as_object().unwrap().is_empty());
+        // An emptied fallback list is removed
+        assert_eq!(config["agents"]["defaults"]["model"], json!({ "primary": "openai/gpt-4o" }));
+        assert!(!set_model_available_in(&mut config, "openai/gpt-4o", false));
+        // The provider definition is untouched
+        assert_eq!(config["models"]["providers"]["openai"]["models"][0]["id"], "gpt-4o");
+    }
+
+    #[test]
+    fn test_set_mcp_enabled_in() {
+        let server = |enabled: bool| crate::models::MCPConfig {
+            enabled,
+            ..crate::models::MCPConfig::stdio("npx", vec![])
+        };
+        let mut configs: HashMap<String, crate::models::MCPConfig> = HashMap::from([
+            ("a".to_string(), server(true)),
+            ("b".to_string(), server(false)),
+            ("c".to_string(), server(true)),
+        ]);
+
+        let disabled = set_mcp_enabled_in(&mut configs, false, None);
+        assert_eq!(disabled, vec!["a", "c"]);
+        assert!(configs.values().all(|m| !m.enabled));
+
+        // Restoring the snapshot leaves "b" disabled
+        let restored = set_mcp_enabled_in(&mut configs, true, Some(&disabled));
+        assert_eq!(restored, vec!["a", "c"]);
+        assert!(!configs["b"].enabled);
+
+        assert_eq!(set_mcp_enabled_in(&mut configs, true, None), vec!["b"]);
+        assert!(set_mcp_enabled_in(&mut configs, true, None).is_empty());
+    }
+
+    fn http_entry(url: &str) -> crate::models::MCPConfig {
+        crate::models::MCPConfig {
+            transport: McpTransport::Http { url: url.to_string(), headers: HashMap::new() },
+            enabled: true,
+            source: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_validate_mcp_entry_accepts_valid() {
+        let stdio = crate::models::MCPConfig::stdio("npx", vec![]);
+        assert!(validate_mcp_entry("excalidraw-mcp", &stdio).is_ok());
+        let http = http_entry("https://example.com/mcp");
+        assert!(validate_mcp_entry("remote_1.0", &http).is_ok());
+    }
+
+    #[test]
+    fn test_validate_mcp_entry_rejects_bad_names() {
+        let stdio = crate::models::MCPConfig::stdio("npx", vec![]);
+        assert!(validate_mcp_entry("", &stdio).unwrap_err().contains("cannot be empty"));
+        assert!(validate_mcp_entry("   ", &stdio).unwrap_err().contains("cannot be empty"));
+        assert!(validate_mcp_entry(" fs", &stdio).unwrap_err().contains("whitespace"));
+        assert!(validate_mcp_entry("my server", &stdio).unwrap_err().contains("invalid character ' '"));
+        assert!(validate_mcp_entry("a\"b", &stdio).unwrap_err().contains("invalid character '\"'"));
+    }
+
+    #[test]
+    fn test_validate_mcp_entry_rejects_bad_transports() {
+        use crate::models::MCPConfig;
+
+        let err = validate_mcp_entry("fs", &MCPConfig::stdio("", vec![])).unwrap_err();
+        assert!(err.contains("needs a command"));
+        let err = validate_mcp_entry("fs", &MCPConfig::stdio("  ", vec![])).unwrap_err();
+        assert!(err.contains("needs a command"));
+        let err = validate_mcp_entry("fs", &http_entry("")).unwrap_err();
+        assert!(err.contains("needs a URL"));
+        let err = validate_mcp_entry("fs", &http_entry("example.com/mcp")).unwrap_err();
+        assert!(err.contains("http:// or https://"));
+
+        let mut stdio = MCPConfig::stdio("npx", vec![]);
+        if let McpTransport::Stdio { env, .. } = &mut stdio.transport {
+            env.insert(" ".to_string(), "x".to_string());
+        }
+        assert!(validate_mcp_entry("fs", &stdio).unwrap_err().contains("empty environment variable name"));
+    }
+
+    #[test]
+    fn test_mcp_config_transport_serde() {
+        use crate::models::MCPConfig;
+
+        // Legacy untagged entries: a URL means HTTP, anything else is stdio
+        let legacy: HashMap<String, MCPConfig> = serde_json::from_value(json!({
+            "fs": { "command": "npx", "args": ["-y", "server-fs"] },
+            "remote": { "url": "https://example.com/mcp", "enabled": false }
+        })).unwrap();
+        assert!(matches!(&legacy["fs"].transport, McpTransport::Stdio { command, .. } if command == "npx"));
+        assert!(legacy["fs"].enabled);
+        assert!(matches!(&legacy["remote"].transport, McpTransport::Http { url, .. } if url == "https://example.com/mcp"));
+        assert!(!legacy["remote"].enabled);
+
+        // Tagged form round-trips and drops fields of the other transport
+        let value = serde_json::to_value(&legacy["remote"]).unwrap();
+        assert_eq!(value, json!({ "transport": "http", "url": "https://example.com/mcp", "enabled": false }));
+        let stray: MCPConfig = serde_json::from_value(json!({
+            "transport": "stdio", "command": "node", "url": "https://ignored"
+        })).unwrap();
+        assert_eq!(serde_json::to_value(&stray).unwrap(), json!({ "transport": "stdio", "command": "node", "enabled": true }));
+
+        let err = serde_json::from_value::<MCPConfig>(json!({ "transport": "sse", "url": "x" })).unwrap_err();
+        assert!(err.to_string().contains("unknown MCP transport 'sse'"));
+    }
+
+    #[test]
+    fn test_resolve_mcp_headers() {
+        let defaults = McpTestDefaults {
+            timeout_ms: None,
+            headers: HashMap::from([("X-Trace".to_string(), "1".to_string())]),
+        };
+        let server = HashMap::from([("Authorization".to_string(), "Bearer server".to_string())]);
+
+        let headers = resolve_mcp_headers(None, &defaults, &server);
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["Authorization"], "Bearer server");
+
+        // An explicit map replaces the defaults and overrides the server's headers
+        let explicit = HashMap::from([("Authorization".to_string(), "Bearer explicit".to_string())]);
+        let headers = resolve_mcp_headers(Some(&explicit), &defaults, &server);
+        assert_eq!(headers, explicit);
+    }
+
+    #[test]
+    fn test_headers_for_log_masks_credentials() {
+        let headers = HashMap::from([
+            ("Authorization".to_string(), "Bearer abcdefghijklmnop".to_string()),
+            ("X-Api-Key".to_string(), "key-1234567890".to_string()),
+            ("Accept-Language".to_string(), "en".to_string()),
+        ]);
+        let logged = headers_for_log(&headers);
+        assert_eq!(logged, "Accept-Language: en, Authorization: Bear...mnop, X-Api-Key: key-...7890");
+        assert!(!is_secret_header("Content-Type"));
+        assert!(is_secret_header("cookie"));
+    }
+
+    #[tokio::test]
+    async fn test_mcp_http_post_stops_reading_sse_after_reply() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            // Chunked SSE stream that stays open after the reply
+            let event = "data: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"protocolVersion\":\"2024-11-05\"}}\n\n";
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nMcp-Session-Id: abc\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n",
+                event.len(),
+                event
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        });
+
+        let started = std::time::Instant::now();
+        let (status, headers, body) = mcp_http_post(&url, "{}", &HashMap::new(), 10_000, Some(1)).await.unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert_eq!(status, 200);
+        assert_eq!(headers["mcp-session-id"], "abc");
+        assert_eq!(parse_http_initialize_body(&body).unwrap()["protocolVersion"], "2024-11-05");
+    }
+
+    #[test]
+    fn test_migration_v1_meta_structure() {
+        let before = json!({ "meta": "broken" });
+        let after = json!({ "meta": { "invalidMeta": "broken", "gui": {} } });
+        let mut config = before.clone();
+        repair_meta_structure(&mut config);
+        assert_eq!(config, after);
+        // Idempotent
+        repair_meta_structure(&mut config);
+        assert_eq!(config, after);
+    }
+
+    #[test]
+    fn test_migration_v2_brave_api_key() {
+        let before = json!({ "web": { "braveApiKey": "BSA-123" } });
+        let after = json!({ "web": { "providers": { "brave": { "apiKey": "BSA-123" } }, "activeProvider": "brave" } });
+        let mut config = before.clone();
+        migrate_legacy_web_config(&mut config);
+        assert_eq!(config, after);
+        migrate_legacy_web_config(&mut config);
+        assert_eq!(config, after);
+    }
+
+    #[test]
+    fn test_migrate_config_runs_pending_steps_once() {
+        let mut config = json!({ "web": { "braveApiKey": "BSA-123" } });
+        assert_eq!(migrate_config(&mut config), Some((0, CONFIG_SCHEMA_VERSION)));
+        assert_eq!(config["meta"]["schemaVersion"], CONFIG_SCHEMA_VERSION);
+        assert_eq!(config["web"]["providers"]["brave"]["apiKey"], "BSA-123");
+        assert!(config["meta"]["gui"].is_object());
+
+        // Already current: nothing to do
+        assert_eq!(migrate_config(&mut config), None);
+
+        // Only steps after the recorded version run
+        let mut partial = json!({ "meta": { "schemaVersion": 1, "gui": {} }, "web": { "braveApiKey": "k" } });
+        assert_eq!(migrate_config(&mut partial), Some((1, CONFIG_SCHEMA_VERSION)));
+        assert!(partial["web"].get("braveApiKey").is_none());
+
+        // A config from a newer Manager is left alone
+        let mut newer = json!({ "meta": { "schemaVersion": CONFIG_SCHEMA_VERSION + 1 }, "web": { "braveApiKey": "k" } });
+        let original = newer.clone();
+        assert_eq!(migrate_config(&mut newer), None);
+        assert_eq!(newer, original);
+    }
+
+    #[test]
+    fn test_resolve_config_paths() {
+        let _scope = CONFIG_SCOPE_TEST_LOCK.blocking_lock();
+        let config_file = crate::utils::platform::get_config_file_path();
+        let paths = resolve_config_paths(|p| p == config_file);
+        assert_eq!(paths.config_file.path, config_file);
+        assert!(paths.config_file.exists);
+        assert!(!paths.env_file.exists && !paths.mcp_config_file.exists && !paths.mcp_install_dir.exists);
+        assert!(paths.config_file.path.starts_with(&paths.config_dir.path));
+    }
+
+    #[test]
+    fn test_config_dir_override() {
+        use crate::utils::platform::config_dir_from;
+        use std::path::PathBuf;
+
+        let home = Some(PathBuf::from("/home/me"));
+        assert_eq!(config_dir_from(Some("/mnt/usb/openclaw/".to_string()), home.clone()), "/mnt/usb/openclaw");
+        assert_eq!(config_dir_from(Some("/".to_string()), home.clone()), "/");
+        if !cfg!(windows) {
+            assert_eq!(config_dir_from(Some("  ".to_string()), home.clone()), "/home/me/.openclaw");
+            assert_eq!(config_dir_from(None, home), "/home/me/.openclaw");
+        }
+        assert_eq!(config_dir_from(None, None), "~/.openclaw");
+    }
+
+    #[test]
+    fn test_delete_provider_in_lists_changes() {
+        let mut config = json!({
+            "models": { "providers": {
+                "openai": { "models": [{ "id": "gpt-4o" }, { "id": "gpt-4o-mini" }] },
+                "anthropic": { "models": [] }
+            } },
+            "agents": {
+                "defaults": {
+                    "model": { "primary": "openai/gpt-4o", "fallbacks": ["openai/gpt-4o", "anthropic/claude"] },
+                    "models": { "openai/gpt-4o": {}, "anthropic/claude": {} }
+                },
+                "list": [{ "id": "coder", "model": { "primary": "openai/gpt-4o-mini" } }]
+            }
+        });
+        let changes = delete_provider_in(&mut config, "openai");
+        assert_eq!(changes, vec![
+            "Remove provider openai (2 models)",
+            "Remove openai/gpt-4o from available models",
+            "Remove openai/gpt-4o from fallback models",
+            "Reset primary model (was openai/gpt-4o)",
+            "Warning: agent coder still uses openai/gpt-4o-mini",
+        ]);
+        assert!(config.pointer("/models/providers/openai").is_none());
+        assert!(config["agents"]["defaults"]["model"]["primary"].is_null());
+        assert_eq!(config["agents"]["defaults"]["model"]["fallbacks"], json!(["anthropic/claude"]));
+        assert!(config.pointer("/agents/defaults/models/anthropic~1claude").is_some());
+
+        // Nothing left to remove the second time round
+        assert_eq!(delete_provider_in(&mut config, "openai"), vec!["Warning: agent coder still uses openai/gpt-4o-mini"]);
+    }
+
+    #[test]
+    fn test_clear_channel_in_lists_changes() {
+        let mut config = json!({
+            "channels": { "feishu": { "enabled": true }, "telegram": {} },
+            "plugins": { "allow": ["feishu", "other"], "entries": { "feishu": {} } }
+        });
+        let changes = clear_channel_in(&mut config, "feishu");
+        assert_eq!(changes, vec![
+            "Remove channels.feishu",
+            "Remove feishu from plugins.allow",
+            "Remove plugins.entries.feishu",
+        ]);
+        assert_eq!(config["plugins"]["allow"], json!(["other"]));
+        assert!(clear_channel_in(&mut config, "feishu").is_empty());
+    }
+
+    #[test]
+    fn test_uninstall_mcp_changes() {
+        let mut configs = HashMap::from([("fs".to_string(), crate::models::MCPConfig::stdio("npx", vec![]))]);
+        assert_eq!(uninstall_mcp_changes("fs", &configs, Some("/mcps/fs")), vec![
+            "Delete directory /mcps/fs",
+            "Remove fs from mcps.json",
+            "Remove fs from mcporter.json",
+        ]);
+        configs.get_mut("fs").unwrap().enabled = false;
+        assert_eq!(uninstall_mcp_changes("fs", &configs, None), vec!["Remove fs from mcps.json"]);
+        assert!(uninstall_mcp_changes("missing", &configs, None).is_empty());
+    }
+
+    #[test]
+    fn test_configured_model_from_reads_reasoning_and_cost() {
+        let available = vec!["openai/o3".to_string()];
+        let stored = json!({
+            "id": "o3", "name": "o3", "api": "openai-completions", "reasoning": true,
+            "cost": { "input": 2.0, "output": 8.0, "cacheRead": 0.5, "cacheWrite": 0 }
+        });
+        let model = configured_model_from("openai", &stored, Some("openai/o3"), &available).unwrap();
+        assert!(model.is_primary && model.is_available);
+        assert_eq!(model.reasoning, Some(true));
+        let cost = model.cost.unwrap();
+        assert_eq!((cost.input, cost.output, cost.cache_read, cost.cache_write), (2.0, 8.0, 0.5, 0.0));
+
+        // Older entries without reasoning/cost still load
+        let legacy = configured_model_from("openai", &json!({ "id": "gpt-4" }), None, &[]).unwrap();
+        assert_eq!(legacy.name, "gpt-4");
+        assert_eq!(legacy.reasoning, None);
+        assert!(legacy.cost.is_none());
+        assert!(configured_model_from("openai", &json!({ "name": "no id" }), None, &[]).is_none());
+    }
+
+    #[test]
+    fn test_available_models_from_flags_orphans() {
+        let config = json!({
+            "models": { "providers": { "openai": { "models": [{ "id": "gpt-4o" }] } } },
+            "agents": { "defaults": { "models": { "openai/gpt-4o": {}, "openai/gpt-3": {}, "gone/model": {} } } }
+        });
+        let mut models = available_models_from(&config);
+        models.sort_by(|a, b| a.full_id.cmp(&b.full_id));
+        let flags: Vec<(&str, bool, bool)> = models.iter()
+            .map(|m| (m.full_id.as_str(), m.exists, m.provider_exists))
+            .collect();
+        assert_eq!(flags, vec![
+            ("gone/model", false, false),
+            ("openai/gpt-3", false, true),
+            ("openai/gpt-4o", true, true),
+        ]);
+        assert!(available_models_from(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_prune_config_in() {
+        let mut config = json!({
+            "models": { "providers": { "openai": { "models": [{ "id": "gpt-4o" }] } } },
+            "agents": {
+                "defaults": {
+                    "model": { "primary": "gone/model" },
+                    "models": { "openai/gpt-4o": {}, "openai/gpt-3": {}, "gone/model": {} }
+                },
+                "list": [{ "id": "coder" }],
+                "bindings": [{ "agentId": "old", "match": { "channel": "slack" } }]
+            },
+            "bindings": [
+                { "agentId": "main", "match": { "channel": "telegram" } },
+                { "agentId": "coder", "match": { "channel": "discord" } },
+                { "agentId": "deleted", "match": { "channel": "feishu" } }
+            ]
+        });
+        let report = prune_config_in(&mut config);
+        assert_eq!(report.removed_models, vec!["gone/model", "openai/gpt-3"]);
+        assert_eq!(report.removed_bindings, vec!["deleted (feishu)", "old (slack)"]);
+        assert_eq!(report.cleared_primary.as_deref(), Some("gone/model"));
+        assert_eq!(config["bindings"].as_array().unwrap().len(), 2);
+        assert!(config["agents"]["defaults"]["model"]["primary"].is_null());
+
+        // Nothing left to prune
+        assert_eq!(prune_config_in(&mut config), crate::models::PruneReport::default());
+    }
+
+    #[test]
+    fn test_sandbox_config_from_value_and_validate() {
+        // Legacy boolean form still reads back
+        let legacy = SandboxConfig::from_value(&json!(true)).unwrap();
+        assert!(legacy.enabled);
+        assert!(legacy.allowed_paths.is_empty());
+
+        let sandbox = SandboxConfig::from_value(&json!({
+            "enabled": true,
+            "allowedPaths": ["/srv/data", "/tmp"]
+        })).unwrap();
+        assert_eq!(sandbox.allowed_paths, vec!["/srv/data", "/tmp"]);
+        assert!(sandbox.validate().is_ok());
+        assert_eq!(sandbox.to_value(None), json!({ "enabled": true, "allowedPaths": ["/srv/data", "/tmp"] }));
+        // Settings the Manager doesn't manage survive a save
+        let existing = json!({ "mode": "docker", "enabled": false, "allowedPaths": ["/old"] });
+        assert_eq!(
+            sandbox.to_value(Some(&existing)),
+            json!({ "mode": "docker", "enabled": true, "allowedPaths": ["/srv/data", "/tmp"] })
+        );
+        assert!(SandboxConfig::from_value(&json!("yes")).is_none());
+
+        let relative = SandboxConfig { enabled: true, allowed_paths: vec!["data/cache".to_string()] };
+        assert!(relative.validate().unwrap_err().contains("absolute"));
+        let blank = SandboxConfig { enabled: true, allowed_paths: vec!["  ".to_string()] };
+        assert!(blank.validate().is_err());
+    }
+
+    #[test]
+    fn test_list_models_request_auth_headers() {
+        let openai = json!({
+            "baseUrl": "https://api.example.com/v1/",
+            "apiKey": "${EXAMPLE_KEY}",
+            "models": [{ "id": "m", "api": "openai-completions" }]
+        });
+        let (url, headers) = list_models_request(&openai, |v| (v == "EXAMPLE_KEY").then(|| "sk-1".to_string())).unwrap();
+        assert_eq!(url, "https://api.example.com/v1/models");
+        assert_eq!(headers, vec![("Authorization".to_string(), "Bearer sk-1".to_string())]);
+
+        let anthropic = json!({
+            "baseUrl": "https://api.anthropic.com",
+            "apiKey": "sk-ant",
+            "models": [{ "id": "m", "api": "anthropic-messages" }]
+        });
+        let (_, headers) = list_models_request(&anthropic, |_| None).unwrap();
+        assert!(headers.contains(&("x-api-key".to_string(), "sk-ant".to_string())));
+
+        assert!(list_models_request(&json!({ "apiKey": "k" }), |_| None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_probe_providers_runs_concurrently() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // One healthy endpoint and two that accept the connection but never answer
+        let healthy = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let healthy_addr = healthy.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = healthy.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let body = "{\"data\":[]}";
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        let mut dead_addrs = Vec::new();
+        for _ in 0..2 {
+            let dead = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            dead_addrs.push(dead.local_addr().unwrap());
+            tokio::spawn(async move {
+                let (_socket, _) = dead.accept().await.unwrap();
+                tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+            });
+        }
+
+        let provider = |addr: std::net::SocketAddr| json!({
+            "baseUrl": format!("http://{}/v1", addr),
+            "apiKey": "sk-test",
+            "models": [{ "id": "m", "api": "openai-completions" }]
+        });
+        let config = json!({ "models": { "providers": {
+            "zeta": provider(healthy_addr),
+            "alpha": provider(dead_addrs[0]),
+            "beta": provider(dead_addrs[1]),
+            "broken": { "apiKey": "k" }
+        } } });
+
+        let started = std::time::Instant::now();
+        let results = probe_providers(&config, |_| None, |_| false, 1000).await.unwrap();
+        // Two dead endpoints time out in parallel, not back to back
+        assert!(started.elapsed() < std::time::Duration::from_millis(1900));
+
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "beta", "broken", "zeta"]);
+        assert!(results[3].reachable);
+        assert!(results[3].latency_ms.is_some());
+        assert!(!results[0].reachable);
+        assert!(results[0].error.as_deref().unwrap().contains("Timed out"));
+        assert_eq!(results[2].error.as_deref(), Some("Provider has no baseUrl"));
+    }
+
+    #[test]
+    fn test_config_round_trip_is_byte_identical() {
+        // Keys deliberately out of alphabetical order, as openclaw writes them
+        let original = r#"{
+  "models": {
+    "providers": {
+      "zeta": {
+        "baseUrl": "https://z.example.com",
+        "models": []
+      },
+      "alpha": {
+        "baseUrl": "https://a.example.com",
+        "apiKey": "${ALPHA_KEY}"
+      }
+    }
+  },
+  "agents": {
+    "defaults": {
+      "model": {
+        "primary": "zeta/m"
+      }
+    }
+  },
+  "meta": {
+    "schemaVersion": 2,
+    "gui": {}
+  }
+}
+"#;
+        let config = parse_config_content(original).unwrap();
+        assert_eq!(render_config_content(&config, Some(original)).unwrap(), original);
+
+        // CRLF line endings and a BOM survive as well
+        let windows = format!("\u{FEFF}{}", original.replace('\n', "\r\n"));
+        let config = parse_config_content(&windows).unwrap();
+        assert_eq!(render_config_content(&config, Some(&windows)).unwrap(), windows);
+
+        // Removing a key keeps its siblings in place
+        let mut config = parse_config_content(original).unwrap();
+        config["models"]["providers"]["beta"] = json!({});
+        delete_provider_in(&mut config, "zeta");
+        let keys: Vec<&String> = config["models"]["providers"].as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["alpha", "beta"]);
+        let top: Vec<&String> = config.as_object().unwrap().keys().collect();
+        assert_eq!(top, vec!["models", "agents", "meta"]);
+    }
+
+    #[test]
+    fn test_upsert_provider_is_idempotent() {
+        let mut config = json!({
+            "agents": { "defaults": { "models": { "acme/m1": { "alias": "fast" } } } },
+            "meta": { "lastTouchedAt": "earlier" }
+        });
+        let provider = json!({ "baseUrl": "https://acme.example.com", "models": [{ "id": "m1" }] });
+        let ids = vec!["m1".to_string()];
+
+        assert!(upsert_provider(&mut config, "acme", provider.clone(), &ids, "first"));
+        assert_eq!(config["meta"]["lastTouchedAt"], "first");
+        // Existing per-model settings are kept
+        assert_eq!(config["agents"]["defaults"]["models"]["acme/m1"], json!({ "alias": "fast" }));
+
+        let saved = config.clone();
+        assert!(!upsert_provider(&mut config, "acme", provider, &ids, "second"));
+        assert_eq!(config, saved);
+
+        let changed = json!({ "baseUrl": "https://acme.example.com/v2", "models": [{ "id": "m1" }] });
+        assert!(upsert_provider(&mut config, "acme", changed, &ids, "third"));
+        assert_eq!(config["meta"]["lastTouchedAt"], "third");
+    }
+
+    #[test]
+    fn test_normalize_base_url() {
+        assert_eq!(normalize_base_url("  https://api.openai.com/v1/ ").unwrap(), "https://api.openai.com/v1");
+        assert_eq!(normalize_base_url("api.openai.com/v1").unwrap(), "https://api.openai.com/v1");
+        assert_eq!(normalize_base_url("https://api.anthropic.com").unwrap(), "https://api.anthropic.com");
+        // Only one trailing slash is stripped
+        assert_eq!(normalize_base_url("https://example.com/v1//").unwrap(), "https://example.com/v1/");
+
+        // Local endpoints (Ollama) stay on plain http
+        assert_eq!(normalize_base_url("http://localhost:11434").unwrap(), "http://localhost:11434");
+        assert_eq!(normalize_base_url("localhost:11434/v1").unwrap(), "http://localhost:11434/v1");
+        assert_eq!(normalize_base_url("127.0.0.1:11434").unwrap(), "http://127.0.0.1:11434");
+
+        assert!(normalize_base_url("   ").is_err());
+        assert!(normalize_base_url("ftp://example.com").is_err());
+        assert!(normalize_base_url("https://exa mple.com").is_err());
+        assert!(normalize_base_url("https://").is_err());
+        assert!(normalize_base_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_gateway_status_from_config() {
+        let status = gateway_status_from(&json!({}));
+        assert_eq!(status, GatewayStatus { mode: None, auth_mode: None, has_token: false, port: 18789 });
+
+        let config = json!({
+            "gateway": { "mode": "remote", "port": 19000, "auth": { "mode": "password", "token": "secret-token" } }
+        });
+        let status = gateway_status_from(&config);
+        assert_eq!(status.mode.as_deref(), Some("remote"));
+        assert_eq!(status.auth_mode.as_deref(), Some("password"));
+        assert!(status.has_token);
+        assert_eq!(status.port, 19000);
+        assert!(!serde_json::to_string(&status).unwrap().contains("secret-token"));
+
+        let empty_token = json!({ "gateway": { "auth": { "token": "" } } });
+        assert!(!gateway_status_from(&empty_token).has_token);
+    }
+
+    #[test]
+    fn test_set_gateway_auth_modes() {
+        let mut config = json!({ "gateway": { "mode": "local", "auth": { "mode": "token", "token": "keep-me" } } });
+
+        // Switching to token keeps an existing token
+        set_gateway_auth_in(&mut config, "token", || "fresh".to_string()).unwrap();
+        assert_eq!(config["gateway"]["auth"]["token"], "keep-me");
+
+        set_gateway_auth_in(&mut config, "none", || unreachable!()).unwrap();
+        assert_eq!(config["gateway"]["auth"], json!({ "mode": "none" }));
+        let status = gateway_status_from(&config);
+        assert_eq!(status.auth_mode.as_deref(), Some("none"));
+        assert!(!status.has_token);
+
+        set_gateway_auth_in(&mut config, "token", || "fresh".to_string()).unwrap();
+        assert_eq!(config["gateway"]["auth"], json!({ "mode": "token", "token": "fresh" }));
+        assert_eq!(config["gateway"]["mode"], "local");
+
+        assert!(set_gateway_auth_in(&mut config, "password", String::new).is_err());
+        let mut empty = json!({});
+        set_gateway_auth_in(&mut empty, "token", || "t".to_string()).unwrap();
+        assert!(gateway_status_from(&empty).has_token);
+    }
+
+    #[test]
+    fn test_existing_gateway_token_never_mints() {
+        assert_eq!(existing_gateway_token(&json!({})), None);
+        assert_eq!(existing_gateway_token(&json!({ "gateway": { "auth": { "token": "" } } })), None);
+        assert_eq!(
+            existing_gateway_token(&json!({ "gateway": { "auth": { "mode": "token", "token": "abc" } } })).as_deref(),
+            Some("abc")
+        );
+        // Auth disabled: no token needed
+        assert_eq!(
+            existing_gateway_token(&json!({ "gateway": { "auth": { "mode": "none" } } })).as_deref(),
+            Some("")
+        );
+    }
+
+    #[test]
+    fn test_prepare_gateway_auth() {
+        let mut fresh = json!({});
+        assert!(prepare_gateway_auth(&mut fresh, || "t".to_string()));
+        assert_eq!(
+            fresh,
+            json!({ "gateway": { "auth": { "token": "t", "mode": "token" }, "mode": "local", "controlUi": { "allowInsecureAuth": true } } })
+        );
+        assert!(!prepare_gateway_auth(&mut fresh, || unreachable!()));
+
+        // An existing token is kept; only the Control UI flag is added
+        let mut older = json!({ "gateway": { "mode": "remote", "auth": { "mode": "token", "token": "abc" } } });
+        assert!(prepare_gateway_auth(&mut older, || unreachable!()));
+        assert_eq!(older["gateway"]["auth"]["token"], "abc");
+        assert_eq!(older["gateway"]["mode"], "remote");
+        assert_eq!(older["gateway"]["controlUi"]["allowInsecureAuth"], true);
+
+        let mut disabled = json!({ "gateway": { "auth": { "mode": "none" } } });
+        assert!(!prepare_gateway_auth(&mut disabled, || unreachable!()));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_gateway_auth_uses_the_config_save_path() {
+        let _scope = CONFIG_SCOPE_TEST_LOCK.lock().await;
+        let dir = std::env::temp_dir().join(format!("openclaw-gateway-auth-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = "\u{FEFF}{\r\n  // local gateway\r\n  \"gateway\": { \"auth\": { \"token\": \"abc\" } }\r\n}\r\n";
+        std::fs::write(dir.join("openclaw.json"), original).unwrap();
+        crate::utils::platform::set_config_scope(Some(dir.display().to_string()));
+
+        let result = ensure_gateway_auth().await;
+        let saved = std::fs::read_to_string(dir.join("openclaw.json")).unwrap();
+        let backups: Vec<String> = std::fs::read_dir(&dir).unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.file_name().unwrap().to_string_lossy().starts_with("openclaw.json.bak-"))
+            .map(|p| std::fs::read_to_string(p).unwrap())
+            .collect();
+
+        crate::utils::platform::set_config_scope(None);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        result.unwrap();
+        // BOM and CRLF survive, and the commented original is backed up before it is rewritten
+        assert!(saved.starts_with('\u{FEFF}') && saved.contains("\r\n"));
+        let saved = parse_config_content(&saved).unwrap();
+        assert_eq!(saved["gateway"]["auth"]["token"], "abc");
+        assert_eq!(saved["gateway"]["controlUi"]["allowInsecureAuth"], true);
+        assert_eq!(backups, vec![original.to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_config_lock_serializes_read_modify_write() {
+        // Point openclaw.json at a temp project scope so the real load/save path is exercised
+        let _scope = CONFIG_SCOPE_TEST_LOCK.lock().await;
+        let dir = std::env::temp_dir().join(format!("openclaw-lock-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("openclaw.json"), "{}").unwrap();
+        crate::utils::platform::set_config_scope(Some(dir.display().to_string()));
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for i in 0..25 {
+            // A command holding the lock from load to save, awaiting mid-update
+            tasks.spawn(async move {
+                let _config_lock = lock_config().await;
+                let mut config = load_openclaw_config()?;
+                tokio::task::yield_now().await;
+                config["meta"][format!("key{}", i)] = json!(i);
+                save_openclaw_config(&config)
+            });
+            // A real command racing it
+            tasks.spawn(async move {
+                set_model_available(format!("p/m{}", i), true).await.map(|_| ())
+            });
+        }
+        // Non-command writers go through the same lock
+        tasks.spawn(ensure_gateway_auth());
+        tasks.spawn(migrate_stored_config());
+        let mut results = Vec::new();
+        while let Some(result) = tasks.join_next().await {
+            results.push(result.unwrap());
+        }
+
+        let config = read_openclaw_config();
+        crate::utils::platform::set_config_scope(None);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(results.iter().all(|r| r.is_ok()), "{:?}", results);
+        let config = config.unwrap();
+        let keys = (0..25).filter(|i| config["meta"].get(format!("key{}", i)).is_some()).count();
+        let models = config.pointer("/agents/defaults/models").and_then(|v| v.as_object()).map_or(0, |m| m.len());
+        assert_eq!((keys, models), (25, 25), "no update may be lost");
+        assert!(existing_gateway_token(&config).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_migration_is_written_only_by_the_startup_pass() {
+        let _scope = CONFIG_SCOPE_TEST_LOCK.lock().await;
+        let dir = std::env::temp_dir().join(format!("openclaw-migrate-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = r#"{ "web": { "braveApiKey": "k" } }"#;
+        std::fs::write(dir.join("openclaw.json"), original).unwrap();
+        crate::utils::platform::set_config_scope(Some(dir.display().to_string()));
+
+        // Loading migrates in memory only
+        let loaded = load_openclaw_config();
+        let untouched = std::fs::read_to_string(dir.join("openclaw.json")).unwrap();
+        let migrated = migrate_stored_config().await;
+        let stored = read_openclaw_config();
+        let backups = std::fs::read_dir(&dir).unwrap()
+            .filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().starts_with("openclaw.json.bak-"))
+            .count();
+
+        crate::utils::platform::set_config_scope(None);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.unwrap()["meta"]["schemaVersion"], json!(CONFIG_SCHEMA_VERSION));
+        assert_eq!(untouched, original);
+        migrated.unwrap();
+        let stored = stored.unwrap();
+        assert_eq!(stored["meta"]["schemaVersion"], json!(CONFIG_SCHEMA_VERSION));
+        assert!(stored.pointer("/web/braveApiKey").is_none());
+        assert_eq!(backups, 1);
+    }
+
+    #[test]
+    fn test_parse_tool_version() {
+        assert_eq!(parse_tool_version("git version 2.43.0\n").as_deref(), Some("2.43.0"));
+        assert_eq!(parse_tool_version("v22.12.0").as_deref(), Some("22.12.0"));
+        assert_eq!(parse_tool_version("10.9.0").as_deref(), Some("10.9.0"));
+        assert_eq!(parse_tool_version("git version 2.43.0.windows.1").as_deref(), Some("2.43.0.windows.1"));
+        assert_eq!(parse_tool_version("command not found"), None);
+        assert_eq!(parse_tool_version(""), None);
+    }
+
+    #[test]
+    fn test_prerequisite_statuses() {
+        let statuses = prerequisite_statuses(
+            |name| name != "git",
+            |name| Some(if name == "node" { "v22.1.0".to_string() } else { "10.2.4".to_string() }),
+        );
+        let names: Vec<&str> = statuses.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["git", "node", "npm", "npx"]);
+        assert_eq!(statuses[0], ToolStatus { name: "git".to_string(), found: false, version: None });
+        assert_eq!(statuses[1].version.as_deref(), Some("22.1.0"));
+        assert!(statuses[3].found);
+    }
+
+    #[test]
+    fn test_format_command_failure_includes_both_streams() {
+        let msg = format_command_failure("npm install failed", b"npm ERR! ERESOLVE peer dep conflict\n", b"npm WARN deprecated\n");
+        assert_eq!(msg, "npm install failed\n--- stderr ---\nnpm WARN deprecated\n--- stdout ---\nnpm ERR! ERESOLVE peer dep conflict");
+
+        assert_eq!(format_command_failure("Git clone failed", b"", b"  \n"), "Git clone failed (no output)");
+
+        // Long output keeps the tail, where the error is
+        let long = format!("{}THE ERROR", "x".repeat(10_000));
+        let msg = format_command_failure("npm install failed", long.as_bytes(), b"");
+        assert!(msg.ends_with("THE ERROR"));
+        assert!(msg.contains("characters truncated"));
+        assert!(msg.len() < 4200);
+    }
+
+    #[test]
+    fn test_installed_npm_packages() {
+        let ls = r#"{ "name": "lib", "dependencies": { "mcporter": { "version": "0.6.1" }, "npm": { "version": "10.9.0" } } }"#;
+        assert_eq!(installed_npm_packages(ls, &["mcporter", "@openclaw/mcporter"]), vec!["mcporter"]);
+
+        let legacy = r#"{ "dependencies": { "@openclaw/mcporter": { "version": "0.1.0" } } }"#;
+        assert_eq!(installed_npm_packages(legacy, &["mcporter", "@openclaw/mcporter"]), vec!["@openclaw/mcporter"]);
+
+        assert!(installed_npm_packages("{}", &["mcporter"]).is_empty());
+        assert!(installed_npm_packages("npm ERR!", &["mcporter"]).is_empty());
+    }
+
+    #[test]
+    fn test_mcporter_status_update_available() {
+        let status = mcporter_status_from(true, Some("0.5.2".to_string()), Some("0.6.0".to_string()));
+        assert!(status.update_available);
+
+        assert!(!mcporter_status_from(true, Some("0.6.0".to_string()), Some("0.6.0".to_string())).update_available);
+        // Unknown versions never claim an update
+        assert!(!mcporter_status_from(true, None, Some("0.6.0".to_string())).update_available);
+        assert!(!mcporter_status_from(true, Some("0.6.0".to_string()), None).update_available);
+
+        assert_eq!(parse_tool_version("mcporter 0.6.1").as_deref(), Some("0.6.1"));
+    }
+
+    #[test]
+    fn test_check_mcp_command_resolves() {
+        let on_path = |c: &str| c == "uvx" || c == "node";
+        let is_file = |p: &str| p == "/opt/mcp/server.js";
+
+        assert!(check_mcp_command_resolves("node", on_path, is_file).is_ok());
+        assert!(check_mcp_command_resolves(" uvx ", on_path, is_file).is_ok());
+        assert!(check_mcp_command_resolves("/opt/mcp/server.js", on_path, is_file).is_ok());
+
+        let err = check_mcp_command_resolves("deno", on_path, is_file).unwrap_err();
+        assert!(err.contains("not found on the PATH"));
+        let err = check_mcp_command_resolves("/opt/mcp/missing.js", on_path, is_file).unwrap_err();
+        assert!(err.contains("does not exist"));
+        assert!(check_mcp_command_resolves("C:\\tools\\mcp.exe", on_path, is_file).is_err());
+    }
+
+    #[test]
+    fn test_mcp_install_name() {
+        assert_eq!(
+            mcp_install_name("https://github.com/excalidraw/excalidraw-mcp").unwrap(),
+            "excalidraw__excalidraw-mcp"
+        );
+        // Same repo name under different owners no longer collides
+        assert_eq!(mcp_install_name("https://github.com/org-a/server.git/ ").unwrap(), "org-a__server");
+        assert_eq!(mcp_install_name("git@github.com:org-b/server.git").unwrap(), "org-b__server");
+        assert_eq!(mcp_install_name("https://example.com/server").unwrap(), "server");
+        assert_eq!(mcp_install_name("https://example.com/owner/my%20server").unwrap(), "owner__my-20server");
+        assert!(mcp_install_name(" / ").is_err());
+
+        assert_eq!(legacy_mcp_install_name("https://github.com/org-a/server.git").as_deref(), Some("server"));
+    }
+
+    #[test]
+    fn test_sanitize_mcp_dir_name() {
+        assert_eq!(sanitize_mcp_dir_name("my:server*?"), "my-server");
+        assert_eq!(sanitize_mcp_dir_name("..hidden.."), "hidden");
+        assert_eq!(sanitize_mcp_dir_name("con"), "con_");
+        assert_eq!(sanitize_mcp_dir_name("LPT1.txt"), "LPT1.txt_");
+        assert_eq!(sanitize_mcp_dir_name("console"), "console");
+        assert_eq!(sanitize_mcp_dir_name("../etc"), "etc");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_mcp_install_timeout_and_cancel() {
+        // Timeout kills the running step
+        let install = McpInstall::start("timeout-test", 1).unwrap();
+        let started = std::time::Instant::now();
+        let err = install.run("sleep", &["30"], None).await.unwrap_err();
+        assert!(err.contains("timed out after 1s"), "{}", err);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+        // Only one install per name at a time; the name is free again once dropped
+        assert!(McpInstall::start("timeout-test", 1).is_err());
+        drop(install);
+        assert!(McpInstall::start("timeout-test", 1).is_ok());
+
+        // Cancellation from another task
+        let install = McpInstall::start("cancel-test", 60).unwrap();
+        tokio::spawn(async {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            assert!(cancel_mcp_install("cancel-test"));
+        });
+        let err = install.run("sleep", &["30"], None).await.unwrap_err();
+        assert!(err.contains("cancelled"), "{}", err);
+        drop(install);
+        assert!(!cancel_mcp_install("cancel-test"));
+    }
+
+    #[test]
+    fn test_env_entries() {
+        let content = "# comment\nexport OPENAI_API_KEY=\"sk-1234567890abcd\"\nexport SHORT='abc'\nexport SHORT=\"abcdefghijkl\"\nPLAIN=ignored\n";
+        let entries = env_entries(content);
+        assert_eq!(
+            entries,
+            vec![
+                EnvEntry { key: "OPENAI_API_KEY".into(), masked_value: "sk-1...abcd".into() },
+                EnvEntry { key: "SHORT".into(), masked_value: "abcd...ijkl".into() },
+            ]
+        );
+        assert!(env_entries("").is_empty());
+    }
+
+    #[test]
+    fn test_channel_env_keys() {
+        let env = "export OPENCLAW_TELEGRAM_EXTRA=\"1\"\nexport OPENCLAW_TELEGRAM_USERID=\"42\"\nexport OPENCLAW_TELEGRAMX_USERID=\"7\"\nexport OPENCLAW_DISCORD_TESTCHANNELID=\"9\"\nexport OPENAI_API_KEY=\"sk\"\n";
+        assert_eq!(channel_env_keys("telegram", env), vec!["OPENCLAW_TELEGRAM_USERID", "OPENCLAW_TELEGRAM_EXTRA"]);
+        assert_eq!(channel_env_keys("discord", env), vec!["OPENCLAW_DISCORD_TESTCHANNELID"]);
+        assert!(channel_env_keys("slack", env).is_empty());
+    }
+
+    #[test]
+    fn test_channel_field_masks_secrets() {
+        use crate::models::FieldSource;
+        let token = channel_field("botToken", &json!("123456:ABCDEFGHIJ"), FieldSource::Config);
+        assert_eq!(token.value_masked, "1234...GHIJ");
+        assert_eq!(token.source, FieldSource::Config);
+
+        let reference = channel_field("botToken", &json!("${TELEGRAM_BOT_TOKEN}"), FieldSource::Config);
+        assert_eq!(reference.value_masked, "${TELEGRAM_BOT_TOKEN}");
+
+        assert_eq!(channel_field("userId", &json!("42"), FieldSource::Env).value_masked, "42");
+        assert_eq!(channel_field("dmPolicy", &json!(["a"]), FieldSource::Config).value_masked, "[\"a\"]");
+    }
+
+    #[test]
+    fn test_mask_channel_value_and_masked_echo() {
+        let token = json!("123456:ABCDEFGHIJ");
+        let masked = mask_channel_value("botToken", &token);
+        assert_eq!(masked, json!("1234...GHIJ"));
+        assert_eq!(mask_channel_value("botToken", &json!("${TG_TOKEN}")), json!("${TG_TOKEN}"));
+        assert_eq!(mask_channel_value("dmPolicy", &json!("pairing")), json!("pairing"));
+        assert_eq!(
+            mask_channel_value("accounts", &json!({ "work": { "botToken": "abcdefghijklmnop" } })),
+            json!({ "work": { "botToken": "abcd...mnop" } })
+        );
+
+        // The masked value sent back on save keeps the stored secret; a real edit replaces it
+        assert!(is_masked_echo("botToken", &masked, Some(&token)));
+        assert!(!is_masked_echo("botToken", &json!("new-token-value"), Some(&token)));
+        assert!(!is_masked_echo("botToken", &token, Some(&token)));
+        assert!(!is_masked_echo("dmPolicy", &json!("****"), Some(&json!("open"))));
+        assert!(!is_masked_echo("botToken", &masked, None));
+    }
+
+    #[test]
+    fn test_parse_config_content_accepts_comments() {
+        let content = r#"{
+  // gateway settings
+  "gateway": { "port": 18789, /* default */ "mode": "local", },
+  "url": "http://example.com/a//b", "note": "not /* a comment */",
+  "escaped": "quote \" // still a string",
+  "list": [1, 2,],
+}
+"#;
+        let config = parse_config_content(content).unwrap();
+        assert_eq!(config["gateway"]["port"], 18789);
+        assert_eq!(config["gateway"]["mode"], "local");
+        assert_eq!(config["url"], "http://example.com/a//b");
+        assert_eq!(config["note"], "not /* a comment */");
+        assert_eq!(config["escaped"], "quote \" // still a string");
+        assert_eq!(config["list"], json!([1, 2]));
+        assert!(content_has_comments(content));
+
+        // Plain JSON is untouched, and errors still point at the original line
+        assert!(!content_has_comments("{\n  \"a\": \"b, ]\"\n}\n"));
+        let err = parse_config_content("{\n /* one\n two */\n \"a\": }").unwrap_err();
+        assert!(err.contains("line 4"), "{}", err);
+    }
+
+    #[test]
+    fn test_typed_config_round_trip() {
+        let content = r#"{
+  "meta": { "schemaVersion": 2, "custom": true },
+  "gateway": { "auth": { "token": "t" }, "port": 18789, "mode": "local" },
+  "models": { "providers": { "openai": { "models": [{ "id": "gpt-4o", "cost": { "input": 3 } }], "baseUrl": "https://api.openai.com/v1" } } },
+  "agents": { "defaults": { "model": { "primary": null, "fallbacks": ["a/b"] }, "workspace": "~/w" }, "list": [] },
+  "unknownTopLevel": { "keep": [1, 2.5] }
+}"#;
+        let raw = parse_config_content(content).unwrap();
+        let typed = typed_config_from(raw.clone()).unwrap();
+        assert_eq!(typed.gateway.as_ref().unwrap().port, Some(18789));
+        assert_eq!(typed.meta.as_ref().unwrap().schema_version, Some(2));
+        assert!(typed.extra.contains_key("unknownTopLevel"));
+
+        // Unchanged: identical document, same key order, explicit null kept
+        let mut value = serde_json::to_value(&typed).unwrap();
+        align_key_order(&mut value, &raw);
+        assert_eq!(serde_json::to_string(&value).unwrap(), serde_json::to_string(&raw).unwrap());
+
+        // A typed edit lands in place
+        let mut typed = typed;
+        typed.agents.as_mut().unwrap().defaults.as_mut().unwrap().model.as_mut().unwrap().primary = Some("openai/gpt-4o".into());
+        let mut value = serde_json::to_value(&typed).unwrap();
+        align_key_order(&mut value, &raw);
+        assert_eq!(value["agents"]["defaults"]["model"], json!({ "primary": "openai/gpt-4o", "fallbacks": ["a/b"] }));
+        let keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["meta", "gateway", "models", "agents", "unknownTopLevel"]);
+
+        // Wrong shapes are reported instead of read as missing
+        let err = typed_config_from(json!({ "gateway": { "port": "18789" } })).unwrap_err();
+        assert!(err.contains("unexpected shape"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_suggested_model_statuses() {
+        let official = get_official_providers().await.unwrap();
+        let config = json!({
+            "models": { "providers": {
+                "anthropic": { "baseUrl": "https://example.com", "models": [{ "id": "claude-opus-4-5-20251101" }] },
+                "my-claude": { "baseUrl": "https://api.anthropic.com/", "models": [{ "id": "claude-opus-4-5-20251101" }] },
+                "other": { "baseUrl": "https://other.example", "models": [{ "id": "gemini-3-pro-preview" }] }
+            } }
+        });
+        let statuses = suggested_model_statuses(&official, &config);
+        let status = |provider: &str, model: &str| {
+            statuses.iter().find(|s| s.provider_id == provider && s.model_id == model).unwrap().clone()
+        };
+
+        let opus = status("anthropic", "claude-opus-4-5-20251101");
+        assert!(opus.configured);
+        assert_eq!(opus.configured_in, vec!["anthropic", "my-claude"]);
+        // Same model ID under an unrelated provider doesn't count
+        assert!(!status("google", "gemini-3-pro-preview").configured);
+        let total: usize = official.iter().map(|p| p.suggested_models.len()).sum();
+        assert_eq!(statuses.len(), total);
+    }
+
+    #[test]
+    fn test_merge_provider_presets() {
+        let builtin: Vec<crate::models::OfficialProvider> = serde_json::from_str(BUILTIN_OFFICIAL_PROVIDERS).unwrap();
+        assert!(builtin.iter().any(|p| p.id == "anthropic"));
+
+        let user: Vec<crate::models::OfficialProvider> = serde_json::from_value(json!([
+            { "id": "openai", "name": "OpenAI (proxy)", "icon": "🟢", "default_base_url": "https://proxy.example/v1",
+              "api_type": "openai-completions", "suggested_models": [], "requires_api_key": true, "docs_url": null },
+            { "id": "local", "name": "Local", "icon": "🏠", "api_type": "openai-completions",
+              "suggested_models": [{ "id": "llama", "name": "Llama", "recommended": true }], "requires_api_key": false }
+        ])).unwrap();
+        let merged = merge_provider_presets(builtin.clone(), user);
+
+        assert_eq!(merged.len(), builtin.len() + 1);
+        let openai_index = builtin.iter().position(|p| p.id == "openai").unwrap();
+        assert_eq!(merged[openai_index].name, "OpenAI (proxy)");
+        assert_eq!(merged.last().unwrap().id, "local");
+        assert_eq!(merged.last().unwrap().suggested_models[0].context_window, None);
+    }
+
+    #[test]
+    fn test_validate_api_type() {
+        assert!(validate_api_type("anthropic-messages").is_ok());
+        assert!(validate_api_type("openai-completions").is_ok());
+        let err = validate_api_type("openai-completion").unwrap_err();
+        assert!(err.contains("openai-completion'"), "{}", err);
+        assert!(err.contains("anthropic-messages, openai-completions"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_google_preset() {
+        let official = get_official_providers().await.unwrap();
+        let google = official.iter().find(|p| p.id == "google").expect("google preset");
+        assert_eq!(google.api_type, "openai-completions");
+        assert!(google.requires_api_key);
+        assert!(google.docs_url.is_some());
+        // Stored exactly as save_provider would normalize it, so editing doesn't flag a custom URL
+        let base_url = google.default_base_url.as_deref().unwrap();
+        assert_eq!(normalize_base_url(base_url).unwrap(), base_url);
+        for id in ["gemini-2.0-flash", "gemini-1.5-pro"] {
+            let model = google.suggested_models.iter().find(|m| m.id == id).unwrap();
+            assert!(model.context_window.unwrap() >= 1_000_000);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_model_limits() {
+        let model = |context_window: Option<u32>, max_tokens: Option<u32>| crate::models::ModelConfig {
+            id: "claude-opus-4-5-20251101".into(),
+            name: "Opus".into(),
+            api: None,
+            input: vec![],
+            context_window,
+            max_tokens,
+            reasoning: None,
+            cost: None,
+        };
+        assert!(validate_model_limits(&model(Some(200000), Some(8192))).is_ok());
+        assert!(validate_model_limits(&model(None, Some(8192))).is_ok());
+        assert!(validate_model_limits(&model(Some(8192), Some(8192))).is_ok());
+
+        let err = validate_model_limits(&model(Some(4096), Some(8192))).unwrap_err();
+        assert!(err.contains("(8192) cannot exceed the context window (4096)"), "{}", err);
+        assert!(validate_model_limits(&model(Some(0), None)).unwrap_err().contains("context window must be greater than 0"));
+        assert!(validate_model_limits(&model(None, Some(0))).unwrap_err().contains("max tokens must be greater than 0"));
+
+        // Above the preset's known limits only warns, and only for the matching preset
+        let official = get_official_providers().await.unwrap();
+        let models = [model(Some(400000), Some(8192))];
+        let warnings = model_limit_warnings("anthropic", &models, &official);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("context window 400000 is above the known limit 200000"), "{}", warnings[0]);
+        assert!(model_limit_warnings("my-proxy", &models, &official).is_empty());
+    }
+
+    #[test]
+    fn test_set_model_chain_in() {
+        let mut config = typed_config_from(json!({
+            "agents": { "defaults": {
+                "model": { "primary": "a/one", "fallbacks": ["b/two"] },
+                "models": { "a/one": {}, "b/two": {}, "c/three": {} }
+            } }
+        })).unwrap();
+
+        set_model_chain_in(&mut config, "c/three", &["a/one".into(), "b/two".into()]).unwrap();
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["agents"]["defaults"]["model"], json!({ "primary": "c/three", "fallbacks": ["a/one", "b/two"] }));
+
+        let err = set_model_chain_in(&mut config, "a/one", &["x/missing".into()]).unwrap_err();
+        assert!(err.contains("x/missing is not in the available models"), "{}", err);
+        let err = set_model_chain_in(&mut config, "a/one", &["b/two".into(), "a/one".into()]).unwrap_err();
+        assert!(err.contains("a/one appears more than once"), "{}", err);
+
+        // Clearing the chain removes the key
+        set_model_chain_in(&mut config, "a/one", &[]).unwrap();
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["agents"]["defaults"]["model"], json!({ "primary": "a/one" }));
+    }
+
+    #[test]
+    fn test_string_agent_model() {
+        let raw = json!({
+            "agents": { "defaults": { "model": "a/one", "models": { "a/one": {}, "b/two": {} } } }
+        });
+        let mut config = typed_config_from(raw.clone()).unwrap();
+        let model = config.agents.as_ref().unwrap().defaults.as_ref().unwrap().model.as_ref().unwrap();
+        assert_eq!(model.primary.as_deref(), Some("a/one"));
+        // Untouched, the string form is written back as is
+        assert_eq!(serde_json::to_value(&config).unwrap(), raw);
+        assert_eq!(model_primary(raw.pointer("/agents/defaults/model")), Some("a/one"));
+
+        // Adding fallbacks switches to the object form
+        set_model_chain_in(&mut config, "b/two", &["a/one".into()]).unwrap();
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["agents"]["defaults"]["model"], json!({ "primary": "b/two", "fallbacks": ["a/one"] }));
+
+        let mut raw = raw;
+        clear_model_primary(&mut raw["agents"]["defaults"]["model"]);
+        assert_eq!(raw["agents"]["defaults"]["model"], json!({ "primary": null }));
+    }
+
+    #[test]
+    fn test_providers_with_base_url() {
+        let config = json!({ "models": { "providers": {
+            "openai": { "baseUrl": "https://api.openai.com/v1/" },
+            "openai-work": { "baseUrl": "HTTPS://API.OPENAI.COM/v1" },
+            "local": { "baseUrl": "localhost:11434" },
+            "broken": {}
+        } } });
+        assert_eq!(providers_with_base_url(&config, "openai-2", "https://api.openai.com/v1"), vec!["openai", "openai-work"]);
+        // The provider being saved doesn't conflict with itself
+        assert_eq!(providers_with_base_url(&config, "openai", "https://api.openai.com/v1"), vec!["openai-work"]);
+        assert_eq!(providers_with_base_url(&config, "ollama", "http://localhost:11434"), vec!["local"]);
+        assert!(providers_with_base_url(&config, "x", "https://example.com").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_config_scope() {
+        let base = std::env::temp_dir().join(format!("openclaw-scope-test-{}", std::process::id()));
+        let nested = base.join("nested-project");
+        std::fs::create_dir_all(nested.join(".openclaw")).unwrap();
+        std::fs::write(nested.join(".openclaw").join("openclaw.json"), "{}").unwrap();
+        let flat = base.join("flat-project");
+        std::fs::create_dir_all(&flat).unwrap();
+
+        // A bare directory is used as-is (openclaw.json is created there on first save)
+        assert_eq!(resolve_config_scope(flat.to_str().unwrap()).unwrap(), flat.display().to_string());
+        assert_eq!(
+            resolve_config_scope(&format!("{}/", nested.display())).unwrap(),
+            nested.join(".openclaw").display().to_string()
+        );
+        // A project-root openclaw.json wins over .openclaw/
+        std::fs::write(nested.join("openclaw.json"), "{}").unwrap();
+        assert_eq!(resolve_config_scope(nested.to_str().unwrap()).unwrap(), nested.display().to_string());
+        assert!(resolve_config_scope(base.join("missing").to_str().unwrap()).is_err());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_default_config_skeleton() {
+        let mut config = default_config_skeleton();
+        assert!(validate_config_value(&config).is_empty());
+        assert_eq!(migrate_config(&mut config), None);
+        assert_eq!(config["gateway"]["mode"], "local");
+        assert!(config["models"]["providers"].as_object().unwrap().is_empty());
+        assert!(config.pointer("/manager/timezone").and_then(|v| v.as_str()).is_some());
+    }
+
+    #[test]
+    fn test_provider_health_cache() {
+        let health = crate::models::ProviderHealth { name: "openai".into(), reachable: true, latency_ms: Some(42), error: None, cached: false };
+        let ttl = std::time::Duration::from_secs(60);
+        let t0 = std::time::Instant::now();
+        let mut cache = ProviderHealthCache::default();
+        cache.insert("https://api.openai.com/v1", t0, health.clone());
+
+        let hit = cache.get("openai", "https://api.openai.com/v1", t0 + std::time::Duration::from_secs(30), ttl).unwrap();
+        assert!(hit.cached);
+        assert_eq!(hit.latency_ms, Some(42));
+
+        // Expired, endpoint changed, unknown provider
+        assert!(cache.get("openai", "https://api.openai.com/v1", t0 + std::time::Duration::from_secs(61), ttl).is_none());
+        assert!(cache.get("openai", "https://proxy.example.com/v1", t0, ttl).is_none());
+        assert!(cache.get("anthropic", "https://api.openai.com/v1", t0, ttl).is_none());
+    }
+
+    #[test]
+    fn test_agent_last_used_model() {
+        let mut config = json!({ "agents": { "list": [{ "id": "coder" }] } });
+        assert_eq!(agent_last_used_model(&config, "coder"), None);
+
+        set_agent_last_used_model_in(&mut config, "coder", Some("openai/gpt-4o"));
+        assert_eq!(agent_last_used_model(&config, "coder").as_deref(), Some("openai/gpt-4o"));
+        assert_eq!(config["meta"]["gui"]["agents"]["coder"]["lastUsedModel"], "openai/gpt-4o");
+        // The agent entry itself is untouched
+        assert_eq!(config["agents"]["list"][0], json!({ "id": "coder" }));
+
+        set_agent_last_used_model_in(&mut config, "coder", None);
+        assert_eq!(agent_last_used_model(&config, "coder"), None);
+        assert!(config["meta"]["gui"].get("agents").is_none());
+
+        // Clearing on a config without the namespace does not create it
+        let mut empty = json!({});
+        set_agent_last_used_model_in(&mut empty, "coder", None);
+        assert!(empty["meta"]["gui"].get("agents").is_none());
+    }
+
+    #[test]
+    fn test_check_config_for_save() {
+        assert_eq!(check_config_for_save(&default_config_skeleton(), None), Ok(Vec::new()));
+
+        assert!(check_config_for_save(&json!([]), None).unwrap_err().contains("JSON object"));
+        assert!(check_config_for_save(&json!({ "gateway": { "port": "eighteen" } }), None).is_err());
+        let err = check_config_for_save(&json!({ "gateway": { "port": 70000 } }), None).unwrap_err();
+        assert!(err.contains("/gateway/port"), "{}", err);
+
+        let redacted = json!({ "models": { "providers": { "openai": { "apiKey": REDACTED_PLACEHOLDER } } } });
+        let err = check_config_for_save(&redacted, None).unwrap_err();
+        assert!(err.contains("/models/providers/openai/apiKey"), "{}", err);
+
+        // Pointer segments are escaped
+        let redacted = json!({ "models": { "providers": { "a/b~c": { "apiKey": REDACTED_PLACEHOLDER } } } });
+        let err = check_config_for_save(&redacted, None).unwrap_err();
+        assert!(err.contains("/models/providers/a~1b~0c/apiKey"), "{}", err);
+    }
+
+    #[test]
+    fn test_check_config_for_save_keeps_existing_schema_errors_as_warnings() {
+        let current = json!({ "agents": { "defaults": { "maxConcurrent": 0 } }, "gateway": { "port": 18789 } });
+        let mut edited = current.clone();
+        edited["gateway"]["mode"] = json!("local");
+
+        let warnings = check_config_for_save(&edited, Some(&current)).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("/agents/defaults/maxConcurrent"), "{:?}", warnings);
+
+        // A new mismatch still blocks the save
+        edited["gateway"]["port"] = json!("eighteen");
+        assert!(check_config_for_save(&edited, Some(&current)).is_err());
+    }
+
+    #[test]
+    fn test_diff_config_values() {
+        let current = json!({
+            "gateway": { "port": 18789, "mode": "local" },
+            "models": { "providers": { "openai": { "apiKey": "sk-old-secret-value" } } },
+            "tags": ["a", "b"],
+            "a/b": 1
+        });
+        let proposed = json!({
+            "gateway": { "port": 18790 },
+            "models": { "providers": { "openai": { "apiKey": "sk-new-secret-value" } } },
+            "tags": ["a", "c", "d"],
+            "a/b": 1,
+            "channels": { "telegram": { "enabled": true } }
+        });
+
+        let changes = diff_config_values(&current, &proposed);
+        let summary: Vec<(String, ChangeKind)> = changes.iter().map(|c| (c.pointer.clone(), c.kind)).collect();
+        assert_eq!(summary, vec![
+            ("/gateway/port".to_string(), ChangeKind::Modified),
+            ("/gateway/mode".to_string(), ChangeKind::Removed),
+            ("/models/providers/openai/apiKey".to_string(), ChangeKind::Modified),
+            ("/tags/1".to_string(), ChangeKind::Modified),
+            ("/tags/2".to_string(), ChangeKind::Added),
+            ("/channels".to_string(), ChangeKind::Added),
+        ]);
+        assert_eq!(changes[0].old_value, Some(json!(18789)));
+        assert_eq!(changes[0].new_value, Some(json!(18790)));
+        // Secrets never leave the backend in clear text
+        assert_eq!(changes[2].old_value, Some(json!("sk-o...alue")));
+        assert_eq!(changes[2].new_value, Some(json!("sk-n...alue")));
+
+        assert!(diff_config_values(&current, &current).is_empty());
+        let escaped = diff_config_values(&json!({}), &json!({ "a/b~c": 1 }));
+        assert_eq!(escaped[0].pointer, "/a~1b~0c");
+    }
+
+    #[test]
+    fn test_resolve_proxy() {
+        let env = |name: &str| match name {
+            "http_proxy" => Some("http://env-proxy:3128".to_string()),
+            "NO_PROXY" => Some("localhost,127.0.0.1".to_string()),
+            _ => None,
+        };
+        let from_env = resolve_proxy(&json!({}), env).unwrap();
+        assert_eq!(from_env.url, "http://env-proxy:3128");
+        assert_eq!(from_env.no_proxy.as_deref(), Some("localhost,127.0.0.1"));
+        assert_eq!(from_env.source, "env");
+
+        // The configured proxy wins, with its own bypass list
+        let config = json!({ "meta": { "gui": { "network": { "proxy": "http://corp:8080", "noProxy": "intranet" } } } });
+        let configured = resolve_proxy(&config, env).unwrap();
+        assert_eq!(configured.url, "http://corp:8080");
+        assert_eq!(configured.no_proxy.as_deref(), Some("intranet"));
+
+        assert_eq!(resolve_proxy(&json!({}), |_| None), None);
+    }
+
+    #[test]
+    fn test_validate_proxy_url() {
+        assert_eq!(validate_proxy_url(" http://user:pw@proxy:8080 ").unwrap(), "http://user:pw@proxy:8080");
+        assert!(validate_proxy_url("https://proxy.corp").is_ok());
+        assert!(validate_proxy_url("socks5://proxy:1080").unwrap_err().contains("socks5"));
+        assert!(validate_proxy_url("proxy:8080").is_err());
+    }
+
+    #[test]
+    fn test_mcp_source_round_trip() {
+        // Entries written before provenance existed still parse
+        let old: crate::models::MCPConfig = serde_json::from_value(json!({ "command": "node", "args": ["index.js"] })).unwrap();
+        assert_eq!(old.source, crate::models::McpSource::default());
+        assert!(!serde_json::to_value(&old).unwrap().as_object().unwrap().contains_key("sourceUrl"));
+
+        let entry = json!({
+            "transport": "stdio",
+            "command": "node",
+            "enabled": true,
+            "sourceUrl": "https://github.com/org/server",
+            "installedAt": "2026-01-02T03:04:05Z",
+            "gitRef": "abc123"
+        });
+        let parsed: crate::models::MCPConfig = serde_json::from_value(entry.clone()).unwrap();
+        assert_eq!(parsed.source.source_url.as_deref(), Some("https://github.com/org/server"));
+        assert_eq!(parsed.source.git_ref.as_deref(), Some("abc123"));
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), entry);
+    }
+
+    #[test]
+    fn test_export_env_content() {
+        let env = "# local notes\nexport OPENCLAW_TELEGRAM_USERID=\"42\"\nexport BRAVE_API_KEY=\"old\"\nexport OPENAI_API_KEY=\"sk-1\"\nexport BRAVE_API_KEY=\"brv-\\\"q\\\"\"\n";
+        let include = vec!["BRAVE_API_KEY".to_string(), "OPENCLAW_TELEGRAM_USERID".to_string()];
+
+        let with_secrets = export_env_content(env, &include, true).unwrap();
+        assert_eq!(
+            with_secrets,
+            "# Exported by OpenClaw Manager\nexport BRAVE_API_KEY=\"brv-\\\"q\\\"\"\nexport OPENCLAW_TELEGRAM_USERID=\"42\"\n"
+        );
+        assert!(!with_secrets.contains("OPENAI_API_KEY"));
+        assert!(!with_secrets.contains("local notes"));
+
+        let template = export_env_content(env, &include, false).unwrap();
+        assert!(template.contains("export BRAVE_API_KEY=\"\"\n"));
+        assert!(template.contains("export OPENCLAW_TELEGRAM_USERID=\"\"\n"));
+        assert!(!template.contains("brv-") && !template.contains("42"));
+
+        let err = export_env_content(env, &["MISSING_KEY".to_string(), "OPENAI_API_KEY".to_string()], true).unwrap_err();
+        assert!(err.contains("MISSING_KEY") && !err.contains("OPENAI_API_KEY"));
+        assert!(export_env_content(env, &[], true).is_err());
+    }
+
+    #[test]
+    fn test_plugin_name_candidates() {
+        assert_eq!(plugin_name_candidates("@m1heng-clawd/feishu"), vec!["@m1heng-clawd/feishu", "feishu"]);
+        assert_eq!(plugin_name_candidates("@scope/Tool@1.2.0"), vec!["@scope/tool", "tool"]);
+        assert_eq!(plugin_name_candidates("https://github.com/acme/mcp-weather.git"), vec!["mcp-weather"]);
+        assert_eq!(plugin_name_candidates("my-plugin@latest"), vec!["my-plugin"]);
+        assert_eq!(plugin_name_candidates("./plugins/local-tool/"), vec!["local-tool"]);
+    }
+
+    #[test]
+    fn test_interpret_plugin_install() {
+        let before = "Plugins:\n  @m1heng-clawd/feishu 0.1.2\n";
+        let after = "Plugins:\n  @m1heng-clawd/feishu 0.1.2\n  mcp-weather 1.0.0\n";
+        let url = "https://github.com/acme/mcp-weather.git";
+
+        let ok = interpret_plugin_install(url, Some(0), "Installed\n".into(), String::new(), before, after).unwrap();
+        assert_eq!(ok.plugin, "mcp-weather 1.0.0");
+        assert_eq!(ok.exit_code, Some(0));
+
+        // Exit 0 but nothing new in the list: an error that carries the CLI output
+        let err = interpret_plugin_install(url, Some(0), "npm warn skipped".into(), String::new(), before, before).unwrap_err();
+        assert!(err.contains("not listed") && err.contains("npm warn skipped"));
+
+        let err = interpret_plugin_install(url, Some(1), String::new(), "404 Not Found".into(), before, "").unwrap_err();
+        assert!(err.contains("exit code 1") && err.contains("404 Not Found"));
+
+        // Reinstalling a listed plugin still verifies by name
+        let ok = interpret_plugin_install("@m1heng-clawd/feishu", Some(0), String::new(), String::new(), before, before).unwrap();
+        assert_eq!(ok.plugin, "@m1heng-clawd/feishu 0.1.2");
+
+        // A single new line is accepted even when it is listed under another name
+        let renamed = format!("{}  weather-tools 1.0.0\n", before);
+        let ok = interpret_plugin_install(url, Some(0), String::new(), String::new(), before, &renamed).unwrap();
+        assert_eq!(ok.plugin, "weather-tools 1.0.0");
+    }
+
+    #[test]
+    fn test_parse_plugin_list() {
+        let plugins = parse_plugin_list("Plugins:\n\n  feishu@0.1.2\n  mcp-weather 1.0.0 (enabled)\n  local-tool\n");
+        assert_eq!(
+            plugins,
+            vec![
+                crate::models::PluginInfo { name: "feishu".into(), version: Some("0.1.2".into()), line: "feishu@0.1.2".into() },
+                crate::models::PluginInfo {
+                    name: "mcp-weather".into(),
+                    version: Some("1.0.0".into()),
+                    line: "mcp-weather 1.0.0 (enabled)".into(),
+                },
+                crate::models::PluginInfo { name: "local-tool".into(), version: None, line: "local-tool".into() },
+            ]
+        );
+        assert_eq!(find_plugin(&plugins, "FEISHU").map(|p| p.name.as_str()), Some("feishu"));
+        assert!(find_plugin(&plugins, "telegram").is_none());
+        assert!(parse_plugin_list("").is_empty());
+    }
+
+    #[test]
+    fn test_interpret_plugin_uninstall() {
+        assert!(interpret_plugin_uninstall("mcp-weather", Some(0), "Removed", "", "Plugins:\n  feishu@0.1.2\n").is_ok());
+
+        let err = interpret_plugin_uninstall("mcp-weather", Some(0), "ok", "", "  MCP-Weather 1.0.0\n").unwrap_err();
+        assert!(err.contains("still listed") && err.contains("ok"));
+
+        let err = interpret_plugin_uninstall("missing", Some(1), "", "Plugin not found", "").unwrap_err();
+        assert!(err.contains("exit code 1") && err.contains("Plugin not found"));
+    }
+
+    #[test]
+    fn test_parse_plugin_spec_forms() {
+        let spec = |line: &str| {
+            let (name, version) = parse_plugin_spec(line);
+            (name, version.unwrap_or_default())
+        };
+        // @scope/name@version
+        assert_eq!(spec("@m1heng-clawd/feishu@0.1.2"), ("@m1heng-clawd/feishu".into(), "0.1.2".into()));
+        // name@version
+        assert_eq!(spec("feishu@1.0.0-beta.2"), ("feishu".into(), "1.0.0-beta.2".into()));
+        // name version, with extra columns
+        assert_eq!(spec("mcp-weather  2.3.4  enabled"), ("mcp-weather".into(), "2.3.4".into()));
+        assert_eq!(spec("@m1heng-clawd/feishu 0.1.2"), ("@m1heng-clawd/feishu".into(), "0.1.2".into()));
+        assert_eq!(spec("local-tool (v1.2.0)"), ("local-tool".into(), "v1.2.0".into()));
+    }
+
+    #[test]
+    fn test_parse_plugin_spec_without_version() {
+        assert_eq!(parse_plugin_spec("@m1heng-clawd/feishu"), ("@m1heng-clawd/feishu".to_string(), None));
+        assert_eq!(parse_plugin_spec("local-tool enabled"), ("local-tool".to_string(), None));
+        assert_eq!(parse_plugin_spec("feishu@"), ("feishu".to_string(), None));
+        assert_eq!(parse_plugin_spec("   "), (String::new(), None));
+        // Digits inside a description are not a version
+        assert_eq!(parse_plugin_spec("tool 3rd-party build"), ("tool".to_string(), None));
+
+        assert_eq!(split_package_version("@scope/name"), ("@scope/name", None));
+        assert_eq!(split_package_version("name@latest"), ("name", Some("latest")));
+    }
+
+    #[test]
+    fn test_find_mcp_entry_point() {
+        let sep = if crate::utils::platform::is_windows() { "\\" } else { "/" };
+        let dist = format!("/mcps/acme__weather{}dist{}index.js", sep, sep);
+        let root = format!("/mcps/acme__weather{}index.js", sep);
+
+        let found = find_mcp_entry_point("/mcps/acme__weather", None, |p| p == dist || p == root).unwrap();
+        assert_eq!(found, dist);
+        let found = find_mcp_entry_point("/mcps/acme__weather", None, |p| p == root).unwrap();
+        assert_eq!(found, root);
+
+        let err = find_mcp_entry_point("/mcps/acme__weather", None, |_| false).unwrap_err();
+        assert!(err.contains("No entry point found in /mcps/acme__weather"));
+        assert!(err.contains("dist/index.js, index.js"));
+    }
+
+    #[test]
+    fn test_package_json_entries() {
+        assert_eq!(package_json_entries(r#"{"bin": "./build/cli.js", "main": "lib/index.js"}"#), vec!["build/cli.js", "lib/index.js"]);
+        // The command named after the (unscoped) package wins over other bins
+        assert_eq!(
+            package_json_entries(r#"{"name": "@acme/weather-mcp", "bin": {"wx": "bin/wx.js", "weather-mcp": "./bin/server.js"}}"#),
+            vec!["bin/server.js", "bin/wx.js"]
+        );
+        assert_eq!(package_json_entries(r#"{"main": "./server.mjs", "bin": {"s": "server.mjs"}}"#), vec!["server.mjs"]);
+        assert!(package_json_entries(r#"{"name": "no-entries"}"#).is_empty());
+        assert!(package_json_entries("not json").is_empty());
+    }
+
+    #[test]
+    fn test_find_mcp_entry_point_from_package_json() {
+        let sep = if crate::utils::platform::is_windows() { "\\" } else { "/" };
+        let path = |rel: &str| format!("/mcps/acme__weather{}{}", sep, rel.replace('/', sep));
+        let package = r#"{"bin": {"weather": "build/server.js"}, "main": "lib/index.js"}"#;
+
+        let exists = |p: &str| p == path("build/server.js") || p == path("dist/index.js");
+        assert_eq!(find_mcp_entry_point("/mcps/acme__weather", Some(package), exists).unwrap(), path("build/server.js"));
+
+        // A declared entry that was not built falls back to the next one, then to the guesses
+        let exists = |p: &str| p == path("lib/index.js");
+        assert_eq!(find_mcp_entry_point("/mcps/acme__weather", Some(package), exists).unwrap(), path("lib/index.js"));
+        let exists = |p: &str| p == path("dist/index.js");
+        assert_eq!(find_mcp_entry_point("/mcps/acme__weather", Some(package), exists).unwrap(), path("dist/index.js"));
+
+        // Entries outside the checkout are never used
+        let escaping = r#"{"bin": "../../usr/bin/evil", "main": "/etc/passwd"}"#;
+        assert_eq!(find_mcp_entry_point("/mcps/acme__weather", Some(escaping), |_| true).unwrap(), path("dist/index.js"));
+    }
+
+    #[test]
+    fn test_mcp_launch_command() {
+        let node = |entry: &str| ("node".to_string(), vec![entry.to_string(), "--stdio".to_string()]);
+        assert_eq!(mcp_launch_command("/m/dist/index.js", None), node("/m/dist/index.js"));
+        assert_eq!(mcp_launch_command("/m/bin/server", Some("#!/usr/bin/env node")), node("/m/bin/server"));
+        assert_eq!(mcp_launch_command("/m/bin/server", Some("'use strict';")), node("/m/bin/server"));
+        // A .js file runs under node whatever its first line says
+        assert_eq!(mcp_launch_command("/m/cli.mjs", Some("#!/usr/bin/env python3")), node("/m/cli.mjs"));
+
+        let direct = mcp_launch_command("/m/bin/server", Some("#!/usr/bin/env python3"));
+        if crate::utils::platform::is_windows() {
+            assert_eq!(direct, node("/m/bin/server"));
+        } else {
+            assert_eq!(direct, ("/m/bin/server".to_string(), vec!["--stdio".to_string()]));
+        }
+    }
+}
@@ -1,5 +1,10 @@
-use crate::models::{AITestResult, ChannelTestResult, DiagnosticResult, SystemInfo};
-use crate::utils::{log_sanitizer, platform, shell};
+use crate::commands::config::{self as config_cmd, PrimaryModelKeyStatus};
+use crate::models::{
+    AITestResult, ChannelTestResult, DiagnosticResult, MCPConfig, McpTestResult, McpTransport, StepStatus, SystemInfo,
+    ValidationError, VerificationReport, VerificationStep,
+};
+use crate::utils::{app_log, file, log_sanitizer, platform, shell};
+use std::collections::HashMap;
 use tauri::command;
 use log::{info, warn, debug};
 
@@ -732,3 +737,258 @@ read -p "Press Enter to close..."
         _ => Err(format!("Login wizard not supported for {}", channel_type)),
     }
 }
+
+// ============ Full Setup Verification ============
+
+/// Checks composed by run_full_verification (abstracted so the sequence can be tested without openclaw)
+pub(crate) trait VerificationProbes {
+    async fn config_errors(&self) -> Result<Vec<ValidationError>, String>;
+    async fn gateway_running(&self) -> Result<bool, String>;
+    async fn primary_key_status(&self) -> Result<PrimaryModelKeyStatus, String>;
+    async fn test_provider(&self) -> Result<AITestResult, String>;
+    async fn enabled_channels(&self) -> Result<Vec<String>, String>;
+    async fn test_channel(&self, channel: &str) -> Result<ChannelTestResult, String>;
+    async fn enabled_mcp_servers(&self) -> Result<Vec<(String, MCPConfig)>, String>;
+    async fn test_mcp_server(&self, name: &str, server: &MCPConfig) -> Result<McpTestResult, String>;
+}
+
+/// Probes backed by the real commands
+struct LiveProbes;
+
+impl VerificationProbes for LiveProbes {
+    async fn config_errors(&self) -> Result<Vec<ValidationError>, String> {
+        config_cmd::validate_config().await
+    }
+
+    async fn gateway_running(&self) -> Result<bool, String> {
+        crate::commands::service::get_service_status().await.map(|s| s.running)
+    }
+
+    async fn primary_key_status(&self) -> Result<PrimaryModelKeyStatus, String> {
+        config_cmd::verify_primary_model_key().await
+    }
+
+    async fn test_provider(&self) -> Result<AITestResult, String> {
+        test_ai_connection().await
+    }
+
+    async fn enabled_channels(&self) -> Result<Vec<String>, String> {
+        let config = config_cmd::load_openclaw_config()?;
+        Ok(config.get("channels")
+            .and_then(|v| v.as_object())
+            .map(|channels| channels.iter()
+                .filter(|(_, ch)| ch.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true))
+                .map(|(id, _)| id.clone())
+                .collect())
+            .unwrap_or_default())
+    }
+
+    async fn test_channel(&self, channel: &str) -> Result<ChannelTestResult, String> {
+        test_channel(channel.to_string()).await
+    }
+
+    async fn enabled_mcp_servers(&self) -> Result<Vec<(String, MCPConfig)>, String> {
+        let mut servers: Vec<(String, MCPConfig)> = config_cmd::load_mcp_config_file()?
+            .into_iter()
+            .filter(|(_, s)| s.enabled)
+            .collect();
+        servers.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(servers)
+    }
+
+    async fn test_mcp_server(&self, name: &str, server: &MCPConfig) -> Result<McpTestResult, String> {
+        let request = mcp_probe_request(name, server, &config_cmd::lookup_env_reference)?;
+        config_cmd::test_mcp_server(
+            request.server_type,
+            request.target,
+            request.command,
+            request.args,
+            None,
+            request.headers,
+            None,
+        ).await
+    }
+}
+
+/// Arguments for config::test_mcp_server that probe a configured server the way openclaw runs it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct McpProbeRequest {
+    pub server_type: String,
+    pub target: String,
+    pub command: Option<String>,
+    pub args: Option<Vec<String>>,
+    pub headers: Option<HashMap<String, String>>,
+}
+
+/// HTTP servers are probed with their (expanded) headers; stdio servers are targeted by name
+/// so the probe launches them with their own env map
+pub(crate) fn mcp_probe_request(
+    name: &str,
+    server: &MCPConfig,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<McpProbeRequest, String> {
+    Ok(match &server.transport {
+        McpTransport::Stdio { command, args, .. } => McpProbeRequest {
+            server_type: "stdio".to_string(),
+            target: name.to_string(),
+            command: Some(command.clone()),
+            args: Some(args.clone()),
+            headers: None,
+        },
+        McpTransport::Http { .. } => match config_cmd::expand_mcp_config(name, server, lookup)?.transport {
+            McpTransport::Http { url, headers } => McpProbeRequest {
+                server_type: "url".to_string(),
+                target: url,
+                command: None,
+                args: None,
+                headers: Some(headers),
+            },
+            McpTransport::Stdio { .. } => unreachable!("expanding keeps the transport"),
+        },
+    })
+}
+
+fn step(name: &str, status: StepStatus, message: impl Into<String>) -> VerificationStep {
+    VerificationStep { name: name.to_string(), status, message: message.into() }
+}
+
+/// Run every verification step in order and build the report
+pub(crate) async fn verify_setup(probes: &impl VerificationProbes) -> VerificationReport {
+    let mut steps = Vec::new();
+
+    // 1. Config is valid
+    steps.push(match probes.config_errors().await {
+        Ok(errors) if errors.is_empty() => step("Configuration", StepStatus::Pass, "openclaw.json matches the config schema"),
+        Ok(errors) => step("Configuration", StepStatus::Fail, format!(
+            "{} schema violation(s), first at {}: {}", errors.len(), errors[0].path, errors[0].message
+        )),
+        Err(e) => step("Configuration", StepStatus::Fail, e),
+    });
+
+    // 2. Gateway is reachable
+    steps.push(match probes.gateway_running().await {
+        Ok(true) => step("Gateway", StepStatus::Pass, "Gateway is running"),
+        Ok(false) => step("Gateway", StepStatus::Fail, "Gateway is not running. Start the service and try again"),
+        Err(e) => step("Gateway", StepStatus::Fail, e),
+    });
+
+    // 3. Primary model's provider key is present, then works against the live API
+    let key_ok = match probes.primary_key_status().await {
+        Ok(status) if status.populated => {
+            steps.push(step("Primary model key", StepStatus::Pass, status.message));
+            true
+        }
+        Ok(status) => {
+            steps.push(step("Primary model key", StepStatus::Fail, status.message));
+            false
+        }
+        Err(e) => {
+            steps.push(step("Primary model key", StepStatus::Fail, e));
+            false
+        }
+    };
+    steps.push(if !key_ok {
+        step("Provider live test", StepStatus::Skip, "Skipped: the primary model has no usable API key")
+    } else {
+        match probes.test_provider().await {
+            Ok(r) if r.success => step("Provider live test", StepStatus::Pass, format!(
+                "Model replied in {}ms", r.latency_ms.unwrap_or(0)
+            )),
+            Ok(r) => step("Provider live test", StepStatus::Fail, r.error.unwrap_or_else(|| "Provider test failed".to_string())),
+            Err(e) => step("Provider live test", StepStatus::Fail, e),
+        }
+    });
+
+    // 4. At least one channel verified
+    steps.push(match probes.enabled_channels().await {
+        Ok(channels) if channels.is_empty() => step("Channels", StepStatus::Skip, "No channels are enabled"),
+        Ok(channels) => {
+            let mut failures = Vec::new();
+            let mut verified = None;
+            for channel in &channels {
+                match probes.test_channel(channel).await {
+                    Ok(r) if r.success => {
+                        verified = Some(r.message);
+                        break;
+                    }
+                    Ok(r) => failures.push(format!("{}: {}", channel, r.error.unwrap_or(r.message))),
+                    Err(e) => failures.push(format!("{}: {}", channel, e)),
+                }
+            }
+            match verified {
+                Some(message) => step("Channels", StepStatus::Pass, message),
+                None => step("Channels", StepStatus::Fail, failures.join("; ")),
+            }
+        }
+        Err(e) => step("Channels", StepStatus::Fail, e),
+    });
+
+    // 5. Enabled MCP servers are reachable and answer the MCP initialize handshake
+    match probes.enabled_mcp_servers().await {
+        Ok(servers) if servers.is_empty() => steps.push(step("MCP servers", StepStatus::Skip, "No MCP servers are enabled")),
+        Ok(servers) => {
+            for (name, server) in &servers {
+                let label = format!("MCP: {}", name);
+                steps.push(match probes.test_mcp_server(name, server).await {
+                    Ok(result) if result.handshake => step(&label, StepStatus::Pass, result.message),
+                    // Reachable or running, but not speaking MCP: openclaw won't get any tools from it
+                    Ok(result) => step(&label, StepStatus::Fail, result.message),
+                    Err(e) => step(&label, StepStatus::Fail, e),
+                });
+            }
+        }
+        Err(e) => steps.push(step("MCP servers", StepStatus::Fail, e)),
+    }
+
+    let overall = if steps.iter().any(|s| s.status == StepStatus::Fail) {
+        StepStatus::Fail
+    } else if steps.iter().any(|s| s.status == StepStatus::Pass) {
+        StepStatus::Pass
+    } else {
+        StepStatus::Skip
+    };
+
+    VerificationReport { steps, overall }
+}
+
+/// Verify the whole setup in one go: config, gateway, provider key, channels and MCP servers
+#[command]
+pub async fn run_full_verification() -> Result<VerificationReport, String> {
+    info!("[Verification] Running full setup verification...");
+    let report = verify_setup(&LiveProbes).await;
+    for s in &report.steps {
+        info!("[Verification] {:?} {} - {}", s.status, s.name, log_sanitizer::sanitize(&s.message));
+    }
+    info!("[Verification] Overall: {:?}", report.overall);
+    Ok(report)
+}
+
+/// Most lines get_recent_logs returns in one call
+const MAX_RECENT_LOG_LINES: usize = 5000;
+
+/// Change the Manager's log verbosity at runtime (resets to info on restart)
+#[command]
+pub async fn set_log_level(level: String) -> Result<String, String> {
+    let filter = app_log::parse_level(&level)?;
+    log::set_max_level(filter);
+    // Logged at warn so the change is recorded even when lowering the level
+    warn!("[Logs] Manager log level set to {}", filter);
+    Ok(format!("Log level set to {}", filter.as_str().to_lowercase()))
+}
+
+/// Current Manager log level
+#[command]
+pub async fn get_log_level() -> Result<String, String> {
+    Ok(log::max_level().as_str().to_lowercase())
+}
+
+/// Last `lines` lines of the Manager log file (manager/manager.log)
+#[command]
+pub async fn get_recent_logs(lines: usize) -> Result<Vec<String>, String> {
+    let path = platform::get_manager_log_file_path();
+    if !file::file_exists(&path) {
+        return Ok(Vec::new());
+    }
+    let content = file::read_file(&path).map_err(|e| format!("Failed to read log file {}: {}", path, e))?;
+    Ok(app_log::tail_lines(&content, lines.min(MAX_RECENT_LOG_LINES)))
+}
@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests {
+    use super::super::config::PrimaryModelKeyStatus;
+    use super::super::diagnostics::*;
+    use crate::models::{
+        AITestResult, ChannelTestResult, MCPConfig, McpTestResult, McpTransport, StepStatus, ValidationError,
+    };
+    use std::collections::HashMap;
+
+    /// Mock environment; every check passes unless overridden
+    struct MockProbes {
+        provider_ok: bool,
+        mcp_handshake: bool,
+        /// Also configure an HTTP server that answers only with its Authorization header
+        remote_mcp: bool,
+    }
+
+    /// HTTP MCP server whose token comes from ${REMOTE_TOKEN}
+    fn remote_mcp_server() -> MCPConfig {
+        MCPConfig {
+            transport: McpTransport::Http {
+                url: "https://mcp.example.com/mcp".to_string(),
+                headers: HashMap::from([("Authorization".to_string(), "Bearer ${REMOTE_TOKEN}".to_string())]),
+            },
+            ..MCPConfig::stdio("", Vec::new())
+        }
+    }
+
+    fn lookup_remote_token(var: &str) -> Option<String> {
+        (var == "REMOTE_TOKEN").then(|| "t0k".to_string())
+    }
+
+    impl VerificationProbes for MockProbes {
+        async fn config_errors(&self) -> Result<Vec<ValidationError>, String> {
+            Ok(Vec::new())
+        }
+
+        async fn gateway_running(&self) -> Result<bool, String> {
+            Ok(true)
+        }
+
+        async fn primary_key_status(&self) -> Result<PrimaryModelKeyStatus, String> {
+            Ok(PrimaryModelKeyStatus {
+                primary_model: Some("anthropic/claude-sonnet-4".to_string()),
+                provider: Some("anthropic".to_string()),
+                source: "inline".to_string(),
+                env_var: None,
+                populated: true,
+                message: "API key is set".to_string(),
+            })
+        }
+
+        async fn test_provider(&self) -> Result<AITestResult, String> {
+            Ok(AITestResult {
+                success: self.provider_ok,
+                provider: "current".to_string(),
+                model: "default".to_string(),
+                response: None,
+                error: if self.provider_ok { None } else { Some("401 invalid x-api-key".to_string()) },
+                latency_ms: Some(120),
+            })
+        }
+
+        async fn enabled_channels(&self) -> Result<Vec<String>, String> {
+            Ok(vec!["telegram".to_string()])
+        }
+
+        async fn test_channel(&self, channel: &str) -> Result<ChannelTestResult, String> {
+            Ok(ChannelTestResult {
+                success: true,
+                channel: channel.to_string(),
+                message: format!("{} status OK", channel),
+                error: None,
+            })
+        }
+
+        async fn enabled_mcp_servers(&self) -> Result<Vec<(String, MCPConfig)>, String> {
+            let mut servers = vec![("github".to_string(), MCPConfig::stdio(
+                "npx",
+                vec!["-y".to_string(), "@modelcontextprotocol/server-github".to_string()],
+            ))];
+            if self.remote_mcp {
+                servers.push(("remote".to_string(), remote_mcp_server()));
+            }
+            Ok(servers)
+        }
+
+        async fn test_mcp_server(&self, name: &str, server: &MCPConfig) -> Result<McpTestResult, String> {
+            let request = mcp_probe_request(name, server, &lookup_remote_token)?;
+            if request.server_type == "url" {
+                let authorized = request.headers.as_ref()
+                    .and_then(|h| h.get("Authorization"))
+                    .is_some_and(|v| v == "Bearer t0k");
+                if !authorized {
+                    return Err("❌ Server returned HTTP 401".to_string());
+                }
+            }
+            Ok(McpTestResult {
+                handshake: self.mcp_handshake,
+                message: if self.mcp_handshake {
+                    "✅ MCP handshake succeeded".to_string()
+                } else {
+                    "⚠️ Process is running but did not respond to initialize".to_string()
+                },
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_verification_all_green() {
+        let report = verify_setup(&MockProbes { provider_ok: true, mcp_handshake: true, remote_mcp: false }).await;
+        assert_eq!(report.overall, StepStatus::Pass);
+        let names: Vec<&str> = report.steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec![
+            "Configuration", "Gateway", "Primary model key", "Provider live test", "Channels", "MCP: github",
+        ]);
+        assert!(report.steps.iter().all(|s| s.status == StepStatus::Pass));
+    }
+
+    #[tokio::test]
+    async fn test_full_verification_failing_provider_key() {
+        let report = verify_setup(&MockProbes { provider_ok: false, mcp_handshake: true, remote_mcp: false }).await;
+        assert_eq!(report.overall, StepStatus::Fail);
+        let provider = report.steps.iter().find(|s| s.name == "Provider live test").unwrap();
+        assert_eq!(provider.status, StepStatus::Fail);
+        assert!(provider.message.contains("401"));
+        // Later steps still run
+        assert_eq!(report.steps.last().unwrap().status, StepStatus::Pass);
+    }
+
+    #[tokio::test]
+    async fn test_full_verification_mcp_without_handshake_fails() {
+        let report = verify_setup(&MockProbes { provider_ok: true, mcp_handshake: false, remote_mcp: false }).await;
+        assert_eq!(report.overall, StepStatus::Fail);
+        let mcp = report.steps.iter().find(|s| s.name == "MCP: github").unwrap();
+        assert_eq!(mcp.status, StepStatus::Fail);
+        assert!(mcp.message.contains("initialize"));
+    }
+
+    #[tokio::test]
+    async fn test_full_verification_sends_configured_mcp_headers() {
+        let report = verify_setup(&MockProbes { provider_ok: true, mcp_handshake: true, remote_mcp: true }).await;
+        let remote = report.steps.iter().find(|s| s.name == "MCP: remote").unwrap();
+        assert_eq!(remote.status, StepStatus::Pass, "{}", remote.message);
+        assert_eq!(report.overall, StepStatus::Pass);
+    }
+
+    #[test]
+    fn test_mcp_probe_request() {
+        let remote = mcp_probe_request("remote", &remote_mcp_server(), &lookup_remote_token).unwrap();
+        assert_eq!(remote.target, "https://mcp.example.com/mcp");
+        assert_eq!(remote.headers.unwrap()["Authorization"], "Bearer t0k");
+
+        // Stdio servers are targeted by name so the probe picks up their env map
+        let github = mcp_probe_request("github", &MCPConfig::stdio("npx", vec!["-y".to_string()]), &|_| None).unwrap();
+        assert_eq!(github.server_type, "stdio");
+        assert_eq!(github.target, "github");
+        assert_eq!(github.command.as_deref(), Some("npx"));
+    }
+}
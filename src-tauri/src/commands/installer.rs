@@ -1312,7 +1312,7 @@ fn get_latest_openclaw_version() -> Option<String> {
 /// Compare version numbers, return whether an update is available
 /// current: Current version (e.g. "1.0.0" or "v1.0.0")
 /// latest: Latest version (e.g. "1.0.1")
-fn compare_versions(current: &str, latest: &str) -> bool {
+pub(crate) fn compare_versions(current: &str, latest: &str) -> bool {
     // Remove possible 'v' prefix and whitespace
     let current = current.trim().trim_start_matches('v');
     let latest = latest.trim().trim_start_matches('v');
@@ -4,3 +4,8 @@ pub mod installer;
 pub mod process;
 pub mod service;
 pub mod skills;
+
+#[cfg(test)]
+mod config_tests;
+#[cfg(test)]
+mod diagnostics_tests;
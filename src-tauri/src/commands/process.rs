@@ -12,6 +12,14 @@ pub async fn check_openclaw_installed() -> Result<bool, String> {
     Ok(installed)
 }
 
+/// Preflight check: whether the openclaw CLI is available to run commands
+#[command]
+pub async fn openclaw_available() -> Result<bool, String> {
+    let available = shell::openclaw_available();
+    debug!("[Process Check] OpenClaw CLI available: {}", available);
+    Ok(available)
+}
+
 /// Get OpenClaw version
 #[command]
 pub async fn get_openclaw_version() -> Result<Option<String>, String> {
@@ -1,4 +1,5 @@
 use crate::models::ServiceStatus;
+use crate::commands::config;
 use crate::utils::shell;
 use tauri::command;
 use std::process::Command;
@@ -149,6 +150,11 @@ pub async fn start_service() -> Result<String, String> {
     }
     info!("[Service] openclaw path: {:?}", openclaw_path);
 
+    // The gateway reads its token from openclaw.json, so make sure one is stored first
+    if let Err(e) = config::ensure_gateway_auth().await {
+        warn!("[Service] Failed to prepare gateway auth: {}", e);
+    }
+
     // Start gateway in background directly (do not wait for doctor, avoid blocking)
     info!("[Service] Starting gateway in background...");
     shell::spawn_openclaw_gateway()
@@ -305,6 +311,9 @@ pub async fn restart_service() -> Result<String, String> {
     }
 
     // Step 2: Start the service
+    if let Err(e) = config::ensure_gateway_auth().await {
+        warn!("[Service] Failed to prepare gateway auth: {}", e);
+    }
     info!("[Service] Starting gateway in background...");
     shell::spawn_openclaw_gateway()
         .map_err(|e| format!("Failed to start service: {}", e))?;
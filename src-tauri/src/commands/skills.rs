@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 use tauri::command;
 use log::{info, error, debug};
+use crate::utils::platform;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Skill {
@@ -24,8 +26,7 @@ struct SkillFrontmatter {
 pub async fn get_skills() -> Result<Vec<Skill>, String> {
     info!("Executing get_skills command");
     let mut skills = Vec::new();
-    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-    let skills_dir = home_dir.join(".openclaw").join("skills");
+    let skills_dir = Path::new(&platform::get_config_dir()).join("skills");
     info!("Using skills directory: {:?}", skills_dir);
 
     if !skills_dir.exists() {
@@ -165,8 +166,7 @@ pub async fn install_clawhub() -> Result<String, String> {
 pub async fn install_skill(skill_name: String) -> Result<String, String> {
     info!("Installing skill: {}", skill_name);
     
-    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-    let openclaw_dir = home_dir.join(".openclaw");
+    let openclaw_dir = PathBuf::from(platform::get_config_dir());
     
     // Ensure .openclaw directory exists
     if !openclaw_dir.exists() {
@@ -206,8 +206,7 @@ pub async fn install_skill(skill_name: String) -> Result<String, String> {
 pub async fn uninstall_skill(skill_id: String) -> Result<String, String> {
     info!("Uninstalling skill: {}", skill_id);
     
-    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-    let skill_path = home_dir.join(".openclaw").join("skills").join(&skill_id);
+    let skill_path = Path::new(&platform::get_config_dir()).join("skills").join(&skill_id);
     
     if !skill_path.exists() {
         return Err(format!("Skill directory not found: {:?}", skill_path));
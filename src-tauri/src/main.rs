@@ -9,26 +9,53 @@ mod models;
 mod utils;
 
 use commands::{config, diagnostics, installer, process, service, skills};
-use utils::log_sanitizer;
+use utils::{app_log, log_sanitizer};
 use std::io::Write;
 
 fn main() {
-    // Initialize logging - show info level logs by default
+    // Initialize logging - show info level logs by default. The Manager's own records pass the
+    // env_logger filter at every level so set_log_level can raise the global cap at runtime.
+    let explicit_filter = std::env::var("RUST_LOG").is_ok_and(|v| !v.trim().is_empty());
     env_logger::Builder::from_env(
-        env_logger::Env::default().default_filter_or("info")
+        env_logger::Env::default().default_filter_or("info,openclaw_manager=trace")
     )
     .format(|buf, record| {
         let sanitized = log_sanitizer::sanitize(&record.args().to_string());
-        writeln!(buf, "{} [{}] {}", record.level(), record.target(), sanitized)
+        writeln!(
+            buf,
+            "{} {} [{}] {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            record.level(),
+            record.target(),
+            sanitized
+        )
     })
+    .target(env_logger::Target::Pipe(Box::new(app_log::TeeWriter::open(
+        &utils::platform::get_manager_log_file_path(),
+    ))))
     .init();
+    if !explicit_filter {
+        log::set_max_level(log::LevelFilter::Info);
+    }
     
     log::info!("🦞 OpenClaw Manager started");
+    if std::env::var(utils::platform::CONFIG_DIR_ENV).is_ok_and(|d| !d.trim().is_empty()) {
+        log::info!(
+            "Using config directory from {}: {}",
+            utils::platform::CONFIG_DIR_ENV,
+            utils::platform::get_config_dir()
+        );
+    }
 
     tauri::Builder::default()
         .setup(|app| {
             #[cfg(desktop)]
             app.handle().plugin(tauri_plugin_updater::Builder::new().build())?;
+            tauri::async_runtime::spawn(async {
+                if let Err(e) = config::migrate_stored_config().await {
+                    log::warn!("[Config] Failed to migrate configuration: {}", e);
+                }
+            });
             Ok(())
         })
         .plugin(tauri_plugin_shell::init())
@@ -46,50 +73,97 @@ fn main() {
             service::kill_all_port_processes,
             // Process management
             process::check_openclaw_installed,
+            process::openclaw_available,
             process::get_openclaw_version,
             process::check_secure_version,
             process::check_port_in_use,
             // Configuration management
             config::get_config,
+            config::get_config_readonly,
+            config::config_has_comments,
             config::save_config,
+            config::save_config_validated,
             config::get_env_value,
             config::save_env_value,
+            config::list_env_keys,
+            config::delete_env_value,
+            config::export_env_file,
             config::get_ai_providers,
             config::get_channels_config,
+            config::get_channel_secret,
+            config::validate_channels,
+            config::repair_channels,
             config::save_channel_config,
             config::clear_channel_config,
+            config::preview_clear_channel_config,
             // Gateway Token
             config::get_or_create_gateway_token,
+            config::set_gateway_auth,
             config::get_dashboard_url,
+            config::get_lan_dashboard_url,
             config::repair_device_token,
             // AI configuration management
             config::get_official_providers,
+            config::reload_official_providers,
+            config::get_suggested_model_status,
+            config::get_supported_api_types,
             config::get_ai_config,
             config::save_provider,
+            config::get_provider_model_counts,
             config::delete_provider,
+            config::preview_delete_provider,
+            config::reorder_provider_model,
+            config::diff_against_official,
+            config::get_provider_usage,
+            config::verify_primary_model_key,
+            config::check_all_providers,
+            config::fetch_provider_models,
+            config::clear_model_catalog_cache,
             config::set_primary_model,
+            config::set_model_chain,
             config::add_available_model,
+            config::reconcile_available_models,
+            config::prune_config,
+            config::preview_prune_config,
             config::remove_available_model,
-            // Feishu plugin management
+            config::set_model_available,
+            // Plugin management
+            config::list_plugins,
+            config::install_plugin,
+            config::uninstall_plugin,
             config::check_feishu_plugin,
             config::install_feishu_plugin,
             // MCP management
             config::get_mcp_config,
             config::save_mcp_config,
+            config::set_all_mcp_enabled,
             config::install_mcp_from_git,
+            config::cancel_install,
+            config::register_existing_mcp,
             config::uninstall_mcp,
+            config::preview_uninstall_mcp,
             config::check_mcporter_installed,
+            config::get_mcporter_version,
             config::install_mcporter,
             config::uninstall_mcporter,
             config::install_mcp_plugin,
             config::openclaw_config_set,
             config::test_mcp_server,
+            config::list_mcp_tools,
+            config::check_test_dependencies,
+            config::check_prerequisites,
+            config::get_mcp_test_defaults,
+            config::set_mcp_test_defaults,
             // Diagnostic tests
             diagnostics::run_doctor,
+            diagnostics::run_full_verification,
             diagnostics::test_ai_connection,
             diagnostics::test_channel,
             diagnostics::get_system_info,
             diagnostics::start_channel_login,
+            diagnostics::set_log_level,
+            diagnostics::get_log_level,
+            diagnostics::get_recent_logs,
             // Installer
             installer::check_environment,
             installer::install_nodejs,
@@ -111,10 +185,15 @@ fn main() {
             skills::uninstall_clawhub,
             // Multi-Agent Routing
             config::get_openclaw_home_dir,
+            config::get_config_paths,
+            config::set_active_config_scope,
+            config::get_active_config_scope,
             config::get_agents_config,
             config::save_agent,
             config::save_subagent_defaults,
             config::delete_agent,
+            config::record_agent_last_used_model,
+            config::clone_agent,
             config::save_agent_binding,
             config::delete_agent_binding,
             config::get_agent_system_prompt,
@@ -132,20 +211,36 @@ fn main() {
             // Workspace & Personality
             config::get_workspace_config,
             config::save_workspace_config,
+            config::list_personality_files,
             config::get_personality_file,
             config::save_personality_file,
             // Browser Control
             config::get_browser_config,
             config::save_browser_config,
+            // Network Proxy
+            config::get_proxy_config,
+            config::save_proxy_config,
             // Web Search
             config::get_web_config,
             config::save_web_config,
             // Gateway Configuration
             config::get_gateway_config,
+            config::get_gateway_status,
             config::save_gateway_config,
             // Configuration Management
+            config::validate_config,
             config::export_config,
+            config::ensure_meta_structure,
+            config::initialize_config,
+            config::apply_config_patch,
+            config::preview_config_patch,
+            config::diff_config,
+            config::get_secret_storage_mode,
+            config::set_secret_storage_mode,
+            config::generate_minimal_config,
             config::import_config,
+            config::export_config_bundle,
+            config::import_config_bundle,
         ])
         .run(tauri::generate_context!())
         .expect("Error occurred while running Tauri application");
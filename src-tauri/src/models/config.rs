@@ -1,94 +1,148 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// OpenClaw complete configuration - corresponds to openclaw.json structure
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Keys of a config object that have no typed field
+pub type ExtraFields = serde_json::Map<String, serde_json::Value>;
+
+/// OpenClaw complete configuration - corresponds to openclaw.json structure.
+/// Only the commonly edited parts are typed. Every other key is kept in `extra` and
+/// absent sections stay absent, so a load/save round trip doesn't lose or add anything.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct OpenClawConfig {
     /// Agent configuration
-    #[serde(default)]
-    pub agents: AgentsConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agents: Option<AgentsConfig>,
     /// Model configuration
-    #[serde(default)]
-    pub models: ModelsConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub models: Option<ModelsConfig>,
     /// Gateway configuration
-    #[serde(default)]
-    pub gateway: GatewayConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<GatewayConfig>,
     /// Channel configuration
-    #[serde(default)]
-    pub channels: HashMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels: Option<HashMap<String, serde_json::Value>>,
     /// Plugin configuration
-    #[serde(default)]
-    pub plugins: PluginsConfig,
-    /// MCP configuration
-    #[serde(default)]
-    pub mcp: HashMap<String, MCPConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugins: Option<PluginsConfig>,
     /// Metadata
-    #[serde(default)]
-    pub meta: MetaConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<MetaConfig>,
+    /// Everything else
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Agent configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct AgentsConfig {
     /// Default configuration
-    #[serde(default)]
-    pub defaults: AgentDefaults,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defaults: Option<AgentDefaults>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Agent default configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct AgentDefaults {
     /// Model configuration
-    #[serde(default)]
-    pub model: AgentModelConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<AgentModelConfig>,
     /// Available model list (provider/model -> {})
-    #[serde(default)]
-    pub models: HashMap<String, serde_json::Value>,
-    /// Compression configuration
-    #[serde(default)]
-    pub compaction: Option<serde_json::Value>,
-    /// Context pruning
-    #[serde(rename = "contextPruning", default)]
-    pub context_pruning: Option<serde_json::Value>,
-    /// Heartbeat configuration
-    #[serde(default)]
-    pub heartbeat: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub models: Option<HashMap<String, serde_json::Value>>,
     /// Maximum concurrency
-    #[serde(rename = "maxConcurrent", default)]
+    #[serde(rename = "maxConcurrent", default, skip_serializing_if = "Option::is_none")]
     pub max_concurrent: Option<u32>,
-    /// Sub-agent configuration
-    #[serde(default)]
-    pub subagents: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
-/// Agent model configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Agent model configuration. openclaw also accepts a plain "provider/model-id" string;
+/// that form is read as { primary } and written back as a string while it has nothing else.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(from = "AgentModelValue", into = "AgentModelValue")]
 pub struct AgentModelConfig {
     /// Primary model (format: provider/model-id)
-    #[serde(default)]
     pub primary: Option<String>,
+    /// Models tried in order when the primary fails
+    pub fallbacks: Option<Vec<String>>,
+    pub extra: ExtraFields,
+    /// Stored as a plain string
+    pub shorthand: bool,
+}
+
+/// On-disk shape of agents.*.model
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum AgentModelValue {
+    Primary(String),
+    Object {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        primary: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fallbacks: Option<Vec<String>>,
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+}
+
+impl From<AgentModelValue> for AgentModelConfig {
+    fn from(value: AgentModelValue) -> Self {
+        match value {
+            AgentModelValue::Primary(primary) => AgentModelConfig {
+                primary: Some(primary),
+                shorthand: true,
+                ..Default::default()
+            },
+            AgentModelValue::Object { primary, fallbacks, extra } => AgentModelConfig {
+                primary,
+                fallbacks,
+                extra,
+                shorthand: false,
+            },
+        }
+    }
+}
+
+impl From<AgentModelConfig> for AgentModelValue {
+    fn from(config: AgentModelConfig) -> Self {
+        match config {
+            AgentModelConfig { primary: Some(primary), fallbacks: None, ref extra, shorthand: true } if extra.is_empty() => {
+                AgentModelValue::Primary(primary)
+            }
+            AgentModelConfig { primary, fallbacks, extra, .. } => AgentModelValue::Object { primary, fallbacks, extra },
+        }
+    }
 }
 
 /// Model configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct ModelsConfig {
     /// Provider configuration mapping
-    #[serde(default)]
-    pub providers: HashMap<String, ProviderConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub providers: Option<HashMap<String, ProviderConfig>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Provider configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct ProviderConfig {
     /// API URL
-    #[serde(rename = "baseUrl")]
-    pub base_url: String,
+    #[serde(rename = "baseUrl", default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
     /// API Key
-    #[serde(rename = "apiKey")]
+    #[serde(rename = "apiKey", default, skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
-    /// Model list
-    #[serde(default)]
-    pub models: Vec<ModelConfig>,
+    /// API type (anthropic-messages / openai-completions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api: Option<String>,
+    /// Model list (kept as stored; cost numbers must not be rewritten as floats)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub models: Option<Vec<serde_json::Value>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Model configuration details
@@ -132,67 +186,206 @@ pub struct ModelCostConfig {
 }
 
 /// Gateway configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct GatewayConfig {
     /// Mode: local or cloud
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mode: Option<String>,
+    /// Listening port
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
     /// Authentication configuration
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auth: Option<GatewayAuthConfig>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Gateway authentication configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct GatewayAuthConfig {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mode: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub token: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Plugin configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct PluginsConfig {
-    #[serde(default)]
-    pub allow: Vec<String>,
-    #[serde(default)]
-    pub entries: HashMap<String, serde_json::Value>,
-    #[serde(default)]
-    pub installs: HashMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entries: Option<HashMap<String, serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installs: Option<HashMap<String, serde_json::Value>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// How an MCP server is reached, tagged as "transport": "stdio" | "http"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "transport", rename_all = "lowercase")]
+pub enum McpTransport {
+    /// Local process speaking MCP over stdin/stdout
+    Stdio {
+        /// Command to run
+        command: String,
+        /// Arguments
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+        /// Environment variables
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        env: HashMap<String, String>,
+    },
+    /// Remote server reached over HTTP
+    Http {
+        /// Server URL
+        url: String,
+        /// Extra request headers
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        headers: HashMap<String, String>,
+    },
+}
+
+/// Where an MCP server was installed from (set by install_mcp_from_git)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSource {
+    /// Git URL the server was cloned from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+    /// When it was installed or last updated (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_at: Option<String>,
+    /// Commit that was checked out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
 }
 
-/// MCP configuration (supports both stdio and HTTP modes)
+/// MCP configuration (stdio or HTTP transport)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawMcpConfig")]
 pub struct MCPConfig {
-    /// Command to run (for stdio servers)
-    #[serde(default, skip_serializing_if = "String::is_empty")]
-    pub command: String,
-    /// Arguments (for stdio servers)
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub args: Vec<String>,
-    /// Environment variables
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub env: HashMap<String, String>,
-    /// URL (for HTTP/remote MCP servers)
-    #[serde(default, skip_serializing_if = "String::is_empty")]
-    pub url: String,
+    /// Transport and its settings
+    #[serde(flatten)]
+    pub transport: McpTransport,
     /// Whether enabled
-    #[serde(default = "default_mcp_enabled")]
     pub enabled: bool,
+    /// Install provenance (sourceUrl / installedAt / gitRef)
+    #[serde(flatten)]
+    pub source: McpSource,
+}
+
+impl MCPConfig {
+    /// Enabled stdio server
+    pub fn stdio(command: impl Into<String>, args: Vec<String>) -> Self {
+        MCPConfig {
+            transport: McpTransport::Stdio { command: command.into(), args, env: HashMap::new() },
+            enabled: true,
+            source: McpSource::default(),
+        }
+    }
+}
+
+/// On-disk MCP entry as written by older versions (flat, no "transport" tag) or the tagged form
+#[derive(Deserialize)]
+struct RawMcpConfig {
+    #[serde(default)]
+    transport: Option<String>,
+    #[serde(default)]
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default = "default_mcp_enabled")]
+    enabled: bool,
+    #[serde(flatten)]
+    source: McpSource,
 }
 
 fn default_mcp_enabled() -> bool {
     true
 }
 
+impl TryFrom<RawMcpConfig> for MCPConfig {
+    type Error = String;
+
+    fn try_from(raw: RawMcpConfig) -> Result<Self, Self::Error> {
+        // Untagged entries are stdio unless they only have a URL (the old implicit rule)
+        let is_http = match raw.transport.as_deref() {
+            Some("stdio") => false,
+            Some("http") => true,
+            Some(other) => return Err(format!("unknown MCP transport '{}' (expected stdio or http)", other)),
+            None => !raw.url.is_empty(),
+        };
+        let transport = if is_http {
+            McpTransport::Http { url: raw.url, headers: raw.headers }
+        } else {
+            McpTransport::Stdio { command: raw.command, args: raw.args, env: raw.env }
+        };
+        Ok(MCPConfig { transport, enabled: raw.enabled, source: raw.source })
+    }
+}
+
+/// Tool exposed by an MCP server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpTool {
+    /// Tool name
+    pub name: String,
+    /// Description provided by the server
+    pub description: Option<String>,
+}
+
+/// Tools listed by an MCP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpToolList {
+    /// Server name (key in mcps.json)
+    pub server: String,
+    /// Tools in the order the server returned them
+    pub tools: Vec<McpTool>,
+    /// Why the list is empty (e.g. the server has no tools capability)
+    pub note: Option<String>,
+}
+
+/// MCP server test result (parsed from the initialize response)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct McpTestResult {
+    /// Whether the server answered the initialize request
+    pub handshake: bool,
+    /// Human-readable summary
+    pub message: String,
+    /// Protocol version advertised by the server
+    pub protocol_version: Option<String>,
+    /// Server name (serverInfo.name)
+    pub server_name: Option<String>,
+    /// Server version (serverInfo.version)
+    pub server_version: Option<String>,
+    /// Advertised capabilities (tools / resources / prompts / ...)
+    pub capabilities: Vec<String>,
+    /// HTTP status code (URL servers only)
+    pub http_status: Option<u16>,
+}
+
 /// Metadata configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct MetaConfig {
-    #[serde(rename = "lastTouchedAt", default)]
+    #[serde(rename = "lastTouchedAt", default, skip_serializing_if = "Option::is_none")]
     pub last_touched_at: Option<String>,
-    #[serde(rename = "lastTouchedVersion", default)]
+    #[serde(rename = "lastTouchedVersion", default, skip_serializing_if = "Option::is_none")]
     pub last_touched_version: Option<String>,
+    /// Config schema version (see migrate_config)
+    #[serde(rename = "schemaVersion", default, skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u64>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 // ============ Data structures for frontend display ============
@@ -235,6 +428,105 @@ pub struct SuggestedModel {
     pub recommended: bool,
 }
 
+/// Whether a preset's suggested model is already configured
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SuggestedModelStatus {
+    /// Official provider preset ID
+    pub provider_id: String,
+    /// Suggested model ID
+    pub model_id: String,
+    /// Whether any matching configured provider already has this model
+    pub configured: bool,
+    /// Configured providers that have it
+    pub configured_in: Vec<String>,
+}
+
+/// Supported API type and its capabilities (for frontend display)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeInfo {
+    /// API type ID (value stored in a model's "api" field)
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// How the API key is sent (bearer / header / query)
+    pub auth_style: String,
+    /// Header or query parameter carrying the key (for header/query auth)
+    pub auth_param: Option<String>,
+    /// Endpoint (relative to the base URL) that lists available models
+    pub list_models_endpoint: Option<String>,
+    /// Whether the Manager can fetch the model list for this API type
+    pub supports_fetch: bool,
+    /// Whether the Manager can run a connection test for this API type
+    pub supports_test: bool,
+}
+
+/// Result of `openclaw config set` (the CLI's real output)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfigSetResult {
+    /// Config key that was set
+    pub key: String,
+    /// Value that was set
+    pub value: String,
+    /// CLI stdout (may contain warnings)
+    pub stdout: String,
+    /// CLI stderr
+    pub stderr: String,
+    /// CLI exit code
+    pub exit_code: Option<i32>,
+}
+
+/// An installed openclaw plugin, parsed from `openclaw plugins list`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PluginInfo {
+    /// Plugin or package name
+    pub name: String,
+    /// Installed version, when the list shows one
+    pub version: Option<String>,
+    /// The raw list line
+    pub line: String,
+}
+
+/// Result of `openclaw plugins install`, verified against `openclaw plugins list`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PluginInstallResult {
+    /// Package name, git URL or path that was installed
+    pub source: String,
+    /// Line of `openclaw plugins list` showing the installed plugin
+    pub plugin: String,
+    /// CLI stdout
+    pub stdout: String,
+    /// CLI stderr
+    pub stderr: String,
+    /// CLI exit code
+    pub exit_code: Option<i32>,
+}
+
+/// A resolved file or directory path and whether it exists
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResolvedPath {
+    /// Absolute path
+    pub path: String,
+    /// Whether it exists on disk
+    pub exists: bool,
+}
+
+/// Where the Manager and OpenClaw keep their files on this machine
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfigPaths {
+    /// OpenClaw home directory (~/.openclaw)
+    pub config_dir: ResolvedPath,
+    /// openclaw.json
+    pub config_file: ResolvedPath,
+    /// Manager .env file
+    pub env_file: ResolvedPath,
+    /// Manager MCP config (mcps.json)
+    pub mcp_config_file: ResolvedPath,
+    /// mcporter config (~/.mcporter/mcporter.json)
+    pub mcporter_config_file: ResolvedPath,
+    /// Directory MCP servers are installed into
+    pub mcp_install_dir: ResolvedPath,
+}
+
 /// Configured Provider (read from configuration file)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfiguredProvider {
@@ -267,6 +559,12 @@ pub struct ConfiguredModel {
     pub max_tokens: Option<u32>,
     /// Whether it is the primary model
     pub is_primary: bool,
+    /// Whether it is listed in agents.defaults.models
+    pub is_available: bool,
+    /// Whether reasoning mode is supported (None if not recorded)
+    pub reasoning: Option<bool>,
+    /// Pricing (None if not recorded)
+    pub cost: Option<ModelCostConfig>,
 }
 
 /// AI configuration overview (returned to frontend)
@@ -274,10 +572,124 @@ pub struct ConfiguredModel {
 pub struct AIConfigOverview {
     /// Primary model
     pub primary_model: Option<String>,
+    /// Fallback models, in the order they are tried
+    pub fallback_models: Vec<String>,
     /// Configured provider list
     pub configured_providers: Vec<ConfiguredProvider>,
     /// Available model list
-    pub available_models: Vec<String>,
+    pub available_models: Vec<AvailableModel>,
+}
+
+/// Everything prune_config removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PruneReport {
+    /// agents.defaults.models keys whose provider or model no longer exists
+    pub removed_models: Vec<String>,
+    /// Bindings to agents that no longer exist ("agentId (channel)")
+    pub removed_bindings: Vec<String>,
+    /// Primary model that was cleared because its provider is gone
+    pub cleared_primary: Option<String>,
+}
+
+/// Entry of agents.defaults.models, cross-referenced with the configured providers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AvailableModel {
+    /// Full model ID (provider/model-id)
+    pub full_id: String,
+    /// Whether the provider and the model both exist (false = orphaned entry)
+    pub exists: bool,
+    /// Whether the provider exists (false = the whole provider is gone)
+    pub provider_exists: bool,
+}
+
+/// Difference between a configured provider and its official preset
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderDelta {
+    /// Field that differs (base_url / api_type / model)
+    pub field: String,
+    /// Value currently configured
+    pub configured: Option<String>,
+    /// Value recommended by the official preset
+    pub official: Option<String>,
+    /// Human-readable explanation
+    pub message: String,
+}
+
+/// Aggregated token usage for one provider/model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderUsage {
+    /// Provider name
+    pub provider: String,
+    /// Model ID (without provider prefix)
+    pub model: String,
+    /// Number of recorded requests
+    pub requests: u64,
+    /// Total input tokens
+    pub input_tokens: u64,
+    /// Total output tokens
+    pub output_tokens: u64,
+    /// Estimated cost (recorded cost, or derived from the model's configured pricing)
+    pub estimated_cost: f64,
+}
+
+/// Provider usage report (returned to frontend)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderUsageReport {
+    /// Whether any session transcript was found
+    pub data_available: bool,
+    /// Agents directory whose session transcripts were read
+    pub source: String,
+    /// Per provider/model totals
+    pub usage: Vec<ProviderUsage>,
+}
+
+/// Result of reconciling the available model list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconcileReport {
+    /// Strategy that was applied
+    pub strategy: String,
+    /// Model IDs added to the available list
+    pub added: Vec<String>,
+    /// Model IDs removed from the available list
+    pub removed: Vec<String>,
+}
+
+/// Number of models configured for a provider
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderModelCount {
+    /// Provider name
+    pub provider: String,
+    /// Models currently configured
+    pub count: usize,
+    /// Soft cap (meta.gui.maxModelsPerProvider)
+    pub limit: usize,
+    /// Whether the provider is above the cap
+    pub over_limit: bool,
+}
+
+/// Result of probing one configured provider with an authenticated request
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderHealth {
+    /// Provider name
+    pub name: String,
+    /// Whether the provider answered with a 2xx status
+    pub reachable: bool,
+    /// Round-trip time, when the provider answered at all
+    pub latency_ms: Option<u64>,
+    /// Why the probe failed
+    pub error: Option<String>,
+    /// Whether this result came from the health cache instead of a fresh probe
+    #[serde(default)]
+    pub cached: bool,
+}
+
+/// A config schema violation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ValidationError {
+    /// JSON pointer of the offending value ("/" for the document root)
+    pub path: String,
+    /// What is wrong with it
+    pub message: String,
 }
 
 // ============ Legacy data structures for compatibility ============
@@ -323,6 +735,28 @@ pub struct ChannelConfig {
     pub enabled: bool,
     /// Configuration details
     pub config: HashMap<String, serde_json::Value>,
+    /// Each configured field with its storage location (filled by get_channels_config; ignored on save)
+    #[serde(default)]
+    pub fields: Vec<ChannelField>,
+}
+
+/// Where a channel field is persisted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldSource {
+    /// Manager env file
+    Env,
+    /// openclaw.json
+    Config,
+}
+
+/// A channel field and where it is stored
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChannelField {
+    pub key: String,
+    /// Value as text, masked when the key looks like a secret
+    pub value_masked: String,
+    pub source: FieldSource,
 }
 
 /// Environment variable configuration
@@ -62,6 +62,35 @@ pub struct DiagnosticResult {
     pub suggestion: Option<String>,
 }
 
+/// Outcome of a verification step
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+/// One step of the full setup verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationStep {
+    /// Step name
+    pub name: String,
+    /// Pass / fail / skip
+    pub status: StepStatus,
+    /// Detailed information
+    pub message: String,
+}
+
+/// Full setup verification report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// Steps in the order they ran
+    pub steps: Vec<VerificationStep>,
+    /// Fail if any step failed, pass if at least one passed, otherwise skip
+    pub overall: StepStatus,
+}
+
 /// AI connection test result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AITestResult {
@@ -0,0 +1,63 @@
+use log::LevelFilter;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Log levels accepted by set_log_level, from quietest to most verbose
+pub const LOG_LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+/// A log file larger than this is moved to `<name>.1` at startup
+const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Parse a level name (case-insensitive) into a log filter
+pub fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    let normalized = level.trim().to_lowercase();
+    if !LOG_LEVELS.contains(&normalized.as_str()) {
+        return Err(format!("Unknown log level '{}'. Valid levels: {}", level, LOG_LEVELS.join(", ")));
+    }
+    normalized.parse().map_err(|_| format!("Unknown log level '{}'", level))
+}
+
+/// Last `n` lines of a log, oldest first
+pub fn tail_lines(content: &str, n: usize) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(n)..].iter().map(|s| s.to_string()).collect()
+}
+
+/// Writer for env_logger that echoes every record to stderr and appends it to the log file.
+/// When the file cannot be opened, logging continues on stderr only.
+pub struct TeeWriter {
+    file: Option<File>,
+}
+
+impl TeeWriter {
+    pub fn open(path: &str) -> Self {
+        let path = Path::new(path);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if std::fs::metadata(path).is_ok_and(|m| m.len() > MAX_LOG_FILE_SIZE) {
+            let _ = std::fs::rename(path, format!("{}.1", path.display()));
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path).ok();
+        TeeWriter { file }
+    }
+}
+
+impl Write for TeeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let _ = io::stderr().write_all(buf);
+        if let Some(file) = self.file.as_mut() {
+            let _ = file.write_all(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let _ = io::stderr().flush();
+        if let Some(file) = self.file.as_mut() {
+            let _ = file.flush();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use super::super::app_log::{parse_level, tail_lines, TeeWriter};
+    use log::LevelFilter;
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("debug").unwrap(), LevelFilter::Debug);
+        assert_eq!(parse_level(" WARN ").unwrap(), LevelFilter::Warn);
+        assert_eq!(parse_level("off").unwrap(), LevelFilter::Off);
+
+        let err = parse_level("verbose").unwrap_err();
+        assert!(err.contains("verbose"));
+        assert!(err.contains("trace"));
+        // Numeric levels are accepted by the log crate but not by the Manager
+        assert!(parse_level("3").is_err());
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        let log = "one\ntwo\nthree\n";
+        assert_eq!(tail_lines(log, 2), vec!["two", "three"]);
+        assert_eq!(tail_lines(log, 10), vec!["one", "two", "three"]);
+        assert!(tail_lines(log, 0).is_empty());
+        assert!(tail_lines("", 5).is_empty());
+    }
+
+    #[test]
+    fn test_tee_writer_appends_to_file() {
+        let base = std::env::temp_dir().join(format!("openclaw-app-log-test-{}", std::process::id()));
+        let path = base.join("logs").join("manager.log");
+        let path = path.to_str().unwrap();
+
+        let mut writer = TeeWriter::open(path);
+        writer.write_all(b"INFO first\n").unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        let mut writer = TeeWriter::open(path);
+        writer.write_all(b"INFO second\n").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "INFO first\nINFO second\n");
+        std::fs::remove_dir_all(&base).ok();
+    }
+}
@@ -44,55 +44,242 @@ pub fn read_last_lines(path: &str, n: usize) -> io::Result<Vec<String>> {
     Ok(lines[start..].to_vec())
 }
 
+/// 环境变量名是否合法。采用保守规则 `[A-Z_][A-Z0-9_]*`，
+/// 含空格、`=` 或小写字母的名称在 dotenv 重新加载时会解析失败
+pub fn is_valid_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_uppercase() || c == '_')
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// 将值写成双引号字符串：去除首尾空白，转义 `\ " $ \``，
+/// 与 shell `source` 的双引号语义一致（值中不应含换行，由 set_env_value 拒绝）
+pub fn quote_env_value(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.trim().chars() {
+        if matches!(c, '\\' | '"' | '$' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// quote_env_value 的逆操作，按 shell 双引号规则解析：只有 `\\ \" \$ \`` 是转义，
+/// 其他反斜杠（如手写的 `C:\dir` 或 `\n`）原样保留。单引号中的值按原样返回，
+/// 未加引号的值只去除首尾空白
+pub fn unquote_env_value(raw: &str) -> String {
+    let raw = raw.trim();
+    if raw.len() >= 2 && raw.starts_with('\'') && raw.ends_with('\'') {
+        return raw[1..raw.len() - 1].to_string();
+    }
+    let Some(inner) = raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')) else {
+        return raw.to_string();
+    };
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(escaped @ ('\\' | '"' | '$' | '`')) => value.push(escaped),
+            Some(other) => {
+                value.push('\\');
+                value.push(other);
+            }
+            None => value.push('\\'),
+        }
+    }
+    value
+}
+
+/// 解析环境变量文件中的 `export KEY="value"` 行（按文件顺序）
+pub fn parse_env_exports(content: &str) -> Vec<(String, String)> {
+    content.lines()
+        .filter_map(|line| line.trim().strip_prefix("export "))
+        .filter_map(|rest| rest.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), unquote_env_value(v)))
+        .filter(|(k, _)| !k.is_empty())
+        .collect()
+}
+
 /// 从环境变量文件读取值
 pub fn read_env_value(env_file: &str, key: &str) -> Option<String> {
     let content = read_file(env_file).ok()?;
+    let prefix = format!("export {}=", key);
     
-    for line in content.lines() {
-        let line = line.trim();
-        if line.starts_with(&format!("export {}=", key)) {
-            let value = line
-                .trim_start_matches(&format!("export {}=", key))
-                .trim_matches('"')
-                .trim_matches('\'');
-            return Some(value.to_string());
-        }
-    }
-    
-    None
+    content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(&prefix).map(unquote_env_value))
 }
 
-/// 设置环境变量文件中的值
+/// 设置环境变量文件中的值。名称不合法或值含换行时返回 InvalidInput，值经 quote_env_value 转义
 pub fn set_env_value(env_file: &str, key: &str, value: &str) -> io::Result<()> {
+    if !is_valid_env_key(key) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "invalid environment variable name '{}' (use A-Z, 0-9 and _, not starting with a digit)",
+                key
+            ),
+        ));
+    }
+    // `source` keeps `\n` literally, so a multi-line value can't round-trip through the file
+    if value.trim().contains(['\n', '\r']) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the value of {} spans multiple lines; store it in a file and reference its path instead", key),
+        ));
+    }
     let content = read_file(env_file).unwrap_or_default();
-    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-    
-    let new_line = format!("export {}=\"{}\"", key, value);
+    write_file(env_file, &set_env_line(&content, key, value))
+}
+
+/// 从环境变量文件中删除指定的值
+pub fn remove_env_value(env_file: &str, key: &str) -> io::Result<()> {
+    let content = read_file(env_file).unwrap_or_default();
+    write_file(env_file, &remove_env_line(&content, key))
+}
+
+/// 该行是否为 `export KEY=...`（允许行首缩进）
+fn is_env_line_for(line: &str, key: &str) -> bool {
+    line.trim_start()
+        .strip_prefix("export ")
+        .and_then(|rest| rest.trim_start().strip_prefix(key))
+        .is_some_and(|rest| rest.starts_with('='))
+}
+
+/// 拆分出行内容与行尾（`\r\n`、`\n` 或无）
+fn split_line_ending(line: &str) -> (&str, &str) {
+    if let Some(body) = line.strip_suffix("\r\n") {
+        (body, "\r\n")
+    } else if let Some(body) = line.strip_suffix('\n') {
+        (body, "\n")
+    } else {
+        (line, "")
+    }
+}
+
+/// 在文件内容中设置 KEY：原位更新所有匹配行（保留缩进），不存在时追加到末尾。
+/// 其他行（注释、空行、其他变量）和换行风格保持不变
+pub fn set_env_line(content: &str, key: &str, value: &str) -> String {
+    let new_line = format!("export {}={}", key, quote_env_value(value));
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut updated = String::with_capacity(content.len() + new_line.len() + 2);
     let mut found = false;
-    
-    for line in &mut lines {
-        if line.starts_with(&format!("export {}=", key)) {
-            *line = new_line.clone();
+
+    for line in content.split_inclusive('\n') {
+        let (body, ending) = split_line_ending(line);
+        if is_env_line_for(body, key) {
+            let indent = &body[..body.len() - body.trim_start().len()];
+            updated.push_str(indent);
+            updated.push_str(&new_line);
+            updated.push_str(ending);
             found = true;
-            break;
+        } else {
+            updated.push_str(line);
         }
     }
-    
+
     if !found {
-        lines.push(new_line);
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push_str(newline);
+        }
+        updated.push_str(&new_line);
+        updated.push_str(newline);
     }
-    
-    write_file(env_file, &lines.join("\n"))
+    updated
 }
 
-/// 从环境变量文件中删除指定的值
-pub fn remove_env_value(env_file: &str, key: &str) -> io::Result<()> {
-    let content = read_file(env_file).unwrap_or_default();
-    let lines: Vec<String> = content
-        .lines()
-        .filter(|line| !line.starts_with(&format!("export {}=", key)))
-        .map(|s| s.to_string())
-        .collect();
-    
-    write_file(env_file, &lines.join("\n"))
+/// 从文件内容中删除 KEY 的所有定义，其他行保持不变
+pub fn remove_env_line(content: &str, key: &str) -> String {
+    content
+        .split_inclusive('\n')
+        .filter(|line| !is_env_line_for(split_line_ending(line).0, key))
+        .collect()
+}
+
+/// 去除 JSONC/JSON5 风格的注释（// 和 /* */）以及尾随逗号，得到标准 JSON。
+/// 字符串内容保持不变；注释中的换行会保留，解析错误的行号与原文件一致。
+pub fn strip_json_comments(content: &str) -> String {
+    let mut without_comments = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            without_comments.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        without_comments.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                without_comments.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().is_some_and(|&n| n != '\n' && n != '\r') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                without_comments.push(' ');
+                let mut prev = '\0';
+                for n in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    if n == '\n' {
+                        without_comments.push('\n');
+                    }
+                    prev = n;
+                }
+            }
+            _ => without_comments.push(c),
+        }
+    }
+
+    // 去除 } 或 ] 前的尾随逗号
+    let chars: Vec<char> = without_comments.chars().collect();
+    let mut result = String::with_capacity(chars.len());
+    let mut in_string = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            if c == '\\' && i + 1 < chars.len() {
+                result.push(c);
+                i += 1;
+                result.push(chars[i]);
+                i += 1;
+                continue;
+            }
+            if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|n| !n.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                i += 1;
+                continue;
+            }
+        }
+        result.push(c);
+        i += 1;
+    }
+    result
 }
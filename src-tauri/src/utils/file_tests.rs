@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use super::super::file::*;
+
+    fn temp_env_file(name: &str) -> (std::path::PathBuf, String) {
+        let base = std::env::temp_dir().join(format!("openclaw-file-test-{}-{}", name, std::process::id()));
+        let path = base.join("env").to_str().unwrap().to_string();
+        (base, path)
+    }
+
+    #[test]
+    fn test_is_valid_env_key() {
+        for key in ["OPENAI_API_KEY", "_PRIVATE", "A", "KEY2"] {
+            assert!(is_valid_env_key(key), "{} should be valid", key);
+        }
+        for key in ["", "lower", "MY KEY", "KEY ", " KEY", "A=B", "2FA_CODE", "KEY-NAME", "KÉY"] {
+            assert!(!is_valid_env_key(key), "{:?} should be rejected", key);
+        }
+    }
+
+    #[test]
+    fn test_quote_env_value_keeps_one_line() {
+        assert_eq!(quote_env_value("  sk-123  "), "\"sk-123\"");
+        assert_eq!(quote_env_value("a\"b"), "\"a\\\"b\"");
+        assert_eq!(quote_env_value("C:\\dir"), "\"C:\\\\dir\"");
+        assert_eq!(quote_env_value("$HOME `id`"), "\"\\$HOME \\`id\\`\"");
+    }
+
+    #[test]
+    fn test_unquote_env_value() {
+        assert_eq!(unquote_env_value("\"plain\""), "plain");
+        assert_eq!(unquote_env_value("'single \"kept\"'"), "single \"kept\"");
+        assert_eq!(unquote_env_value(" bare "), "bare");
+        // Hand-written Windows paths keep their backslashes
+        assert_eq!(unquote_env_value("\"C:\\dir\""), "C:\\dir");
+        // As with `source`, \n inside double quotes is not a newline
+        assert_eq!(unquote_env_value("\"a\\nb\""), "a\\nb");
+        assert_eq!(unquote_env_value("\""), "\"");
+    }
+
+    #[test]
+    fn test_set_env_value_round_trips_tricky_values() {
+        let (base, path) = temp_env_file("roundtrip");
+        let values = [
+            ("QUOTED", "say \"hi\" and 'bye'"),
+            ("SHELLISH", "$HOME `whoami` \\n literal"),
+            ("WITH_EQUALS", "a=b=c # not a comment"),
+            ("BACKSLASH_END", "ends with \\"),
+        ];
+        for (key, value) in values {
+            set_env_value(&path, key, value).unwrap();
+        }
+        set_env_value(&path, "PADDED", "  trimmed  ").unwrap();
+
+        for (key, value) in values {
+            assert_eq!(read_env_value(&path, key).as_deref(), Some(value), "{}", key);
+        }
+        assert_eq!(read_env_value(&path, "PADDED").as_deref(), Some("trimmed"));
+        // Every entry stays on its own line
+        assert_eq!(read_file(&path).unwrap().lines().count(), values.len() + 1);
+
+        set_env_value(&path, "QUOTED", "updated").unwrap();
+        assert_eq!(read_env_value(&path, "QUOTED").as_deref(), Some("updated"));
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn test_set_env_value_rejects_invalid_keys() {
+        let (base, path) = temp_env_file("invalid");
+        for key in ["KEY ", "my_key", "A=B", "1KEY"] {
+            let err = set_env_value(&path, key, "value").unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+            assert!(err.to_string().contains(key));
+        }
+        // Multi-line values would not survive `source`
+        for value in ["-----BEGIN KEY-----\nabc\n-----END KEY-----", "a\r\nb"] {
+            let err = set_env_value(&path, "PEM", value).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        }
+        assert!(!file_exists(&path));
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    const COMMENTED_ENV: &str = concat!(
+        "# OpenClaw environment\n",
+        "# Keys below are managed by hand\n",
+        "export OPENAI_API_KEY=\"sk-old\"\n",
+        "\n",
+        "  # indented comment\n",
+        "export CUSTOM_FLAG=1 # trailing note\n",
+        "MY_UNEXPORTED=keep\n",
+    );
+
+    #[test]
+    fn test_set_env_line_preserves_comments_and_other_keys() {
+        let updated = set_env_line(COMMENTED_ENV, "OPENAI_API_KEY", "sk-new");
+        assert_eq!(
+            updated,
+            COMMENTED_ENV.replace("export OPENAI_API_KEY=\"sk-old\"", "export OPENAI_API_KEY=\"sk-new\"")
+        );
+
+        let appended = set_env_line(COMMENTED_ENV, "NEW_KEY", "v");
+        assert_eq!(appended, format!("{}export NEW_KEY=\"v\"\n", COMMENTED_ENV));
+
+        // A prefix of another key is not a match
+        let prefixed = set_env_line(COMMENTED_ENV, "CUSTOM", "x");
+        assert!(prefixed.contains("export CUSTOM_FLAG=1 # trailing note\n"));
+        assert!(prefixed.ends_with("export CUSTOM=\"x\"\n"));
+    }
+
+    #[test]
+    fn test_set_env_line_edge_cases() {
+        assert_eq!(set_env_line("", "A", "1"), "export A=\"1\"\n");
+        // Missing final newline is added before appending
+        assert_eq!(set_env_line("# note", "A", "1"), "# note\nexport A=\"1\"\n");
+        // Indentation and CRLF line endings are kept
+        assert_eq!(
+            set_env_line("# c\r\n  export A=\"0\"\r\n", "A", "1"),
+            "# c\r\n  export A=\"1\"\r\n"
+        );
+        assert_eq!(set_env_line("# c\r\n", "B", "2"), "# c\r\nexport B=\"2\"\r\n");
+        // Every definition is updated so a later duplicate cannot shadow the new value
+        assert_eq!(
+            set_env_line("export A=\"0\"\n# x\nexport A=\"stale\"", "A", "1"),
+            "export A=\"1\"\n# x\nexport A=\"1\""
+        );
+    }
+
+    #[test]
+    fn test_remove_env_line_preserves_other_lines() {
+        let removed = remove_env_line(COMMENTED_ENV, "OPENAI_API_KEY");
+        assert_eq!(removed, COMMENTED_ENV.replace("export OPENAI_API_KEY=\"sk-old\"\n", ""));
+        assert_eq!(remove_env_line(COMMENTED_ENV, "MISSING"), COMMENTED_ENV);
+    }
+
+    #[test]
+    fn test_set_env_value_keeps_commented_file() {
+        let (base, path) = temp_env_file("commented");
+        write_file(&path, COMMENTED_ENV).unwrap();
+
+        set_env_value(&path, "OPENAI_API_KEY", "sk-new").unwrap();
+        set_env_value(&path, "ANTHROPIC_API_KEY", "sk-ant").unwrap();
+
+        let content = read_file(&path).unwrap();
+        assert!(content.starts_with("# OpenClaw environment\n# Keys below are managed by hand\n"));
+        assert!(content.contains("\n\n  # indented comment\nexport CUSTOM_FLAG=1 # trailing note\nMY_UNEXPORTED=keep\n"));
+        assert_eq!(read_env_value(&path, "OPENAI_API_KEY").as_deref(), Some("sk-new"));
+        assert_eq!(read_env_value(&path, "ANTHROPIC_API_KEY").as_deref(), Some("sk-ant"));
+        std::fs::remove_dir_all(&base).ok();
+    }
+}
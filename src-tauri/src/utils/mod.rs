@@ -1,7 +1,15 @@
+pub mod app_log;
 pub mod file;
 pub mod log_sanitizer;
 pub mod platform;
+pub mod secrets;
 pub mod shell;
 
+#[cfg(test)]
+mod app_log_tests;
+#[cfg(test)]
+mod file_tests;
 #[cfg(test)]
 mod log_sanitizer_tests;
+#[cfg(test)]
+mod secrets_tests;
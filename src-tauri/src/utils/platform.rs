@@ -10,9 +10,21 @@ pub fn get_arch() -> String {
     env::consts::ARCH.to_string()
 }
 
+/// Environment variable that relocates the config directory (portable installs, CI, tests)
+pub const CONFIG_DIR_ENV: &str = "OPENCLAW_CONFIG_DIR";
+
 /// 获取配置目录路径
 pub fn get_config_dir() -> String {
-    if let Some(home) = dirs::home_dir() {
+    config_dir_from(env::var(CONFIG_DIR_ENV).ok(), dirs::home_dir())
+}
+
+/// Config directory: a non-empty override wins, otherwise ~/.openclaw
+pub fn config_dir_from(override_dir: Option<String>, home: Option<std::path::PathBuf>) -> String {
+    if let Some(dir) = override_dir.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        let trimmed = dir.trim_end_matches(['/', '\\']);
+        return if trimmed.is_empty() { dir.to_string() } else { trimmed.to_string() };
+    }
+    if let Some(home) = home {
         if is_windows() {
             format!("{}\\.openclaw", home.display())
         } else {
@@ -32,12 +44,28 @@ pub fn get_env_file_path() -> String {
     }
 }
 
-/// 获取 openclaw.json 配置文件路径
+/// Project directory whose openclaw.json replaces the global one (None = global config)
+static CONFIG_SCOPE: std::sync::RwLock<Option<String>> = std::sync::RwLock::new(None);
+
+/// Active config scope directory, if a project-local one is set
+pub fn get_config_scope() -> Option<String> {
+    CONFIG_SCOPE.read().ok().and_then(|scope| scope.clone())
+}
+
+/// Switch openclaw.json to `<dir>/openclaw.json` (None returns to the global config)
+pub fn set_config_scope(dir: Option<String>) {
+    if let Ok(mut scope) = CONFIG_SCOPE.write() {
+        *scope = dir;
+    }
+}
+
+/// 获取 openclaw.json 配置文件路径（项目作用域优先）
 pub fn get_config_file_path() -> String {
+    let dir = get_config_scope().unwrap_or_else(get_config_dir);
     if is_windows() {
-        format!("{}\\openclaw.json", get_config_dir())
+        format!("{}\\openclaw.json", dir)
     } else {
-        format!("{}/openclaw.json", get_config_dir())
+        format!("{}/openclaw.json", dir)
     }
 }
 
@@ -50,6 +78,24 @@ pub fn get_manager_config_file_path() -> String {
     }
 }
 
+/// Get user provider presets file path (manager/providers.json), merged over the built-ins
+pub fn get_provider_presets_file_path() -> String {
+    if is_windows() {
+        format!("{}\\manager\\providers.json", get_config_dir())
+    } else {
+        format!("{}/manager/providers.json", get_config_dir())
+    }
+}
+
+/// Get the Manager's own log file path (manager/manager.log)
+pub fn get_manager_log_file_path() -> String {
+    if is_windows() {
+        format!("{}\\manager\\manager.log", get_config_dir())
+    } else {
+        format!("{}/manager/manager.log", get_config_dir())
+    }
+}
+
 /// 获取日志文件路径
 pub fn get_log_file_path() -> String {
     if is_windows() {
@@ -77,6 +123,15 @@ pub fn get_mcp_config_file_path() -> String {
     }
 }
 
+/// Get the agents directory; openclaw keeps session transcripts in agents/<id>/sessions/*.jsonl
+pub fn get_agents_dir() -> String {
+    if is_windows() {
+        format!("{}\\agents", get_config_dir())
+    } else {
+        format!("{}/agents", get_config_dir())
+    }
+}
+
 /// Get mcporter configuration file path (~/.mcporter/mcporter.json)
 pub fn get_mcporter_config_file_path() -> String {
     if let Some(home) = dirs::home_dir() {
@@ -0,0 +1,253 @@
+use super::file;
+use log::{info, warn};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Keyring service name used for all Manager-owned secrets
+const KEYRING_SERVICE: &str = "openclaw-manager";
+
+/// Prefix of env variables that reference keyring secrets
+const SECRET_VAR_PREFIX: &str = "OPENCLAW_SECRET_";
+
+/// Config subtrees that must keep their secrets inline (the gateway token is read back by the Manager)
+const INLINE_ONLY_ROOTS: [&str; 1] = ["gateway"];
+
+/// Whether a config key holds a secret (apiKey, botToken, appSecret, GITHUB_TOKEN, ...)
+pub fn is_secret_key(key: &str) -> bool {
+    let k: String = key.chars().filter(|c| *c != '_' && *c != '-').collect::<String>().to_lowercase();
+    k.ends_with("apikey")
+        || k.ends_with("token")
+        || k.ends_with("secret")
+        || k.ends_with("password")
+        || k.ends_with("privatekey")
+        || k == "encryptkey"
+}
+
+/// Build the env variable name for a secret at the given config path
+pub fn secret_var_name(path: &[String]) -> String {
+    let joined: String = path.join("_")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("{}{}", SECRET_VAR_PREFIX, joined)
+}
+
+/// Extract the variable name from a "${OPENCLAW_SECRET_...}" placeholder
+pub fn parse_secret_ref(value: &str) -> Option<&str> {
+    value.strip_prefix("${")
+        .and_then(|v| v.strip_suffix('}'))
+        .filter(|v| v.starts_with(SECRET_VAR_PREFIX))
+}
+
+/// Callback invoked with the config path and value of each secret string
+type SecretVisitor<'a> = dyn FnMut(&[String], &mut Value) -> Result<(), String> + 'a;
+
+/// Visit every secret string below `value`, skipping the top-level keys in `skip_roots`
+fn walk_secrets(
+    value: &mut Value,
+    path: &mut Vec<String>,
+    skip_roots: &[&str],
+    visit: &mut SecretVisitor<'_>,
+) -> Result<(), String> {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if path.is_empty() && skip_roots.contains(&key.as_str()) {
+                    continue;
+                }
+                path.push(key.clone());
+                if is_secret_key(key) && v.is_string() {
+                    visit(path, v)?;
+                } else {
+                    walk_secrets(v, path, skip_roots, visit)?;
+                }
+                path.pop();
+            }
+        }
+        Value::Array(arr) => {
+            for (i, v) in arr.iter_mut().enumerate() {
+                path.push(i.to_string());
+                walk_secrets(v, path, skip_roots, visit)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Rewrite every secret string in a config with `replace`, the inline-only ones included.
+/// Used for redacting exports and masking logs.
+pub fn replace_secrets(value: &mut Value, replace: &mut dyn FnMut(&str) -> String) {
+    let _ = walk_secrets(value, &mut Vec::new(), &[], &mut |_, v| {
+        let replaced = replace(v.as_str().unwrap_or(""));
+        *v = Value::String(replaced);
+        Ok(())
+    });
+}
+
+/// Move plaintext secrets out of the config via `store(var, secret)`, leaving "${VAR}" placeholders.
+/// Values that are already env references are left alone. Returns the number of secrets moved.
+pub fn externalize_secrets(
+    config: &mut Value,
+    store: &mut dyn FnMut(&str, &str) -> Result<(), String>,
+) -> Result<usize, String> {
+    let mut moved = 0;
+    walk_secrets(config, &mut Vec::new(), &INLINE_ONLY_ROOTS, &mut |path, v| {
+        let secret = v.as_str().unwrap_or("");
+        if secret.is_empty() || secret.starts_with("${") {
+            return Ok(());
+        }
+        let var = secret_var_name(path);
+        store(&var, secret)?;
+        *v = Value::String(format!("${{{}}}", var));
+        moved += 1;
+        Ok(())
+    })?;
+    Ok(moved)
+}
+
+/// Replace keyring placeholders with the secrets returned by `load(var)`. Returns the variables restored.
+pub fn internalize_secrets(
+    config: &mut Value,
+    load: &mut dyn FnMut(&str) -> Result<Option<String>, String>,
+) -> Result<Vec<String>, String> {
+    let mut restored = Vec::new();
+    walk_secrets(config, &mut Vec::new(), &INLINE_ONLY_ROOTS, &mut |_, v| {
+        let Some(var) = v.as_str().and_then(parse_secret_ref).map(|s| s.to_string()) else {
+            return Ok(());
+        };
+        match load(&var)? {
+            Some(secret) => {
+                *v = Value::String(secret);
+                restored.push(var);
+            }
+            None => warn!("[Secrets] No keyring entry for {}, leaving placeholder", var),
+        }
+        Ok(())
+    })?;
+    Ok(restored)
+}
+
+/// Collect all keyring placeholder variable names referenced by a config
+pub fn collect_secret_refs(config: &Value) -> Vec<String> {
+    let mut refs = Vec::new();
+    let mut copy = config.clone();
+    let _ = walk_secrets(&mut copy, &mut Vec::new(), &INLINE_ONLY_ROOTS, &mut |_, v| {
+        if let Some(var) = v.as_str().and_then(parse_secret_ref) {
+            refs.push(var.to_string());
+        }
+        Ok(())
+    });
+    refs
+}
+
+/// Keyring variable holding the secret stored in the env file under `key`
+pub fn env_secret_var(key: &str) -> String {
+    format!("{}ENV_{}", SECRET_VAR_PREFIX, key)
+}
+
+/// Move secret entries of an env file (OPENAI_API_KEY, TELEGRAM_BOT_TOKEN, ...) out via
+/// `store(var, secret)`, leaving `export KEY="${VAR}"` references. Returns the new content
+/// and the number of secrets moved.
+pub fn externalize_env_secrets(
+    content: &str,
+    store: &mut dyn FnMut(&str, &str) -> Result<(), String>,
+) -> Result<(String, usize), String> {
+    let mut updated = content.to_string();
+    let mut moved = 0;
+    for (key, value) in file::parse_env_exports(content) {
+        if !is_secret_key(&key) || value.is_empty() || value.starts_with("${") {
+            continue;
+        }
+        let var = env_secret_var(&key);
+        store(&var, &value)?;
+        updated = file::set_env_line(&updated, &key, &format!("${{{}}}", var));
+        moved += 1;
+    }
+    Ok((updated, moved))
+}
+
+/// Replace keyring references in an env file with the secrets returned by `load(var)`.
+/// Returns the new content and the variables restored.
+pub fn internalize_env_secrets(
+    content: &str,
+    load: &mut dyn FnMut(&str) -> Result<Option<String>, String>,
+) -> Result<(String, Vec<String>), String> {
+    let mut updated = content.to_string();
+    let mut restored = Vec::new();
+    for (key, value) in file::parse_env_exports(content) {
+        let Some(var) = parse_secret_ref(&value) else { continue };
+        match load(var)? {
+            Some(secret) => {
+                updated = file::set_env_line(&updated, &key, &secret);
+                restored.push(var.to_string());
+            }
+            None => warn!("[Secrets] No keyring entry for {}, leaving placeholder", var),
+        }
+    }
+    Ok((updated, restored))
+}
+
+/// Store a secret in the OS keyring
+pub fn store_secret(name: &str, secret: &str) -> Result<(), String> {
+    keyring::Entry::new(KEYRING_SERVICE, name)
+        .and_then(|e| e.set_password(secret))
+        .map_err(|e| format!("Failed to store {} in the OS keyring: {}", name, e))
+}
+
+/// Read a secret from the OS keyring (None if it does not exist)
+pub fn load_secret(name: &str) -> Result<Option<String>, String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, name)
+        .map_err(|e| format!("Failed to open keyring entry {}: {}", name, e))?;
+    match entry.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read {} from the OS keyring: {}", name, e)),
+    }
+}
+
+/// Delete a secret from the OS keyring (missing entries are ignored)
+pub fn delete_secret(name: &str) -> Result<(), String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, name)
+        .map_err(|e| format!("Failed to open keyring entry {}: {}", name, e))?;
+    match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete {} from the OS keyring: {}", name, e)),
+    }
+}
+
+/// Resolve env file entries that reference the keyring into `KEY -> secret`, so openclaw
+/// processes see the real value under the name the user gave it
+pub fn resolve_env_file_secrets(content: &str) -> HashMap<String, String> {
+    let mut env = HashMap::new();
+    for (key, value) in file::parse_env_exports(content) {
+        let Some(var) = parse_secret_ref(&value) else { continue };
+        match load_secret(var) {
+            Ok(Some(secret)) => {
+                env.insert(key, secret);
+            }
+            Ok(None) => warn!("[Secrets] Keyring entry {} is missing", var),
+            Err(e) => warn!("[Secrets] {}", e),
+        }
+    }
+    env
+}
+
+/// Resolve keyring placeholders referenced by a config into env variables for openclaw processes
+pub fn resolve_secret_env(config: &Value) -> HashMap<String, String> {
+    let mut env = HashMap::new();
+    for var in collect_secret_refs(config) {
+        match load_secret(&var) {
+            Ok(Some(secret)) => {
+                env.insert(var, secret);
+            }
+            Ok(None) => warn!("[Secrets] Keyring entry {} is missing", var),
+            Err(e) => warn!("[Secrets] {}", e),
+        }
+    }
+    if !env.is_empty() {
+        info!("[Secrets] Resolved {} secrets from the OS keyring", env.len());
+    }
+    env
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use super::super::secrets::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_secrets_round_trip_through_store() {
+        let original = json!({
+            "models": { "providers": { "anthropic": { "apiKey": "sk-ant-123", "models": [{ "id": "claude", "maxTokens": 8192 }] } } },
+            "channels": { "telegram": { "botToken": "123:abc" } },
+            "gateway": { "auth": { "token": "gateway-token" } }
+        });
+        let mut config = original.clone();
+        let mut store: HashMap<String, String> = HashMap::new();
+
+        let moved = externalize_secrets(&mut config, &mut |var, secret| {
+            store.insert(var.to_string(), secret.to_string());
+            Ok(())
+        }).unwrap();
+        assert_eq!(moved, 2);
+        assert_eq!(config["models"]["providers"]["anthropic"]["apiKey"], "${OPENCLAW_SECRET_MODELS_PROVIDERS_ANTHROPIC_APIKEY}");
+        assert_eq!(config["channels"]["telegram"]["botToken"], "${OPENCLAW_SECRET_CHANNELS_TELEGRAM_BOTTOKEN}");
+        // The gateway token stays inline and non-secret numbers are untouched
+        assert_eq!(config["gateway"]["auth"]["token"], "gateway-token");
+        assert_eq!(config["models"]["providers"]["anthropic"]["models"][0]["maxTokens"], 8192);
+
+        let mut refs = collect_secret_refs(&config);
+        refs.sort();
+        assert_eq!(refs.len(), 2);
+
+        // Externalizing again is a no-op
+        assert_eq!(externalize_secrets(&mut config, &mut |_, _| Ok(())).unwrap(), 0);
+
+        let restored = internalize_secrets(&mut config, &mut |var| Ok(store.get(var).cloned())).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(config, original);
+    }
+
+    #[test]
+    fn test_parse_secret_ref_ignores_other_env_refs() {
+        assert_eq!(parse_secret_ref("${OPENCLAW_SECRET_X}"), Some("OPENCLAW_SECRET_X"));
+        assert_eq!(parse_secret_ref("${ANTHROPIC_API_KEY}"), None);
+        assert_eq!(parse_secret_ref("sk-plain"), None);
+    }
+
+    #[test]
+    fn test_env_secrets_round_trip() {
+        let env = "# keys\nexport OPENAI_API_KEY=\"sk-live\"\nexport OPENCLAW_TELEGRAM_USERID=\"42\"\nexport TELEGRAM_BOT_TOKEN=\"123:abc\"\n";
+        let mut vault: HashMap<String, String> = HashMap::new();
+
+        let (externalized, moved) = externalize_env_secrets(env, &mut |var, secret| {
+            vault.insert(var.to_string(), secret.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(moved, 2);
+        assert!(!externalized.contains("sk-live") && !externalized.contains("123:abc"));
+        assert!(externalized.contains("# keys\n"));
+        assert!(externalized.contains("export OPENCLAW_TELEGRAM_USERID=\"42\""));
+        assert_eq!(vault.get(&env_secret_var("OPENAI_API_KEY")).map(String::as_str), Some("sk-live"));
+
+        // References are read back as ${VAR} and are not moved twice
+        let vars = crate::utils::file::parse_env_exports(&externalized);
+        assert!(vars.contains(&("OPENAI_API_KEY".to_string(), format!("${{{}}}", env_secret_var("OPENAI_API_KEY")))));
+        let (_, again) = externalize_env_secrets(&externalized, &mut |_, _| panic!("already moved")).unwrap();
+        assert_eq!(again, 0);
+
+        let (restored, vars) = internalize_env_secrets(&externalized, &mut |var| Ok(vault.get(var).cloned())).unwrap();
+        assert_eq!(restored, env);
+        assert_eq!(vars.len(), 2);
+    }
+}
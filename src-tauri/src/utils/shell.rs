@@ -3,6 +3,7 @@ use std::io;
 use std::collections::HashMap;
 use crate::utils::platform;
 use crate::utils::file;
+use crate::utils::secrets;
 use log::{info, debug, warn};
 
 #[cfg(windows)]
@@ -83,6 +84,31 @@ pub fn run_command(cmd: &str, args: &[&str]) -> io::Result<Output> {
     command.output()
 }
 
+/// Execute shell command asynchronously (with extended PATH), optionally in `cwd`.
+/// Use this for long-running commands (git clone, npm install) inside async commands
+/// so a Tokio worker thread isn't blocked for the whole run.
+/// Dropping the returned future kills the process (used for timeouts and cancellation).
+pub async fn run_command_async(cmd: &str, args: &[&str], cwd: Option<&str>) -> io::Result<Output> {
+    let mut command = tokio::process::Command::new(cmd);
+    command.args(args).kill_on_drop(true);
+
+    if let Some(dir) = cwd {
+        command.current_dir(dir);
+    }
+
+    // Use extended PATH on non-Windows systems
+    #[cfg(not(windows))]
+    {
+        let extended_path = get_extended_path();
+        command.env("PATH", extended_path);
+    }
+
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    command.output().await
+}
+
 /// Execute shell command and get output string
 pub fn run_command_output(cmd: &str, args: &[&str]) -> Result<String, String> {
     match run_command(cmd, args) {
@@ -351,11 +377,27 @@ fn get_windows_openclaw_paths() -> Vec<String> {
 
 /// Execute openclaw command and get output
 pub fn run_openclaw(args: &[&str]) -> Result<String, String> {
+    let out = run_openclaw_raw(args)?;
+    let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+    debug!("[Shell] Command exit code: {:?}", out.status.code());
+    if out.status.success() {
+        debug!("[Shell] Command executed successfully, stdout length: {}", stdout.len());
+        Ok(stdout)
+    } else {
+        debug!("[Shell] Command execution failed, stderr: {}", stderr);
+        Err(format!("{}\n{}", stdout, stderr).trim().to_string())
+    }
+}
+
+/// Execute openclaw command and return the raw process output (exit status, stdout, stderr).
+/// Only fails if the CLI can't be found or started.
+pub fn run_openclaw_raw(args: &[&str]) -> Result<Output, String> {
     debug!("[Shell] Executing openclaw command: {:?}", args);
     
     let openclaw_path = get_openclaw_path().ok_or_else(|| {
         warn!("[Shell] Cannot find openclaw command");
-        "Cannot find openclaw command, please ensure it is installed via npm install -g openclaw".to_string()
+        OPENCLAW_NOT_FOUND.to_string()
     })?;
     
     debug!("[Shell] openclaw path: {}", openclaw_path);
@@ -367,10 +409,13 @@ pub fn run_openclaw(args: &[&str]) -> Result<String, String> {
     let output = if platform::is_windows() && openclaw_path.ends_with(".cmd") {
         // Windows: .cmd files can be executed directly
         let mut cmd = Command::new(&openclaw_path);
-        let gw_token = get_gateway_token_from_config();
         cmd.args(args)
-            .env("OPENCLAW_GATEWAY_TOKEN", &gw_token)
+            .envs(load_keyring_secret_env())
+            .envs(config_scope_env())
             .env("PATH", &extended_path);
+        if let Some(gw_token) = get_gateway_token_from_config() {
+            cmd.env("OPENCLAW_GATEWAY_TOKEN", &gw_token);
+        }
         
         #[cfg(windows)]
         cmd.creation_flags(CREATE_NO_WINDOW);
@@ -378,10 +423,13 @@ pub fn run_openclaw(args: &[&str]) -> Result<String, String> {
         cmd.output()
     } else {
         let mut cmd = Command::new(&openclaw_path);
-        let gw_token = get_gateway_token_from_config();
         cmd.args(args)
-            .env("OPENCLAW_GATEWAY_TOKEN", &gw_token)
+            .envs(load_keyring_secret_env())
+            .envs(config_scope_env())
             .env("PATH", &extended_path);
+        if let Some(gw_token) = get_gateway_token_from_config() {
+            cmd.env("OPENCLAW_GATEWAY_TOKEN", &gw_token);
+        }
         
         #[cfg(windows)]
         cmd.creation_flags(CREATE_NO_WINDOW);
@@ -390,17 +438,10 @@ pub fn run_openclaw(args: &[&str]) -> Result<String, String> {
     };
     
     match output {
-        Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-            debug!("[Shell] Command exit code: {:?}", out.status.code());
-            if out.status.success() {
-                debug!("[Shell] Command executed successfully, stdout length: {}", stdout.len());
-                Ok(stdout)
-            } else {
-                debug!("[Shell] Command execution failed, stderr: {}", stderr);
-                Err(format!("{}\n{}", stdout, stderr).trim().to_string())
-            }
+        Ok(out) => Ok(out),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            warn!("[Shell] openclaw at {} could not be started: {}", openclaw_path, e);
+            Err(OPENCLAW_NOT_FOUND.to_string())
         }
         Err(e) => {
             warn!("[Shell] Failed to execute openclaw: {}", e);
@@ -409,96 +450,37 @@ pub fn run_openclaw(args: &[&str]) -> Result<String, String> {
     }
 }
 
-/// Default Gateway Token (fallback only)
-pub const DEFAULT_GATEWAY_TOKEN: &str = "openclaw-manager-local-token";
+/// Error returned by run_openclaw when the CLI isn't installed (as opposed to a command that ran and failed)
+pub const OPENCLAW_NOT_FOUND: &str = "OpenClaw CLI not found on PATH — install it first (npm install -g openclaw)";
 
-/// Read the actual gateway auth token from openclaw.json config.
-/// If no token exists (fresh install), generates one and saves it to config.
-/// Falls back to DEFAULT_GATEWAY_TOKEN only if config is completely unreadable.
-fn get_gateway_token_from_config() -> String {
-    let config_path = platform::get_config_file_path();
+/// Whether the openclaw CLI can be found
+pub fn openclaw_available() -> bool {
+    get_openclaw_path().is_some()
+}
 
-    // Try to read existing config
-    let mut config = if let Ok(content) = file::read_file(&config_path) {
-        let content = content.strip_prefix('\u{FEFF}').unwrap_or(&content);
-        match serde_json::from_str::<serde_json::Value>(content) {
-            Ok(c) => c,
-            Err(_) => serde_json::json!({}),
-        }
-    } else {
-        serde_json::json!({})
-    };
+/// Read the gateway auth token from openclaw.json. Read-only: the token is created
+/// under the config lock by config::ensure_gateway_auth before the gateway starts.
+/// Returns None when gateway auth is disabled (gateway.auth.mode = "none"), no token is
+/// set yet, or the config can't be read.
+fn get_gateway_token_from_config() -> Option<String> {
+    let content = file::read_file(&platform::get_config_file_path()).ok()?;
+    let content = content.strip_prefix('\u{FEFF}').unwrap_or(&content);
+    let config: serde_json::Value = serde_json::from_str(&file::strip_json_comments(content)).ok()?;
+
+    if config.pointer("/gateway/auth/mode").and_then(|v| v.as_str()) == Some("none") {
+        info!("[Shell] Gateway auth is disabled, not using a token");
+        return None;
+    }
 
-    // Check if token already exists
-    let existing_token = config
+    let token = config
         .pointer("/gateway/auth/token")
         .and_then(|v| v.as_str())
         .filter(|t| !t.is_empty())
         .map(|t| t.to_string());
-
-    if let Some(token) = existing_token {
-        // Ensure controlUi.allowInsecureAuth is set (may be missing on older configs)
-        let needs_update = config
-            .pointer("/gateway/controlUi/allowInsecureAuth")
-            .and_then(|v| v.as_bool())
-            != Some(true);
-        if needs_update {
-            info!("[Shell] Setting gateway.controlUi.allowInsecureAuth = true");
-            if config["gateway"].get("controlUi").is_none() {
-                config["gateway"]["controlUi"] = serde_json::json!({});
-            }
-            config["gateway"]["controlUi"]["allowInsecureAuth"] = serde_json::json!(true);
-            if let Ok(content) = serde_json::to_string_pretty(&config) {
-                let _ = file::write_file(&config_path, &content);
-            }
-        }
+    if token.is_some() {
         info!("[Shell] Using gateway token from config");
-        return token;
-    }
-
-    // No token found — generate one and save it to config
-    info!("[Shell] No gateway token found, generating new token...");
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
-    let random_part: u64 = (timestamp as u64) ^ 0x5DEECE66Du64;
-    let new_token = format!(
-        "{:016x}{:016x}{:016x}",
-        random_part,
-        random_part.wrapping_mul(0x5DEECE66Du64),
-        timestamp as u64
-    );
-
-    // Ensure gateway.auth path exists in config
-    if config.get("gateway").is_none() {
-        config["gateway"] = serde_json::json!({});
-    }
-    if config["gateway"].get("auth").is_none() {
-        config["gateway"]["auth"] = serde_json::json!({});
-    }
-    config["gateway"]["auth"]["token"] = serde_json::json!(&new_token);
-    config["gateway"]["auth"]["mode"] = serde_json::json!("token");
-    if config["gateway"].get("mode").is_none() {
-        config["gateway"]["mode"] = serde_json::json!("local");
-    }
-    // Allow Control UI to connect with token-only auth (skip device pairing for local manager)
-    if config["gateway"].get("controlUi").is_none() {
-        config["gateway"]["controlUi"] = serde_json::json!({});
     }
-    config["gateway"]["controlUi"]["allowInsecureAuth"] = serde_json::json!(true);
-
-    // Save config
-    if let Ok(content) = serde_json::to_string_pretty(&config) {
-        if let Err(e) = file::write_file(&config_path, &content) {
-            warn!("[Shell] Failed to save generated token to config: {}", e);
-            return DEFAULT_GATEWAY_TOKEN.to_string();
-        }
-    }
-
-    info!("[Shell] Generated and saved new gateway token: {}...", &new_token[..8]);
-    new_token
+    token
 }
 
 /// Read all environment variables from ~/.openclaw/env file
@@ -517,12 +499,8 @@ fn load_openclaw_env_vars() -> HashMap<String, String> {
             // Parse export KEY=VALUE or KEY=VALUE format
             let line = line.strip_prefix("export ").unwrap_or(line);
             if let Some((key, value)) = line.split_once('=') {
-                let key = key.trim();
-                // Remove quotes around the value
-                let value = value.trim()
-                    .trim_matches('"')
-                    .trim_matches('\'');
-                env_vars.insert(key.to_string(), value.to_string());
+                // Undo quoting and escapes the same way the shell would
+                env_vars.insert(key.trim().to_string(), file::unquote_env_value(value));
             }
         }
     }
@@ -530,6 +508,30 @@ fn load_openclaw_env_vars() -> HashMap<String, String> {
     env_vars
 }
 
+/// Resolve keyring-backed secrets referenced as ${OPENCLAW_SECRET_*} in openclaw.json and
+/// in the env file (the latter under their env file names)
+fn load_keyring_secret_env() -> HashMap<String, String> {
+    let config_path = platform::get_config_file_path();
+    let mut env = file::read_file(&config_path)
+        .ok()
+        .and_then(|content| {
+            let content = content.strip_prefix('\u{FEFF}').unwrap_or(&content);
+            serde_json::from_str::<serde_json::Value>(&file::strip_json_comments(content)).ok()
+        })
+        .map(|config| secrets::resolve_secret_env(&config))
+        .unwrap_or_default();
+    if let Ok(content) = file::read_file(&platform::get_env_file_path()) {
+        env.extend(secrets::resolve_env_file_secrets(&content));
+    }
+    env
+}
+
+/// Point openclaw at the project-local openclaw.json while a config scope is active,
+/// so CLI commands and the gateway read the same file the Manager edits
+fn config_scope_env() -> Option<(&'static str, String)> {
+    platform::get_config_scope().map(|_| ("OPENCLAW_CONFIG_PATH", platform::get_config_file_path()))
+}
+
 /// Start openclaw gateway in background
 /// Consistent with shell script behavior: load env file first, then start gateway
 pub fn spawn_openclaw_gateway() -> io::Result<()> {
@@ -575,12 +577,19 @@ pub fn spawn_openclaw_gateway() -> io::Result<()> {
     for (key, value) in &user_env_vars {
         cmd.env(key, value);
     }
+    // Inject secrets stored in the OS keyring (referenced from openclaw.json)
+    cmd.envs(load_keyring_secret_env());
+    cmd.envs(config_scope_env());
     
     // Set PATH and gateway token (read from config to avoid mismatch)
-    let gateway_token = get_gateway_token_from_config();
     cmd.env("PATH", &extended_path);
-    cmd.env("OPENCLAW_GATEWAY_TOKEN", &gateway_token);
-    info!("[Shell] Gateway token: {}...", &gateway_token[..8.min(gateway_token.len())]);
+    match get_gateway_token_from_config() {
+        Some(gateway_token) => {
+            cmd.env("OPENCLAW_GATEWAY_TOKEN", &gateway_token);
+            info!("[Shell] Gateway token: {}...", &gateway_token[..8.min(gateway_token.len())]);
+        }
+        None => info!("[Shell] No gateway token configured, starting without one"),
+    }
     
     // Windows: hide console window
     #[cfg(windows)]
@@ -610,7 +619,7 @@ pub fn spawn_openclaw_gateway() -> io::Result<()> {
     }
 }
 
-/// Check if command exists
+/// Check if command exists (on the extended PATH)
 pub fn command_exists(cmd: &str) -> bool {
     if platform::is_windows() {
         // Windows: use where command
@@ -627,6 +636,7 @@ pub fn command_exists(cmd: &str) -> bool {
         // Unix: use which command
         Command::new("which")
             .arg(cmd)
+            .env("PATH", get_extended_path())
             .output()
             .map(|o| o.status.success())
             .unwrap_or(false)
@@ -35,6 +35,20 @@ interface SuggestedModel {
   recommended: boolean;
 }
 
+// API type the backend accepts (get_supported_api_types)
+interface ApiTypeInfo {
+  id: string;
+  name: string;
+}
+
+// Suggested model already present in a configured provider
+interface SuggestedModelStatus {
+  provider_id: string;
+  model_id: string;
+  configured: boolean;
+  configured_in: string[];
+}
+
 interface OfficialProvider {
   id: string;
   name: string;
@@ -64,10 +78,17 @@ interface ConfiguredProvider {
   models: ConfiguredModel[];
 }
 
+interface AvailableModel {
+  full_id: string;
+  exists: boolean;
+  provider_exists: boolean;
+}
+
 interface AIConfigOverview {
   primary_model: string | null;
+  fallback_models: string[];
   configured_providers: ConfiguredProvider[];
-  available_models: string[];
+  available_models: AvailableModel[];
 }
 
 interface ModelConfig {
@@ -134,6 +155,25 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
   const [saving, setSaving] = useState(false);
   const [formError, setFormError] = useState<string | null>(null);
   const [showCustomUrlWarning, setShowCustomUrlWarning] = useState(false);
+  const [suggestedStatus, setSuggestedStatus] = useState<SuggestedModelStatus[]>([]);
+  const [apiTypes, setApiTypes] = useState<ApiTypeInfo[]>([
+    { id: 'openai-completions', name: 'OpenAI Compatible' },
+    { id: 'anthropic-messages', name: 'Anthropic Compatible' },
+  ]);
+
+  useEffect(() => {
+    invoke<SuggestedModelStatus[]>('get_suggested_model_status')
+      .then(setSuggestedStatus)
+      .catch(e => aiLogger.error('Failed to load suggested model status', e));
+    invoke<ApiTypeInfo[]>('get_supported_api_types')
+      .then(setApiTypes)
+      .catch(e => aiLogger.error('Failed to load API types', e));
+  }, []);
+
+  // When adding a provider, suggested models that are already configured elsewhere are greyed out
+  const configuredElsewhere = (providerId: string, modelId: string) =>
+    suggestedStatus.find(s => s.provider_id === providerId && s.model_id === modelId && s.configured)
+      ?.configured_in.filter(name => name !== editingProvider?.name) ?? [];
 
   // Check if using official Provider name with custom URL
   const isCustomUrlWithOfficialName = (() => {
@@ -150,8 +190,9 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
     setBaseUrl(provider.default_base_url || '');
     setApiType(provider.api_type);
     // Pre-select recommended models
-    const recommended = provider.suggested_models.filter(m => m.recommended).map(m => m.id);
-    setSelectedModels(recommended.length > 0 ? recommended : [provider.suggested_models[0]?.id].filter(Boolean));
+    const fresh = provider.suggested_models.filter(m => configuredElsewhere(provider.id, m.id).length === 0);
+    const recommended = fresh.filter(m => m.recommended).map(m => m.id);
+    setSelectedModels(recommended.length > 0 ? recommended : [fresh[0]?.id].filter(Boolean));
     setFormError(null);
     setShowCustomUrlWarning(false);
     setStep('configure');
@@ -233,7 +274,7 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
         };
       });
 
-      await invoke('save_provider', {
+      const result = await invoke<string>('save_provider', {
         providerName,
         baseUrl,
         apiKey: apiKey || null,
@@ -241,7 +282,14 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
         models,
       });
 
-      aiLogger.info(`✓ Provider ${providerName} ${isEditing ? 'updated' : 'saved'}`);
+      if (result.startsWith('No changes')) {
+        aiLogger.info(`Provider ${providerName} unchanged`);
+      } else if (result.includes('(warning:')) {
+        aiLogger.warn(result);
+        alert(result);
+      } else {
+        aiLogger.info(`✓ Provider ${providerName} ${isEditing ? 'updated' : 'saved'}`);
+      }
       onSave();
       onClose();
     } catch (e) {
@@ -434,8 +482,9 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
                     onChange={e => setApiType(e.target.value)}
                     className="input-base"
                   >
-                    <option value="openai-completions">OpenAI Compatible (openai-completions)</option>
-                    <option value="anthropic-messages">Anthropic Compatible (anthropic-messages)</option>
+                    {apiTypes.map(t => (
+                      <option key={t.id} value={t.id}>{t.name} ({t.id})</option>
+                    ))}
                   </select>
                 </div>
 
@@ -451,7 +500,9 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
                   {/* Preset Models */}
                   {selectedOfficial && (
                     <div className="space-y-2 mb-3">
-                      {selectedOfficial.suggested_models.map(model => (
+                      {selectedOfficial.suggested_models.map(model => {
+                        const configuredIn = configuredElsewhere(selectedOfficial.id, model.id);
+                        return (
                         <button
                           key={model.id}
                           onClick={() => toggleModel(model.id)}
@@ -459,7 +510,8 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
                             'w-full flex items-center justify-between p-3 rounded-lg border transition-all text-left',
                             selectedModels.includes(model.id)
                               ? 'bg-claw-500/20 border-claw-500'
-                              : 'bg-dark-700 border-dark-500 hover:border-dark-400'
+                              : 'bg-dark-700 border-dark-500 hover:border-dark-400',
+                            configuredIn.length > 0 && !selectedModels.includes(model.id) && 'opacity-50'
                           )}
                         >
                           <div>
@@ -471,6 +523,9 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
                               {model.recommended && (
                                 <span className="ml-2 text-xs text-claw-400">Recommended</span>
                               )}
+                              {configuredIn.length > 0 && (
+                                <span className="ml-2 text-xs text-gray-500">Already in {configuredIn.join(', ')}</span>
+                              )}
                             </p>
                             {model.description && (
                               <p className="text-xs text-gray-500 mt-0.5">{model.description}</p>
@@ -480,7 +535,8 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
                             <Check size={16} className="text-claw-400" />
                           )}
                         </button>
-                      ))}
+                        );
+                      })}
                     </div>
                   )}
 
@@ -911,17 +967,41 @@ export function AIConfig() {
     loadData();
   }, [loadData]);
 
-  const handleSetPrimary = async (modelId: string) => {
+  const handleSetPrimary = async (modelId: string, force = false) => {
     try {
-      await invoke('set_primary_model', { modelId });
+      await invoke('set_primary_model', { modelId, force });
       aiLogger.info(`Primary model set to: ${modelId}`);
       loadData();
     } catch (e) {
       aiLogger.error('Failed to set primary model', e);
+      if (!force && confirm(`${e}\n\nSet ${modelId} as the primary model anyway?`)) {
+        await handleSetPrimary(modelId, true);
+        return;
+      }
+      alert('Failed to set: ' + e);
+    }
+  };
+
+  const handleSetFallbacks = async (fallbacks: string[]) => {
+    if (!aiConfig?.primary_model) return;
+    try {
+      await invoke('set_model_chain', { primary: aiConfig.primary_model, fallbacks });
+      aiLogger.info(`Fallback models set to: ${fallbacks.join(', ') || 'none'}`);
+      loadData();
+    } catch (e) {
+      aiLogger.error('Failed to set fallback models', e);
       alert('Failed to set: ' + e);
     }
   };
 
+  const moveFallback = (index: number, delta: number) => {
+    if (!aiConfig) return;
+    const next = [...aiConfig.fallback_models];
+    const [item] = next.splice(index, 1);
+    next.splice(index + delta, 0, item);
+    handleSetFallbacks(next);
+  };
+
   if (loading) {
     return (
       <div className="h-full flex items-center justify-center">
@@ -1100,12 +1180,22 @@ export function AIConfig() {
             </h3>
             <div className="bg-dark-700 rounded-xl border border-dark-500 p-4">
               <div className="flex flex-wrap gap-2">
-                {aiConfig.available_models.map(modelId => (
+                {aiConfig.available_models.map(({ full_id: modelId, exists, provider_exists }) => {
+                  const canAddFallback = exists && !!aiConfig.primary_model && modelId !== aiConfig.primary_model
+                    && !aiConfig.fallback_models.includes(modelId);
+                  return (
                   <span
                     key={modelId}
+                    onClick={canAddFallback ? () => handleSetFallbacks([...aiConfig.fallback_models, modelId]) : undefined}
+                    title={!exists
+                      ? (provider_exists ? 'Model no longer exists in its provider' : 'Provider no longer exists')
+                      : canAddFallback ? 'Click to add as a fallback model' : undefined}
                     className={clsx(
                       'inline-flex items-center gap-1 px-3 py-1.5 rounded-lg text-sm',
-                      modelId === aiConfig.primary_model
+                      canAddFallback && 'cursor-pointer hover:bg-dark-500',
+                      !exists
+                        ? 'bg-dark-600/50 text-gray-500 line-through'
+                        : modelId === aiConfig.primary_model
                         ? 'bg-claw-500/20 text-claw-300 border border-claw-500/30'
                         : 'bg-dark-600 text-gray-300'
                     )}
@@ -1113,7 +1203,48 @@ export function AIConfig() {
                     {modelId === aiConfig.primary_model && <Star size={12} />}
                     {modelId}
                   </span>
-                ))}
+                  );
+                })}
+              </div>
+
+              {/* Fallback chain */}
+              <div className="mt-4 pt-4 border-t border-dark-500">
+                <p className="text-sm text-gray-400 mb-2">Fallback order (tried when the primary model fails)</p>
+                {aiConfig.fallback_models.length === 0 ? (
+                  <p className="text-xs text-gray-500">No fallback models. Click an available model to add one.</p>
+                ) : (
+                  <div className="space-y-1">
+                    {aiConfig.fallback_models.map((modelId, index) => (
+                      <div key={modelId} className="flex items-center gap-2 text-sm text-gray-300">
+                        <span className="w-5 text-gray-500">{index + 1}.</span>
+                        <span className="flex-1">{modelId}</span>
+                        <button
+                          onClick={() => moveFallback(index, -1)}
+                          disabled={index === 0}
+                          className="px-2 text-gray-500 hover:text-white disabled:opacity-30"
+                          title="Move up"
+                        >
+                          ↑
+                        </button>
+                        <button
+                          onClick={() => moveFallback(index, 1)}
+                          disabled={index === aiConfig.fallback_models.length - 1}
+                          className="px-2 text-gray-500 hover:text-white disabled:opacity-30"
+                          title="Move down"
+                        >
+                          ↓
+                        </button>
+                        <button
+                          onClick={() => handleSetFallbacks(aiConfig.fallback_models.filter(m => m !== modelId))}
+                          className="px-2 text-gray-500 hover:text-red-400"
+                          title="Remove"
+                        >
+                          <Trash2 size={14} />
+                        </button>
+                      </div>
+                    ))}
+                  </div>
+                )}
               </div>
             </div>
           </div>
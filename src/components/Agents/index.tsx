@@ -28,16 +28,22 @@ interface SubagentConfig {
     allow_agents: string[] | null;
 }
 
+interface SandboxConfig {
+    enabled: boolean;
+    allowedPaths: string[];
+}
+
 interface AgentInfo {
     id: string;
     name: string | null;
     workspace: string | null;
     agent_dir: string | null;
     model: string | null;
-    sandbox: boolean | null;
+    sandbox: SandboxConfig | null;
     heartbeat: string | null;
     default: boolean | null;
     subagents: SubagentConfig | null;
+    last_used_model?: string | null;
 }
 
 interface MatchRule {
@@ -93,6 +99,7 @@ export function Agents() {
     // Routing test state
     const [testResult, setTestResult] = useState<RoutingTestResult | null>(null);
     const [testingAccount, setTestingAccount] = useState<string | null>(null);
+    const [testPeerId, setTestPeerId] = useState('');
 
     // Form states
     const [agentForm, setAgentForm] = useState<AgentInfo>({
@@ -157,7 +164,14 @@ export function Agents() {
         if (!agentForm.id) return;
         setSaving(true);
         try {
-            await invoke('save_agent', { agent: agentForm });
+            const sandbox = agentForm.sandbox
+                ? { ...agentForm.sandbox, allowedPaths: agentForm.sandbox.allowedPaths.map(p => p.trim()).filter(Boolean) }
+                : null;
+            await invoke('save_agent', { agent: { ...agentForm, sandbox } });
+            // Remember the model being replaced so it can be restored later
+            if (editingAgent && editingAgent.model && editingAgent.model !== agentForm.model) {
+                await invoke('record_agent_last_used_model', { agentId: agentForm.id, model: editingAgent.model });
+            }
 
             setShowAgentDialog(false);
             fetchData();
@@ -224,7 +238,10 @@ export function Agents() {
     const handleTestRouting = async (accountId: string) => {
         setTestingAccount(accountId);
         try {
-            const result = await invoke<RoutingTestResult>('test_agent_routing', { accountId });
+            const result = await invoke<RoutingTestResult>('test_agent_routing', {
+                accountId,
+                peerId: testPeerId.trim() || null,
+            });
             setTestResult(result);
         } catch (e) {
             setError(String(e));
@@ -392,7 +409,7 @@ export function Agents() {
                                             <h3 className="font-medium text-white">{agent.name || agent.id}</h3>
                                             <div className="flex gap-1">
                                                 {agent.default && <span className="text-xs text-emerald-400 bg-emerald-500/10 px-1.5 rounded">Default</span>}
-                                                {agent.sandbox && <span className="text-xs text-amber-400 bg-amber-500/10 px-1.5 rounded">Sandbox</span>}
+                                                {agent.sandbox?.enabled && <span className="text-xs text-amber-400 bg-amber-500/10 px-1.5 rounded">Sandbox</span>}
                                             </div>
                                         </div>
                                     </div>
@@ -456,20 +473,30 @@ export function Agents() {
                         </h2>
                         <p className="text-sm text-gray-500">Route incoming messages to specific agents</p>
                     </div>
-                    <button
-                        onClick={() => {
-                            setBindingForm({
-                                agent_id: agents[0]?.id || '',
-                                match_rule: { channel: 'telegram', account_id: telegramAccounts[0]?.id || null, peer: null }
-                            });
-                            setShowBindingDialog(true);
-                        }}
-                        disabled={agents.length === 0}
-                        className="btn-secondary flex items-center gap-2"
-                    >
-                        <Plus size={16} />
-                        Add Rule
-                    </button>
+                    <div className="flex items-center gap-2">
+                        <input
+                            type="text"
+                            value={testPeerId}
+                            onChange={(e) => setTestPeerId(e.target.value)}
+                            placeholder="Test peer id (optional)"
+                            title="Peer (chat/user) id used when testing routing"
+                            className="input-base w-48 text-sm"
+                        />
+                        <button
+                            onClick={() => {
+                                setBindingForm({
+                                    agent_id: agents[0]?.id || '',
+                                    match_rule: { channel: 'telegram', account_id: telegramAccounts[0]?.id || null, peer: null }
+                                });
+                                setShowBindingDialog(true);
+                            }}
+                            disabled={agents.length === 0}
+                            className="btn-secondary flex items-center gap-2"
+                        >
+                            <Plus size={16} />
+                            Add Rule
+                        </button>
+                    </div>
                 </div>
 
                 <div className="bg-dark-700 rounded-xl border border-dark-600 overflow-hidden">
@@ -654,6 +681,15 @@ export function Agents() {
                                         className="input-base"
                                         placeholder="e.g. glm/glm-5"
                                     />
+                                    {agentForm.last_used_model && agentForm.last_used_model !== agentForm.model && (
+                                        <button
+                                            type="button"
+                                            onClick={() => setAgentForm({ ...agentForm, model: agentForm.last_used_model ?? null })}
+                                            className="text-xs text-claw-400 hover:text-claw-300 mt-1"
+                                        >
+                                            Restore last used: {agentForm.last_used_model}
+                                        </button>
+                                    )}
                                 </div>
 
 
@@ -661,12 +697,33 @@ export function Agents() {
                                     <input
                                         type="checkbox"
                                         id="sandbox"
-                                        checked={agentForm.sandbox || false}
-                                        onChange={e => setAgentForm({ ...agentForm, sandbox: e.target.checked })}
+                                        checked={agentForm.sandbox?.enabled || false}
+                                        onChange={e => setAgentForm({
+                                            ...agentForm,
+                                            sandbox: { enabled: e.target.checked, allowedPaths: agentForm.sandbox?.allowedPaths || [] },
+                                        })}
                                         className="w-4 h-4 rounded bg-dark-600 border-dark-500 text-claw-500 focus:ring-claw-500/50"
                                     />
                                     <label htmlFor="sandbox" className="text-sm text-gray-300 select-none">Enable Sandbox</label>
                                 </div>
+                                {agentForm.sandbox?.enabled && (
+                                    <div>
+                                        <label className="block text-sm text-gray-400 mb-1">Allowed Paths (one absolute path per line)</label>
+                                        <textarea
+                                            value={(agentForm.sandbox.allowedPaths || []).join('\n')}
+                                            onChange={e => setAgentForm({
+                                                ...agentForm,
+                                                sandbox: {
+                                                    enabled: true,
+                                                    allowedPaths: e.target.value.split('\n'),
+                                                },
+                                            })}
+                                            className="input-base font-mono text-sm"
+                                            rows={3}
+                                            placeholder="/home/user/projects"
+                                        />
+                                    </div>
+                                )}
                                 <div>
                                     <label className="block text-sm text-gray-400 mb-1">Heartbeat Interval (Optional)</label>
                                     <input
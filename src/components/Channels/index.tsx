@@ -29,6 +29,7 @@ import {
   Users,
 } from 'lucide-react';
 import clsx from 'clsx';
+import { api } from '../../lib/tauri';
 
 // Reusable component for DM Allowlist management with Fetch capability
 const DmAllowListEditor = ({
@@ -198,6 +199,14 @@ interface ChannelConfig {
   channel_type: string;
   enabled: boolean;
   config: Record<string, unknown>;
+  fields?: StoredChannelField[];
+}
+
+// Where a saved field is persisted
+interface StoredChannelField {
+  key: string;
+  value_masked: string;
+  source: 'env' | 'config';
 }
 
 // Channel configuration field definition
@@ -391,6 +400,7 @@ export function Channels() {
   const [loginLoading, setLoginLoading] = useState(false);
   const [clearing, setClearing] = useState(false);
   const [showClearConfirm, setShowClearConfirm] = useState(false);
+  const [clearPreview, setClearPreview] = useState<string[]>([]);
 
   // Per-group settings type
   interface GroupSettings {
@@ -434,7 +444,19 @@ export function Channels() {
   // Track which password fields are visible
   const [visiblePasswords, setVisiblePasswords] = useState<Set<string>>(new Set());
 
-  const togglePasswordVisibility = (fieldKey: string) => {
+  const togglePasswordVisibility = async (fieldKey: string) => {
+    // get_channels_config returns secrets masked; fetch the real value when revealing an unedited one
+    const channel = channels.find((c) => c.id === selectedChannel);
+    if (selectedChannel && !visiblePasswords.has(fieldKey) && channel && configForm[fieldKey] === channel.config[fieldKey]) {
+      try {
+        const secret = await invoke<string | null>('get_channel_secret', { channelId: selectedChannel, key: fieldKey });
+        if (secret !== null) {
+          setConfigForm((prev) => ({ ...prev, [fieldKey]: secret }));
+        }
+      } catch (e) {
+        console.error('Failed to read secret:', e);
+      }
+    }
     setVisiblePasswords((prev) => {
       const next = new Set(prev);
       if (next.has(fieldKey)) {
@@ -475,10 +497,22 @@ export function Channels() {
     }
   };
 
-  // Show clear confirmation
-  const handleShowClearConfirm = () => {
+  // Show clear confirmation, listing what will be removed
+  const handleShowClearConfirm = async () => {
     if (!selectedChannel) return;
-    setShowClearConfirm(true);
+    setClearing(true);
+    try {
+      setClearPreview(await api.previewClearChannelConfig(selectedChannel));
+      setShowClearConfirm(true);
+    } catch (e) {
+      setTestResult({
+        success: false,
+        message: 'Failed to preview clear',
+        error: String(e),
+      });
+    } finally {
+      setClearing(false);
+    }
   };
 
   // Execute clear channel config
@@ -491,7 +525,7 @@ export function Channels() {
     setShowClearConfirm(false);
     setClearing(true);
     try {
-      await invoke('clear_channel_config', { channelId: selectedChannel });
+      await api.clearChannelConfig(selectedChannel);
       // Clear form
       setConfigForm({});
       // Refresh list
@@ -987,6 +1021,12 @@ export function Channels() {
                           {configForm[field.key] && (
                             <span className="ml-2 text-green-500 text-xs">✓</span>
                           )}
+                          {field.type === 'password' &&
+                            currentChannel?.fields?.some(
+                              (f) => f.key === field.key && f.source === 'config' && !f.value_masked.startsWith('${')
+                            ) && (
+                              <span className="ml-2 text-yellow-500 text-xs">stored in plaintext in openclaw.json</span>
+                            )}
                         </label>
 
                         {field.type === 'select' ? (
@@ -1668,6 +1708,22 @@ export function Channels() {
                     )}
                   </div>
 
+                  {/* Changes the clear will make */}
+                  {showClearConfirm && (
+                    <div className="mt-4 p-4 rounded-xl bg-red-500/10 border border-red-500/30">
+                      <p className="text-sm text-red-300 mb-2">Clearing will make these changes:</p>
+                      {clearPreview.length > 0 ? (
+                        <ul className="text-xs text-gray-300 space-y-1 list-disc list-inside">
+                          {clearPreview.map((change) => (
+                            <li key={change}>{change}</li>
+                          ))}
+                        </ul>
+                      ) : (
+                        <p className="text-xs text-gray-400">Nothing is configured for this channel.</p>
+                      )}
+                    </div>
+                  )}
+
                   {/* Test result display */}
                   {testResult && (
                     <motion.div
@@ -9,8 +9,13 @@ import {
 } from 'lucide-react';
 import clsx from 'clsx';
 import { logStore, LogEntry } from '../../lib/logger';
+import { api } from '../../lib/tauri';
 
 type FilterLevel = 'all' | 'debug' | 'info' | 'warn' | 'error';
+type LogSource = 'app' | 'manager';
+
+const MANAGER_LOG_LINES = 500;
+const MANAGER_LOG_LEVELS = ['error', 'warn', 'info', 'debug', 'trace'];
 
 const LEVEL_COLORS: Record<string, string> = {
   debug: 'text-gray-400',
@@ -44,6 +49,32 @@ export function Logs() {
   const [moduleFilter, setModuleFilter] = useState<string>('all');
   const [autoScroll, setAutoScroll] = useState(true);
   const logsEndRef = useRef<HTMLDivElement>(null);
+  const [source, setSource] = useState<LogSource>('app');
+  const [managerLines, setManagerLines] = useState<string[]>([]);
+  const [managerLevel, setManagerLevel] = useState('info');
+
+  const loadManagerLogs = async () => {
+    try {
+      setManagerLines(await api.getRecentLogs(MANAGER_LOG_LINES));
+      setManagerLevel(await api.getLogLevel());
+    } catch (e) {
+      setManagerLines([`Failed to read Manager log: ${String(e)}`]);
+    }
+  };
+
+  const handleManagerLevel = async (level: string) => {
+    try {
+      await api.setLogLevel(level);
+      setManagerLevel(level);
+      loadManagerLogs();
+    } catch (e) {
+      alert('Failed to set log level: ' + String(e));
+    }
+  };
+
+  useEffect(() => {
+    if (source === 'manager') loadManagerLogs();
+  }, [source]);
 
   // Subscribe to log updates
   useEffect(() => {
@@ -60,7 +91,7 @@ export function Logs() {
     if (autoScroll && logsEndRef.current) {
       logsEndRef.current.scrollIntoView({ behavior: 'smooth' });
     }
-  }, [logs, autoScroll]);
+  }, [logs, managerLines, autoScroll]);
 
   // Filter logs
   const filteredLogs = logs.filter(log => {
@@ -128,76 +159,121 @@ export function Logs() {
     <div className="h-full flex flex-col overflow-hidden">
       {/* Toolbar */}
       <div className="flex items-center gap-4 mb-4 flex-wrap">
-        {/* Level filter */}
-        <div className="flex items-center gap-2">
-          <Filter size={14} className="text-gray-500" />
-          <select
-            value={filter}
-            onChange={(e) => setFilter(e.target.value as FilterLevel)}
-            className="bg-dark-700 border border-dark-500 rounded-lg px-3 py-1.5 text-sm text-gray-300"
-          >
-            <option value="all">All Levels</option>
-            <option value="debug">Debug</option>
-            <option value="info">Info</option>
-            <option value="warn">Warn</option>
-            <option value="error">Error</option>
-          </select>
+        {/* Source */}
+        <div className="flex rounded-lg border border-dark-500 overflow-hidden text-sm">
+          {(['app', 'manager'] as LogSource[]).map(s => (
+            <button
+              key={s}
+              onClick={() => setSource(s)}
+              className={clsx(
+                'px-3 py-1.5',
+                source === s ? 'bg-dark-500 text-white' : 'bg-dark-700 text-gray-400 hover:text-white'
+              )}
+            >
+              {s === 'app' ? 'Application' : 'Manager Backend'}
+            </button>
+          ))}
         </div>
 
-        {/* Module filter */}
-        <select
-          value={moduleFilter}
-          onChange={(e) => setModuleFilter(e.target.value)}
-          className="bg-dark-700 border border-dark-500 rounded-lg px-3 py-1.5 text-sm text-gray-300"
-        >
-          <option value="all">All Modules</option>
-          {modules.map(module => (
-            <option key={module} value={module}>{module}</option>
-          ))}
-        </select>
+        {source === 'manager' ? (
+          <>
+            <div className="flex items-center gap-2">
+              <Filter size={14} className="text-gray-500" />
+              <select
+                value={managerLevel}
+                onChange={(e) => handleManagerLevel(e.target.value)}
+                className="bg-dark-700 border border-dark-500 rounded-lg px-3 py-1.5 text-sm text-gray-300"
+                title="Backend log level (resets on restart)"
+              >
+                {MANAGER_LOG_LEVELS.map(level => (
+                  <option key={level} value={level}>{level.charAt(0).toUpperCase() + level.slice(1)}</option>
+                ))}
+              </select>
+            </div>
+            <div className="flex-1" />
+            <span className="text-xs text-gray-500">Last {managerLines.length} lines</span>
+            <button
+              onClick={loadManagerLogs}
+              className="icon-button text-gray-400 hover:text-white"
+              title="Refresh"
+            >
+              <RefreshCw size={16} />
+            </button>
+          </>
+        ) : (
+          <>
+            {/* Level filter */}
+            <div className="flex items-center gap-2">
+              <Filter size={14} className="text-gray-500" />
+              <select
+                value={filter}
+                onChange={(e) => setFilter(e.target.value as FilterLevel)}
+                className="bg-dark-700 border border-dark-500 rounded-lg px-3 py-1.5 text-sm text-gray-300"
+              >
+                <option value="all">All Levels</option>
+                <option value="debug">Debug</option>
+                <option value="info">Info</option>
+                <option value="warn">Warn</option>
+                <option value="error">Error</option>
+              </select>
+            </div>
 
-        <div className="flex-1" />
+            {/* Module filter */}
+            <select
+              value={moduleFilter}
+              onChange={(e) => setModuleFilter(e.target.value)}
+              className="bg-dark-700 border border-dark-500 rounded-lg px-3 py-1.5 text-sm text-gray-300"
+            >
+              <option value="all">All Modules</option>
+              {modules.map(module => (
+                <option key={module} value={module}>{module}</option>
+              ))}
+            </select>
 
-        {/* Statistics */}
-        <div className="flex items-center gap-3 text-xs text-gray-500">
-          <span>{filteredLogs.length} / {logs.length} entries</span>
-          <span className="text-red-400">{logs.filter(l => l.level === 'error').length} errors</span>
-          <span className="text-yellow-400">{logs.filter(l => l.level === 'warn').length} warnings</span>
-        </div>
+            <div className="flex-1" />
 
-        {/* Action buttons */}
-        <div className="flex items-center gap-2">
-          <label className="flex items-center gap-1 text-xs text-gray-400">
-            <input
-              type="checkbox"
-              checked={autoScroll}
-              onChange={(e) => setAutoScroll(e.target.checked)}
-              className="w-3 h-3 rounded"
-            />
-            Auto scroll
-          </label>
-          <button
-            onClick={handleExport}
-            className="icon-button text-gray-400 hover:text-white"
-            title="Export logs"
-          >
-            <Download size={16} />
-          </button>
-          <button
-            onClick={() => setLogs(logStore.getAll())}
-            className="icon-button text-gray-400 hover:text-white"
-            title="Refresh"
-          >
-            <RefreshCw size={16} />
-          </button>
-          <button
-            onClick={handleClear}
-            className="icon-button text-gray-400 hover:text-red-400"
-            title="Clear logs"
-          >
-            <Trash2 size={16} />
-          </button>
-        </div>
+            {/* Statistics */}
+            <div className="flex items-center gap-3 text-xs text-gray-500">
+              <span>{filteredLogs.length} / {logs.length} entries</span>
+              <span className="text-red-400">{logs.filter(l => l.level === 'error').length} errors</span>
+              <span className="text-yellow-400">{logs.filter(l => l.level === 'warn').length} warnings</span>
+            </div>
+
+            {/* Action buttons */}
+            <div className="flex items-center gap-2">
+              <label className="flex items-center gap-1 text-xs text-gray-400">
+                <input
+                  type="checkbox"
+                  checked={autoScroll}
+                  onChange={(e) => setAutoScroll(e.target.checked)}
+                  className="w-3 h-3 rounded"
+                />
+                Auto scroll
+              </label>
+              <button
+                onClick={handleExport}
+                className="icon-button text-gray-400 hover:text-white"
+                title="Export logs"
+              >
+                <Download size={16} />
+              </button>
+              <button
+                onClick={() => setLogs(logStore.getAll())}
+                className="icon-button text-gray-400 hover:text-white"
+                title="Refresh"
+              >
+                <RefreshCw size={16} />
+              </button>
+              <button
+                onClick={handleClear}
+                className="icon-button text-gray-400 hover:text-red-400"
+                title="Clear logs"
+              >
+                <Trash2 size={16} />
+              </button>
+            </div>
+          </>
+        )}
       </div>
 
       {/* Log list */}
@@ -205,12 +281,29 @@ export function Logs() {
         {/* Title bar */}
         <div className="flex items-center gap-2 px-4 py-2 bg-dark-700 border-b border-dark-600">
           <Terminal size={14} className="text-gray-500" />
-          <span className="text-xs text-gray-400 font-medium">Application Logs</span>
+          <span className="text-xs text-gray-400 font-medium">
+            {source === 'app' ? 'Application Logs' : 'Manager Backend Log'}
+          </span>
         </div>
 
         {/* Log content */}
         <div className="flex-1 overflow-y-auto p-2 font-mono text-xs">
-          {filteredLogs.length === 0 ? (
+          {source === 'manager' ? (
+            <>
+              {managerLines.map((line, i) => (
+                <div
+                  key={i}
+                  className={clsx(
+                    'py-0.5 px-2 whitespace-pre-wrap break-all',
+                    line.includes(' ERROR ') ? LEVEL_COLORS.error : line.includes(' WARN ') ? LEVEL_COLORS.warn : 'text-gray-300'
+                  )}
+                >
+                  {line}
+                </div>
+              ))}
+              <div ref={logsEndRef} />
+            </>
+          ) : filteredLogs.length === 0 ? (
             <div className="h-full flex items-center justify-center text-gray-500">
               <div className="text-center">
                 <Terminal size={32} className="mx-auto mb-2 opacity-50" />
@@ -1,9 +1,13 @@
 import { useState, useEffect } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
-import { api, MCPConfig, isTauri } from '../../lib/tauri';
-import { Plus, Trash2, Edit2, Save, Terminal, Blocks, AlertCircle, GitBranch, Loader2, Download, CheckCircle, Package, Plug, Globe, Zap } from 'lucide-react';
+import { api, MCPConfig, McporterStatus, ToolStatus, isTauri } from '../../lib/tauri';
+import { Plus, Trash2, Edit2, Save, Terminal, Blocks, AlertCircle, GitBranch, Loader2, Download, CheckCircle, XCircle, Package, Plug, Globe, Zap } from 'lucide-react';
 import clsx from 'clsx';
 
+// Environment variable names of a stdio server (remote servers have none)
+const envKeys = (config: MCPConfig) =>
+    config.transport === 'stdio' ? Object.keys(config.env || {}) : [];
+
 export function MCP() {
     const [configs, setConfigs] = useState<Record<string, MCPConfig>>({});
     const [loading, setLoading] = useState(true);
@@ -20,9 +24,11 @@ export function MCP() {
     const [installing, setInstalling] = useState(false);
     const [installProgress, setInstallProgress] = useState('');
     const [installMode, setInstallMode] = useState<'plugin' | 'source'>('source');
+    const [prerequisites, setPrerequisites] = useState<ToolStatus[] | null>(null);
 
     // mcporter state
     const [mcporterInstalled, setMcporterInstalled] = useState<boolean | null>(null);
+    const [mcporterStatus, setMcporterStatus] = useState<McporterStatus | null>(null);
     const [installingMcporter, setInstallingMcporter] = useState(false);
 
     // Form state
@@ -33,6 +39,7 @@ export function MCP() {
         args: string;
         env: string;
         url: string;
+        headers: string;
         enabled: boolean;
     }>({
         name: '',
@@ -41,6 +48,7 @@ export function MCP() {
         args: '',
         env: '',
         url: '',
+        headers: '',
         enabled: true,
     });
 
@@ -64,8 +72,9 @@ export function MCP() {
     const checkMcporter = async () => {
         if (!isTauri()) return;
         try {
-            const installed = await api.checkMcporterInstalled();
-            setMcporterInstalled(installed);
+            const status = await api.checkMcporterInstalled();
+            setMcporterStatus(status);
+            setMcporterInstalled(status.installed);
         } catch (e) {
             console.error('Failed to check mcporter:', e);
         }
@@ -76,7 +85,7 @@ export function MCP() {
         setError(null);
         try {
             await api.installMcporter();
-            setMcporterInstalled(true);
+            await checkMcporter();
             setSuccess('mcporter installed successfully!');
         } catch (e) {
             setError(`Failed to install mcporter: ${e}`);
@@ -90,6 +99,13 @@ export function MCP() {
         checkMcporter();
     }, []);
 
+    // Check git/node/npm/npx when the install dialog opens
+    useEffect(() => {
+        if (showInstallDialog && isTauri()) {
+            api.checkPrerequisites().then(setPrerequisites).catch(() => setPrerequisites(null));
+        }
+    }, [showInstallDialog]);
+
     // Auto-clear success messages
     useEffect(() => {
         if (success) {
@@ -100,16 +116,17 @@ export function MCP() {
 
     const handleEdit = (id: string) => {
         const config = configs[id];
-        const isRemote = !!config.url;
+        const isRemote = config.transport === 'http';
         setEditingId(id);
         setIsNew(false);
         setFormData({
             name: id,
             serverType: isRemote ? 'remote' : 'local',
-            command: config.command || '',
-            args: (config.args || []).join(' '),
-            env: Object.entries(config.env || {}).map(([k, v]) => `${k}=${v}`).join('\n'),
-            url: config.url || '',
+            command: isRemote ? '' : config.command,
+            args: isRemote ? '' : (config.args || []).join(' '),
+            env: isRemote ? '' : Object.entries(config.env || {}).map(([k, v]) => `${k}=${v}`).join('\n'),
+            url: isRemote ? config.url : '',
+            headers: isRemote ? Object.entries(config.headers || {}).map(([k, v]) => `${k}: ${v}`).join('\n') : '',
             enabled: config.enabled,
         });
     };
@@ -124,6 +141,7 @@ export function MCP() {
             args: '',
             env: '',
             url: '',
+            headers: '',
             enabled: true,
         });
     };
@@ -143,15 +161,17 @@ export function MCP() {
             return copy;
         });
         try {
-            if (config.url) {
+            const result = config.transport === 'http'
                 // Remote URL test
-                const result = await api.testMCPServer('url', config.url);
-                setTestResult(prev => ({ ...prev, [id]: { ok: true, msg: result } }));
-            } else {
+                ? await api.testMCPServer('url', config.url)
                 // Local stdio test — pass command and args separately to preserve paths
-                const result = await api.testMCPServer('stdio', id, config.command || '', config.args || []);
-                setTestResult(prev => ({ ...prev, [id]: { ok: true, msg: result } }));
-            }
+                : await api.testMCPServer('stdio', id, config.command, config.args || []);
+            const details = [
+                result.protocol_version && `Protocol: ${result.protocol_version}`,
+                result.capabilities.length > 0 && `Capabilities: ${result.capabilities.join(', ')}`,
+            ].filter(Boolean).join('\n');
+            const msg = details ? `${result.message}\n${details}` : result.message;
+            setTestResult(prev => ({ ...prev, [id]: { ok: true, msg } }));
         } catch (e) {
             setTestResult(prev => ({ ...prev, [id]: { ok: false, msg: String(e) } }));
         } finally {
@@ -188,8 +208,19 @@ export function MCP() {
             let config: MCPConfig;
 
             if (formData.serverType === 'remote') {
+                const headers: Record<string, string> = {};
+                formData.headers.split('\n').forEach(line => {
+                    const idx = line.indexOf(':');
+                    if (idx > 0) {
+                        const key = line.slice(0, idx).trim();
+                        if (key) headers[key] = line.slice(idx + 1).trim();
+                    }
+                });
+
                 config = {
+                    transport: 'http',
                     url: formData.url.trim(),
+                    headers,
                     enabled: formData.enabled,
                 };
             } else {
@@ -208,6 +239,7 @@ export function MCP() {
                     : [];
 
                 config = {
+                    transport: 'stdio',
                     command: formData.command,
                     args,
                     env,
@@ -215,7 +247,7 @@ export function MCP() {
                 };
             }
 
-            await api.saveMCPConfig(formData.name, config);
+            await api.saveMCPConfig(formData.name, config, isNew);
             setEditingId(null);
             setIsNew(false);
             setError(null);
@@ -238,8 +270,13 @@ export function MCP() {
         try {
             let result: string;
             if (installMode === 'plugin') {
+                if (!(await api.openclawAvailable())) {
+                    setError('OpenClaw CLI not found on PATH — install OpenClaw first, or use the Git install mode.');
+                    return;
+                }
                 setInstallProgress('Installing via OpenClaw plugins system...');
-                result = await api.installMCPPlugin(gitUrl.trim());
+                const installed = await api.installMCPPlugin(gitUrl.trim());
+                result = `Installed plugin: ${installed.plugin}`;
             } else {
                 setInstallProgress('Cloning repository and building from source...');
                 result = await api.installMCPFromGit(gitUrl.trim());
@@ -340,13 +377,20 @@ export function MCP() {
                                 'text-sm font-medium',
                                 mcporterInstalled ? 'text-green-200' : 'text-amber-200'
                             )}>
-                                {mcporterInstalled ? 'mcporter is installed' : 'mcporter is required for MCP support'}
+                                {mcporterInstalled
+                                    ? `mcporter is installed${mcporterStatus?.version ? ` (v${mcporterStatus.version})` : ''}`
+                                    : 'mcporter is required for MCP support'}
                             </p>
                             <p className="text-xs text-gray-500">
                                 {mcporterInstalled
                                     ? 'OpenClaw can use MCP servers via the mcporter skill'
                                     : 'Install mcporter to enable MCP server integration with OpenClaw agents'}
                             </p>
+                            {mcporterStatus?.update_available && (
+                                <p className="text-xs text-amber-400">
+                                    Update available: v{mcporterStatus.latest_version}
+                                </p>
+                            )}
                         </div>
                     </div>
                     {mcporterInstalled ? (
@@ -469,6 +513,19 @@ export function MCP() {
                         </p>
 
                         <div className="space-y-4">
+                            {installMode === 'source' && prerequisites && (
+                                <div className="grid grid-cols-2 gap-2">
+                                    {prerequisites.map(tool => (
+                                        <div key={tool.name} className="flex items-center gap-2 text-xs">
+                                            {tool.found
+                                                ? <CheckCircle size={14} className="text-green-400" />
+                                                : <XCircle size={14} className="text-red-400" />}
+                                            <span className="font-mono text-gray-300">{tool.name}</span>
+                                            <span className="text-gray-500">{tool.found ? (tool.version || 'installed') : 'not found'}</span>
+                                        </div>
+                                    ))}
+                                </div>
+                            )}
                             <div>
                                 <label className="block text-sm font-medium text-gray-400 mb-2">Repository URL</label>
                                 <div className="relative">
@@ -511,11 +568,18 @@ export function MCP() {
 
                             <div className="flex items-center justify-end gap-2">
                                 <button
-                                    onClick={() => { setShowInstallDialog(false); setGitUrl(''); setInstallProgress(''); }}
-                                    disabled={installing}
+                                    onClick={() => {
+                                        if (installing) {
+                                            // Source installs can be stopped; plugin installs run to completion
+                                            api.cancelInstall(gitUrl.trim()).catch(e => setError(String(e)));
+                                            return;
+                                        }
+                                        setShowInstallDialog(false); setGitUrl(''); setInstallProgress('');
+                                    }}
+                                    disabled={installing && installMode === 'plugin'}
                                     className="px-4 py-2 text-gray-400 hover:text-white hover:bg-dark-600 rounded-lg transition-colors disabled:opacity-50"
                                 >
-                                    Cancel
+                                    {installing ? 'Stop' : 'Cancel'}
                                 </button>
                                 <button
                                     onClick={handleInstall}
@@ -645,6 +709,15 @@ export function MCP() {
                                         />
                                     </div>
                                     <p className="mt-1 text-xs text-gray-500">HTTP/HTTPS endpoint for the remote MCP server</p>
+
+                                    <label className="block text-sm font-medium text-gray-400 mt-4 mb-2">Headers</label>
+                                    <textarea
+                                        value={formData.headers}
+                                        onChange={(e) => setFormData({ ...formData, headers: e.target.value })}
+                                        placeholder={'Authorization: Bearer ${MY_TOKEN}\nX-Custom-Header: value'}
+                                        rows={3}
+                                        className="w-full bg-dark-800 border border-dark-600 rounded-xl px-4 py-3 text-white focus:ring-2 focus:ring-blue-500 focus:border-transparent outline-none font-mono text-sm resize-none"
+                                    />
                                 </div>
                             ) : (
                                 <>
@@ -769,26 +842,34 @@ export function MCP() {
 
                                     <div className="space-y-2">
                                         <div className="bg-dark-800/50 rounded-lg px-3 py-2 border border-dark-600/50 font-mono text-xs text-gray-400 truncate">
-                                            {config.url ? (
+                                            {config.transport === 'http' ? (
                                                 <><Globe size={12} className="inline mr-1.5 text-blue-400" />{config.url}</>
                                             ) : (
                                                 <><span className="text-claw-500">$</span> {config.command} {(config.args || []).join(' ')}</>
                                             )}
                                         </div>
-                                        {Object.keys(config.env || {}).length > 0 && (
+                                        {envKeys(config).length > 0 && (
                                             <div className="flex flex-wrap gap-2">
-                                                {Object.keys(config.env || {}).slice(0, 3).map(key => (
+                                                {envKeys(config).slice(0, 3).map(key => (
                                                     <span key={key} className="px-2 py-0.5 rounded text-[10px] bg-dark-600 text-gray-400 border border-dark-500">
                                                         {key}
                                                     </span>
                                                 ))}
-                                                {Object.keys(config.env || {}).length > 3 && (
+                                                {envKeys(config).length > 3 && (
                                                     <span className="px-2 py-0.5 rounded text-[10px] bg-dark-600 text-gray-500 border border-dark-500">
-                                                        +{Object.keys(config.env || {}).length - 3}
+                                                        +{envKeys(config).length - 3}
                                                     </span>
                                                 )}
                                             </div>
                                         )}
+                                        {config.sourceUrl && (
+                                            <p className="text-[11px] text-gray-500 truncate" title={config.sourceUrl}>
+                                                <GitBranch size={11} className="inline mr-1" />
+                                                {config.sourceUrl}
+                                                {config.gitRef && <span className="font-mono"> @ {config.gitRef.slice(0, 7)}</span>}
+                                                {config.installedAt && <> · installed {new Date(config.installedAt).toLocaleDateString()}</>}
+                                            </p>
+                                        )}
                                     </div>
                                     {testResult[id] && (
                                         <div className={clsx(
@@ -17,10 +17,11 @@ import {
   Clock,
   Server,
   FileJson,
-  GitMerge
+  GitMerge,
+  KeyRound
 } from 'lucide-react';
 import { appLogger } from '../../lib/logger';
-import { isTauri } from '../../lib/tauri';
+import { isTauri, api, EnvEntry } from '../../lib/tauri';
 
 interface InstallResult {
   success: boolean;
@@ -41,6 +42,12 @@ interface WebConfig {
   brave_api_key: string | null;
 }
 
+interface ProxyConfig {
+  url: string | null;
+  no_proxy: string | null;
+  env_proxy: string | null;
+}
+
 interface CompactionConfig {
   enabled: boolean;
   threshold: number | null;
@@ -50,6 +57,7 @@ interface CompactionConfig {
 
 interface WorkspaceConfig {
   workspace: string | null;
+  workspace_exists?: boolean;
   timezone: string | null;
   time_format: string | null;
   skip_bootstrap: boolean;
@@ -91,20 +99,26 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
   // Config States
   const [browser, setBrowser] = useState<BrowserConfig>({ enabled: true, color: null });
   const [webConfig, setWebConfig] = useState<WebConfig>({ brave_api_key: null });
+  const [proxy, setProxy] = useState<ProxyConfig>({ url: null, no_proxy: null, env_proxy: null });
   const [compaction, setCompaction] = useState<CompactionConfig>({ enabled: false, threshold: null, context_pruning: false, max_context_messages: null });
   const [workspace, setWorkspace] = useState<WorkspaceConfig>({ workspace: null, timezone: null, time_format: null, skip_bootstrap: false, bootstrap_max_chars: null });
   const [gateway, setGateway] = useState<GatewayConfig>({ port: 3000, log_level: 'info' });
   const [subagentDefaults, setSubagentDefaults] = useState<SubagentDefaults>({ max_spawn_depth: null, max_children_per_agent: null, max_concurrent: null });
   const [appVersion, setAppVersion] = useState<string>('...');
+  const [envEntries, setEnvEntries] = useState<EnvEntry[]>([]);
+  const [selectedEnvKeys, setSelectedEnvKeys] = useState<string[]>([]);
+  const [configHasComments, setConfigHasComments] = useState(false);
+  const [configScope, setConfigScope] = useState<string | null>(null);
 
   // Load initial data
   useEffect(() => {
     const loadConfig = async () => {
       setLoading(true);
       try {
-        const [br, web, comp, ws, gw, sub] = await Promise.all([
+        const [br, web, px, comp, ws, gw, sub] = await Promise.all([
           invoke<BrowserConfig>('get_browser_config'),
           invoke<WebConfig>('get_web_config'),
+          invoke<ProxyConfig>('get_proxy_config'),
           invoke<CompactionConfig>('get_compaction_config'),
           invoke<WorkspaceConfig>('get_workspace_config'),
           invoke<GatewayConfig>('get_gateway_config'),
@@ -112,10 +126,14 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
         ]);
         setBrowser(br);
         setWebConfig(web);
+        setProxy(px);
         setCompaction(comp);
         setWorkspace(ws);
         setGateway(gw);
         setSubagentDefaults(sub);
+        setEnvEntries(await api.listEnvKeys());
+        setConfigHasComments(await api.configHasComments());
+        setConfigScope(await api.getActiveConfigScope());
 
         if (isTauri()) {
           const { getVersion } = await import('@tauri-apps/api/app');
@@ -131,12 +149,18 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
   }, []);
 
   const handleSave = async () => {
+    // The backend only creates a missing workspace directory when asked to
+    const workspacePath = workspace.workspace?.trim();
+    const createWorkspace = !!workspacePath && !workspace.workspace_exists
+      && confirm(`Create the workspace directory ${workspacePath} if it does not exist?`);
+
     setSaving(true);
     setSaveSuccess(false);
     try {
       await Promise.all([
         invoke('save_browser_config', { enabled: browser.enabled, color: browser.color }),
         invoke('save_web_config', { braveApiKey: webConfig.brave_api_key }),
+        invoke('save_proxy_config', { url: proxy.url, noProxy: proxy.no_proxy }),
         invoke('save_compaction_config', {
           enabled: compaction.enabled,
           threshold: compaction.threshold,
@@ -148,12 +172,14 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
           timezone: workspace.timezone,
           timeFormat: workspace.time_format,
           skipBootstrap: workspace.skip_bootstrap,
-          bootstrapMaxChars: workspace.bootstrap_max_chars
+          bootstrapMaxChars: workspace.bootstrap_max_chars,
+          create: createWorkspace
         }),
         invoke('save_gateway_config', { port: gateway.port, logLevel: gateway.log_level }),
         invoke('save_subagent_defaults', { defaults: subagentDefaults }),
       ]);
 
+      if (createWorkspace) setWorkspace(w => ({ ...w, workspace_exists: true }));
       setSaveSuccess(true);
       setTimeout(() => setSaveSuccess(false), 2000);
     } catch (e) {
@@ -164,6 +190,34 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
     }
   };
 
+  const handleDeleteEnv = async (key: string) => {
+    if (!confirm(`Remove ${key} from the env file?`)) return;
+    try {
+      await api.deleteEnvValue(key);
+      setEnvEntries(await api.listEnvKeys());
+      setSelectedEnvKeys((keys) => keys.filter((k) => k !== key));
+    } catch (e) {
+      alert('Failed to remove environment variable: ' + String(e));
+    }
+  };
+
+  const toggleEnvKey = (key: string) => {
+    setSelectedEnvKeys((keys) => (keys.includes(key) ? keys.filter((k) => k !== key) : [...keys, key]));
+  };
+
+  const handleExportEnv = async () => {
+    try {
+      const path = await save({ defaultPath: '.env' });
+      if (!path) return;
+      const includeSecrets = confirm(
+        'Write the secret values into the exported file?\n\nOK: include values (keep the file private)\nCancel: export the keys only, as a template'
+      );
+      alert(await api.exportEnvFile(path, selectedEnvKeys, includeSecrets));
+    } catch (e) {
+      alert('Failed to export environment variables: ' + String(e));
+    }
+  };
+
   const handleExport = async () => {
     try {
       const path = await save({
@@ -207,6 +261,22 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
     }
   };
 
+  const handleChangeScope = async (useProject: boolean) => {
+    try {
+      let path: string | null = null;
+      if (useProject) {
+        const selected = await open({ directory: true });
+        if (typeof selected !== 'string') return;
+        path = selected;
+      }
+      await api.setActiveConfigScope(path);
+      // Every section reads openclaw.json, so reload them from the new scope
+      window.location.reload();
+    } catch (e) {
+      alert('Failed to switch configuration: ' + String(e));
+    }
+  };
+
   const handleUninstall = async () => {
     setUninstalling(true);
     setUninstallResult(null);
@@ -386,11 +456,26 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
             </div>
             <div>
               <h3 className="text-lg font-semibold text-white">Workspace</h3>
-              <p className="text-xs text-gray-500">Time and localization settings</p>
+              <p className="text-xs text-gray-500">Workspace directory, time and localization settings</p>
             </div>
           </div>
 
           <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+            <div className="md:col-span-2">
+              <label className="block text-sm text-gray-400 mb-2">Workspace Directory</label>
+              <input
+                type="text"
+                value={workspace.workspace || ''}
+                onChange={e => setWorkspace({ ...workspace, workspace: e.target.value || null, workspace_exists: false })}
+                placeholder="~/.openclaw/workspace"
+                className="input-base"
+              />
+              {workspace.workspace && !workspace.workspace_exists && (
+                <p className="text-xs text-yellow-500/80 mt-1 flex items-center gap-1">
+                  <AlertTriangle size={12} /> You will be asked to create this directory on save
+                </p>
+              )}
+            </div>
             <div>
               <label className="block text-sm text-gray-400 mb-2">Timezone</label>
               <select
@@ -530,6 +615,35 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
             </div>
           </div>
 
+          {configHasComments && (
+            <div className="flex items-start gap-2 mb-4 p-3 bg-yellow-500/10 border border-yellow-500/30 rounded-lg text-xs text-yellow-300">
+              <AlertTriangle size={14} className="mt-0.5 shrink-0" />
+              <span>
+                openclaw.json contains comments. Saving from the Manager removes them; the annotated
+                original is backed up as openclaw.json.bak-&lt;timestamp&gt; on the first save.
+              </span>
+            </div>
+          )}
+
+          <div className="flex items-center justify-between mb-4 p-3 bg-dark-600 rounded-lg">
+            <div className="min-w-0">
+              <p className="text-sm text-white">Active openclaw.json</p>
+              <p className="text-xs text-gray-500 font-mono truncate">
+                {configScope ? `${configScope} (project)` : 'Global (~/.openclaw)'}
+              </p>
+            </div>
+            <div className="flex gap-2 shrink-0">
+              <button onClick={() => handleChangeScope(true)} className="btn-secondary text-xs px-3 py-1.5">
+                Open Project…
+              </button>
+              {configScope && (
+                <button onClick={() => handleChangeScope(false)} className="btn-secondary text-xs px-3 py-1.5">
+                  Use Global
+                </button>
+              )}
+            </div>
+          </div>
+
           <div className="flex gap-4">
             <button
               onClick={handleExport}
@@ -548,6 +662,60 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
           </div>
         </div>
 
+        {/* Stored Environment Variables */}
+        <div className="bg-dark-700 rounded-2xl p-6 border border-dark-500">
+          <div className="flex items-center gap-3 mb-6">
+            <div className="w-10 h-10 rounded-xl bg-amber-500/20 flex items-center justify-center">
+              <KeyRound size={20} className="text-amber-400" />
+            </div>
+            <div>
+              <h3 className="text-lg font-semibold text-white">Environment Variables</h3>
+              <p className="text-xs text-gray-500">Secrets stored in ~/.openclaw/env</p>
+            </div>
+            {envEntries.length > 0 && (
+              <button
+                onClick={handleExportEnv}
+                disabled={selectedEnvKeys.length === 0}
+                className="ml-auto flex items-center gap-2 px-3 py-2 bg-dark-600 hover:bg-dark-500 rounded-lg text-sm text-white border border-dark-500 disabled:opacity-50 transition-colors"
+                title="Export the selected variables to a .env file for a headless server"
+              >
+                <Download size={14} />
+                Export .env ({selectedEnvKeys.length})
+              </button>
+            )}
+          </div>
+
+          {envEntries.length === 0 ? (
+            <p className="text-sm text-gray-500">No variables stored</p>
+          ) : (
+            <div className="space-y-2">
+              {envEntries.map((entry) => (
+                <div key={entry.key} className="flex items-center justify-between p-3 bg-dark-600 rounded-lg">
+                  <label className="flex items-center gap-3 min-w-0 cursor-pointer">
+                    <input
+                      type="checkbox"
+                      checked={selectedEnvKeys.includes(entry.key)}
+                      onChange={() => toggleEnvKey(entry.key)}
+                      className="w-4 h-4 rounded border-dark-500 bg-dark-700"
+                    />
+                    <div className="min-w-0">
+                      <p className="text-sm text-white font-mono truncate">{entry.key}</p>
+                      <p className="text-xs text-gray-500 font-mono">{entry.masked_value}</p>
+                    </div>
+                  </label>
+                  <button
+                    onClick={() => handleDeleteEnv(entry.key)}
+                    className="p-2 text-gray-400 hover:text-red-400 hover:bg-dark-500 rounded-lg transition-colors"
+                    title="Remove"
+                  >
+                    <Trash2 size={16} />
+                  </button>
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+
         {/* Browser Control */}
         <div className="bg-dark-700 rounded-2xl p-6 border border-dark-500">
           <div className="flex items-center gap-3 mb-6">
@@ -619,6 +787,49 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
           </div>
         </div>
 
+        {/* Network Proxy */}
+        <div className="bg-dark-700 rounded-2xl p-6 border border-dark-500">
+          <div className="flex items-center gap-3 mb-6">
+            <div className="w-10 h-10 rounded-xl bg-cyan-500/20 flex items-center justify-center">
+              <Server size={20} className="text-cyan-400" />
+            </div>
+            <div>
+              <h3 className="text-lg font-semibold text-white">Network Proxy</h3>
+              <p className="text-xs text-gray-500">Used for model fetching, provider checks and MCP URL tests</p>
+            </div>
+          </div>
+
+          <div className="space-y-4">
+            <div>
+              <label className="block text-sm text-gray-400 mb-2">Proxy URL</label>
+              <input
+                type="text"
+                value={proxy.url || ''}
+                onChange={e => setProxy({ ...proxy, url: e.target.value || null })}
+                placeholder={proxy.env_proxy || 'http://proxy.example.com:8080'}
+                className="input-base"
+              />
+              <p className="text-xs text-gray-500 mt-1">
+                {proxy.env_proxy && !proxy.url
+                  ? 'Leave empty to keep using the proxy from HTTPS_PROXY / HTTP_PROXY.'
+                  : 'Leave empty to use the HTTPS_PROXY / HTTP_PROXY environment variables.'}
+              </p>
+            </div>
+            {proxy.url && (
+              <div>
+                <label className="block text-sm text-gray-400 mb-2">Bypass Proxy For</label>
+                <input
+                  type="text"
+                  value={proxy.no_proxy || ''}
+                  onChange={e => setProxy({ ...proxy, no_proxy: e.target.value || null })}
+                  placeholder="localhost,127.0.0.1,.internal.example.com"
+                  className="input-base"
+                />
+              </div>
+            )}
+          </div>
+        </div>
+
         {/* Manager Update */}
         <div className="bg-dark-700 rounded-2xl p-6 border border-dark-500">
           <div className="flex items-center gap-3 mb-6">
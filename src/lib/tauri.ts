@@ -81,6 +81,25 @@ export interface SuggestedModel {
   recommended: boolean;
 }
 
+// API type accepted for a provider/model (get_supported_api_types)
+export interface ApiTypeInfo {
+  id: string;
+  name: string;
+  auth_style: string;
+  auth_param: string | null;
+  list_models_endpoint: string | null;
+  supports_fetch: boolean;
+  supports_test: boolean;
+}
+
+// Suggested model already present in a configured provider (get_suggested_model_status)
+export interface SuggestedModelStatus {
+  provider_id: string;
+  model_id: string;
+  configured: boolean;
+  configured_in: string[];
+}
+
 // Configured Provider
 export interface ConfiguredProvider {
   name: string;
@@ -98,13 +117,56 @@ export interface ConfiguredModel {
   context_window: number | null;
   max_tokens: number | null;
   is_primary: boolean;
+  is_available: boolean;
+  reasoning: boolean | null;
+  cost: ModelCost | null;
+}
+
+// Stored model pricing (as written to openclaw.json)
+export interface ModelCost {
+  input: number;
+  output: number;
+  cacheRead: number;
+  cacheWrite: number;
+}
+
+// Entry of agents.defaults.models; exists = false means it points at a missing provider/model
+export interface AvailableModel {
+  full_id: string;
+  exists: boolean;
+  provider_exists: boolean;
+}
+
+// What prune_config removed
+export interface PruneReport {
+  removed_models: string[];
+  removed_bindings: string[];
+  cleared_primary: string | null;
+}
+
+// Result of probing one provider (check_all_providers)
+export interface ProviderHealth {
+  name: string;
+  reachable: boolean;
+  latency_ms: number | null;
+  error: string | null;
+  cached: boolean;
+}
+
+// One difference reported by diff_config
+export interface ConfigChange {
+  pointer: string;
+  kind: 'added' | 'removed' | 'modified';
+  old_value: unknown | null;
+  new_value: unknown | null;
 }
 
 // AI configuration overview
 export interface AIConfigOverview {
   primary_model: string | null;
+  fallback_models: string[];
   configured_providers: ConfiguredProvider[];
-  available_models: string[];
+  available_models: AvailableModel[];
 }
 
 // Model configuration
@@ -125,6 +187,14 @@ export interface ChannelConfig {
   channel_type: string;
   enabled: boolean;
   config: Record<string, unknown>;
+  fields?: StoredChannelField[];
+}
+
+// Channel field and where it is persisted (get_channels_config)
+export interface StoredChannelField {
+  key: string;
+  value_masked: string;
+  source: 'env' | 'config';
 }
 
 // Diagnostic result
@@ -145,13 +215,104 @@ export interface AITestResult {
   latency_ms: number | null;
 }
 
-// MCP Configuration
-export interface MCPConfig {
-  command?: string;
-  args?: string[];
-  env?: Record<string, string>;
-  url?: string;
-  enabled: boolean;
+// Where an MCP server was installed from (set by install_mcp_from_git)
+export interface McpSource {
+  sourceUrl?: string;
+  installedAt?: string;
+  gitRef?: string;
+}
+
+// MCP Configuration (tagged by transport)
+export type MCPConfig = (
+  | {
+      transport: 'stdio';
+      command: string;
+      args?: string[];
+      env?: Record<string, string>;
+      enabled: boolean;
+    }
+  | {
+      transport: 'http';
+      url: string;
+      headers?: Record<string, string>;
+      enabled: boolean;
+    }
+) & McpSource;
+
+export interface ConfigSetResult {
+  key: string;
+  value: string;
+  stdout: string;
+  stderr: string;
+  exit_code: number | null;
+}
+
+export interface PluginInfo {
+  name: string;
+  version: string | null;
+  line: string;
+}
+
+export interface PluginInstallResult {
+  source: string;
+  plugin: string;
+  stdout: string;
+  stderr: string;
+  exit_code: number | null;
+}
+
+export interface MCPTestResult {
+  handshake: boolean;
+  message: string;
+  protocol_version: string | null;
+  server_name: string | null;
+  server_version: string | null;
+  capabilities: string[];
+  http_status: number | null;
+}
+
+// Availability of a command-line tool (check_prerequisites)
+export interface ToolStatus {
+  name: string;
+  found: boolean;
+  version: string | null;
+}
+
+// mcporter install state (check_mcporter_installed)
+export interface McporterStatus {
+  installed: boolean;
+  version: string | null;
+  latest_version: string | null;
+  update_available: boolean;
+}
+
+// Resolved path and whether it exists
+export interface ResolvedPath {
+  path: string;
+  exists: boolean;
+}
+
+export interface ConfigPaths {
+  config_dir: ResolvedPath;
+  config_file: ResolvedPath;
+  env_file: ResolvedPath;
+  mcp_config_file: ResolvedPath;
+  mcporter_config_file: ResolvedPath;
+  mcp_install_dir: ResolvedPath;
+}
+
+// Key stored in the env file (list_env_keys)
+export interface EnvEntry {
+  key: string;
+  masked_value: string;
+}
+
+// Gateway mode and auth state (token itself is not exposed)
+export interface GatewayStatus {
+  mode: string | null;
+  auth_mode: string | null;
+  has_token: boolean;
+  port: number;
 }
 
 // Skill
@@ -174,20 +335,43 @@ export const api = {
   // System information
   getSystemInfo: () => invokeWithLog<SystemInfo>('get_system_info'),
   checkOpenclawInstalled: () => invokeWithLog<boolean>('check_openclaw_installed'),
+  openclawAvailable: () => invokeWithLog<boolean>('openclaw_available'),
   getOpenclawVersion: () => invokeWithLog<string | null>('get_openclaw_version'),
 
   // Configuration management
   getConfig: () => invokeWithLog<unknown>('get_config'),
+  getConfigReadonly: () => invokeWithLog<unknown>('get_config_readonly'),
+  initializeConfig: () => invokeWithLog<string>('initialize_config'),
+  configHasComments: () => invokeWithLog<boolean>('config_has_comments'),
+  getDashboardUrl: (createToken?: boolean) =>
+    invokeWithLog<string>('get_dashboard_url', { createToken: createToken ?? null }),
+  getConfigPaths: () => invokeWithLog<ConfigPaths>('get_config_paths'),
+  getActiveConfigScope: () => invokeWithLog<string | null>('get_active_config_scope'),
+  setActiveConfigScope: (path: string | null) =>
+    invokeWithLog<string>('set_active_config_scope', { path }),
+  getGatewayStatus: () => invokeWithLog<GatewayStatus>('get_gateway_status'),
+  setGatewayAuth: (mode: 'token' | 'none') =>
+    invokeWithLog<GatewayStatus>('set_gateway_auth', { mode }),
   saveConfig: (config: unknown) => invokeWithLog<string>('save_config', { config }),
+  saveConfigValidated: (config: unknown) =>
+    invokeWithLog<string>('save_config_validated', { config }),
+  diffConfig: (proposed: unknown) => invokeWithLog<ConfigChange[]>('diff_config', { proposed }),
   getEnvValue: (key: string) => invokeWithLog<string | null>('get_env_value', { key }),
   saveEnvValue: (key: string, value: string) =>
     invokeWithLog<string>('save_env_value', { key, value }),
+  listEnvKeys: () => invokeWithLog<EnvEntry[]>('list_env_keys'),
+  deleteEnvValue: (key: string) => invokeWithLog<string>('delete_env_value', { key }),
+  exportEnvFile: (destPath: string, include: string[], includeSecrets?: boolean) =>
+    invokeWithLog<string>('export_env_file', { destPath, include, includeSecrets }),
 
   // AI Provider (legacy compatibility)
   getAIProviders: () => invokeWithLog<AIProviderOption[]>('get_ai_providers'),
 
   // AI Configuration (new version)
   getOfficialProviders: () => invokeWithLog<OfficialProvider[]>('get_official_providers'),
+  getSupportedApiTypes: () => invokeWithLog<ApiTypeInfo[]>('get_supported_api_types'),
+  reloadOfficialProviders: () => invokeWithLog<OfficialProvider[]>('reload_official_providers'),
+  getSuggestedModelStatus: () => invokeWithLog<SuggestedModelStatus[]>('get_suggested_model_status'),
   getAIConfig: () => invokeWithLog<AIConfigOverview>('get_ai_config'),
   saveProvider: (
     providerName: string,
@@ -205,38 +389,71 @@ export const api = {
     }),
   deleteProvider: (providerName: string) =>
     invokeWithLog<string>('delete_provider', { providerName }),
-  setPrimaryModel: (modelId: string) =>
-    invokeWithLog<string>('set_primary_model', { modelId }),
+  previewDeleteProvider: (providerName: string) =>
+    invokeWithLog<string[]>('preview_delete_provider', { providerName }),
+  setPrimaryModel: (modelId: string, force?: boolean) =>
+    invokeWithLog<string>('set_primary_model', { modelId, force: force ?? null }),
+  setModelChain: (primary: string, fallbacks: string[]) =>
+    invokeWithLog<string>('set_model_chain', { primary, fallbacks }),
   addAvailableModel: (modelId: string) =>
     invokeWithLog<string>('add_available_model', { modelId }),
   removeAvailableModel: (modelId: string) =>
     invokeWithLog<string>('remove_available_model', { modelId }),
+  checkAllProviders: (timeoutMs?: number, forceRefresh?: boolean) =>
+    invokeWithLog<ProviderHealth[]>('check_all_providers', {
+      timeoutMs: timeoutMs ?? null,
+      forceRefresh: forceRefresh ?? null,
+    }),
+  pruneConfig: () => invokeWithLog<PruneReport>('prune_config'),
+  previewPruneConfig: () => invokeWithLog<PruneReport>('preview_prune_config'),
+  setModelAvailable: (modelId: string, available: boolean) =>
+    invokeWithLog<string>('set_model_available', { modelId, available }),
 
   // Channels
   getChannelsConfig: () => invokeWithLog<ChannelConfig[]>('get_channels_config'),
   saveChannelConfig: (channel: ChannelConfig) =>
     invokeWithLog<string>('save_channel_config', { channel }),
+  clearChannelConfig: (channelId: string) =>
+    invokeWithLog<string>('clear_channel_config', { channelId }),
+  previewClearChannelConfig: (channelId: string) =>
+    invokeWithLog<string[]>('preview_clear_channel_config', { channelId }),
 
   // MCP
   getMCPConfig: () => invokeWithLog<Record<string, MCPConfig>>('get_mcp_config'),
-  saveMCPConfig: (name: string, config: MCPConfig | null) =>
-    invokeWithLog<string>('save_mcp_config', { name, config }),
-  installMCPFromGit: (url: string) =>
-    invokeWithLog<string>('install_mcp_from_git', { url }),
+  saveMCPConfig: (name: string, config: MCPConfig | null, isNew?: boolean) =>
+    invokeWithLog<string>('save_mcp_config', { name, config, isNew: isNew ?? null }),
+  setAllMCPEnabled: (enabled: boolean, restorePrevious?: boolean) =>
+    invokeWithLog<number>('set_all_mcp_enabled', { enabled, restorePrevious: restorePrevious ?? null }),
+  checkPrerequisites: () => invokeWithLog<ToolStatus[]>('check_prerequisites'),
+  installMCPFromGit: (url: string, force?: boolean, timeoutSecs?: number) =>
+    invokeWithLog<string>('install_mcp_from_git', { url, force: force ?? null, timeoutSecs: timeoutSecs ?? null }),
+  cancelInstall: (name: string) =>
+    invokeWithLog<string>('cancel_install', { name }),
+  registerExistingMCP: (name: string, command: string, args: string[], env: Record<string, string>) =>
+    invokeWithLog<string>('register_existing_mcp', { name, command, args, env }),
   uninstallMCP: (name: string) =>
     invokeWithLog<string>('uninstall_mcp', { name }),
+  previewUninstallMCP: (name: string) =>
+    invokeWithLog<string[]>('preview_uninstall_mcp', { name }),
   checkMcporterInstalled: () =>
-    invokeWithLog<boolean>('check_mcporter_installed'),
+    invokeWithLog<McporterStatus>('check_mcporter_installed'),
+  getMcporterVersion: () =>
+    invokeWithLog<string | null>('get_mcporter_version'),
   installMcporter: () =>
     invokeWithLog<string>('install_mcporter'),
   uninstallMcporter: () =>
     invokeWithLog<string>('uninstall_mcporter'),
+  listPlugins: () => invokeWithLog<PluginInfo[]>('list_plugins'),
+  installPlugin: (pkg: string) =>
+    invokeWithLog<PluginInstallResult>('install_plugin', { package: pkg }),
+  uninstallPlugin: (name: string) =>
+    invokeWithLog<string>('uninstall_plugin', { name }),
   installMCPPlugin: (url: string) =>
-    invokeWithLog<string>('install_mcp_plugin', { url }),
+    invokeWithLog<PluginInstallResult>('install_mcp_plugin', { url }),
   openclawConfigSet: (key: string, value: string) =>
-    invokeWithLog<string>('openclaw_config_set', { key, value }),
+    invokeWithLog<ConfigSetResult>('openclaw_config_set', { key, value }),
   testMCPServer: (serverType: string, target: string, command?: string, args?: string[]) =>
-    invokeWithLog<string>('test_mcp_server', { serverType, target, command: command || null, args: args || null }),
+    invokeWithLog<MCPTestResult>('test_mcp_server', { serverType, target, command: command || null, args: args || null }),
 
   // Skills
   getSkills: () => invokeWithLog<Skill[]>('get_skills'),
@@ -251,4 +468,7 @@ export const api = {
   testAIConnection: () => invokeWithLog<AITestResult>('test_ai_connection'),
   testChannel: (channelType: string) =>
     invokeWithLog<unknown>('test_channel', { channelType }),
+  setLogLevel: (level: string) => invokeWithLog<string>('set_log_level', { level }),
+  getLogLevel: () => invokeWithLog<string>('get_log_level'),
+  getRecentLogs: (lines: number) => invokeWithLog<string[]>('get_recent_logs', { lines }),
 };